@@ -178,6 +178,7 @@ fn test_chart_data_updates() {
             tot_received_bytes_prev: tot_received,
             tot_sent_packets_prev: tot_sent,
             tot_received_packets_prev: tot_received,
+            process_traffic_prev: Default::default(),
             logged_notifications: Default::default(),
             tot_emitted_notifications: 0,
         };
@@ -1,9 +1,12 @@
+use serde::{Deserialize, Serialize};
+
 use crate::translations::translations::{bytes_translation, packets_translation};
 use crate::Language;
 
 /// Enum representing the possible kind of chart displayed.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum ChartType {
+    #[default]
     Packets,
     Bytes,
 }
@@ -0,0 +1,273 @@
+//! Module containing the headless mode, in which traffic is sniffed without GUI
+//! and summarized periodically on the standard output.
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use pcap::Device;
+
+use crate::cli::CliOptions;
+use crate::configs::types::config_device::ConfigDevice;
+use crate::gui::types::status::Status;
+use crate::networking::manage_packets::{get_file_local_addresses, open_capture};
+use crate::networking::types::capture_source::CaptureSource;
+use crate::networking::types::data_info::DataInfo;
+use crate::networking::types::my_device::MyDevice;
+use crate::report::export_connections::export_connections;
+use crate::report::get_report_entries::{
+    get_app_entries, get_host_entries, get_pid_entries, get_user_entries,
+};
+use crate::secondary_threads::parse_packets::parse_packets;
+use crate::secondary_threads::refresh_socket_cache::start_socket_cache;
+use crate::utils::formatted_strings::get_formatted_bytes_string_with_b;
+use crate::{ChartType, InfoTraffic};
+
+/// Number of entries printed for each ranking of the summary
+const SUMMARY_ENTRIES: usize = 5;
+
+/// Sniffs traffic according to the command line options, printing a summary every
+/// `summary_interval` seconds until the capture ends (i.e., forever in case of live capture)
+/// or it's stopped by changing the current capture ID (e.g., when the process is interrupted);
+/// the final summary is printed and the connections table is exported in both cases
+pub fn run_headless(
+    options: &CliOptions,
+    config_device: &ConfigDevice,
+    current_capture_id: &Arc<Mutex<u16>>,
+    info_traffic: &Arc<Mutex<InfoTraffic>>,
+    status_pair: &Arc<(Mutex<Status>, Condvar)>,
+) -> Result<(), String> {
+    let capture_source =
+        CaptureSource::from_import_path(options.import_path.as_deref().unwrap_or_default());
+    let mut devices = match &options.adapter {
+        Some(name) => vec![get_device(name)?],
+        None => vec![config_device.to_my_device()],
+    };
+    // an imported file replaces all the adapters, and its traffic is classified according to
+    // the addresses of the host it was recorded on
+    if let CaptureSource::File(path) = &capture_source {
+        let local_addresses = options.local_addresses.as_deref().unwrap_or_default();
+        devices[0].set_addresses(&get_file_local_addresses(path, local_addresses));
+    } else {
+        if options.adapter.is_some() {
+            for name in &options.additional_adapters {
+                devices.push(get_device(name)?);
+            }
+        } else {
+            devices.extend(config_device.to_additional_devices());
+        }
+    }
+
+    // without the option, the BPF expression stored in the configuration is used
+    let bpf_filter = if options.filters.bpf.is_empty() {
+        &config_device.bpf_filter
+    } else {
+        &options.filters.bpf
+    };
+    let mut captures = Vec::new();
+    for (i, device) in devices.into_iter().enumerate() {
+        // the packets of the additional adapters are dumped into savefiles of their own
+        let savefile_options = if i == 0 {
+            options.savefile_options.clone()
+        } else {
+            options.savefile_options.for_device(&device.name)
+        };
+        let (cap, savefile) =
+            open_capture(&device, &capture_source, bpf_filter, &savefile_options)?;
+        captures.push((device, cap, savefile));
+    }
+
+    match &capture_source {
+        CaptureSource::Live => println!(
+            "Sniffing adapter {}...",
+            captures
+                .iter()
+                .map(|(device, _, _)| device.name.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ")
+        ),
+        CaptureSource::File(path) => println!("Importing packets from {path}..."),
+    }
+
+    // start the report writer
+    let capture_id = *current_capture_id.lock().unwrap();
+    *status_pair.0.lock().unwrap() = Status::Running;
+    status_pair.1.notify_all();
+
+    // packets read from a file can't be attributed to the local processes
+    let socket_cache =
+        (!capture_source.is_offline()).then(|| start_socket_cache(current_capture_id));
+    // each adapter is parsed by its own thread, all of them updating the same traffic data
+    let mut parse_packets_handles = Vec::new();
+    for (device, cap, savefile) in captures {
+        let current_capture_id = current_capture_id.clone();
+        let socket_cache = socket_cache.clone();
+        let capture_source = capture_source.clone();
+        let info_traffic_mutex = info_traffic.clone();
+        let filters = options.filters.clone();
+        let parse_packets_handle = thread::Builder::new()
+            .name("thread_parse_packets".to_string())
+            .spawn(move || {
+                parse_packets(
+                    &current_capture_id,
+                    &device,
+                    cap,
+                    &capture_source,
+                    &filters,
+                    savefile,
+                    socket_cache.as_ref(),
+                    &info_traffic_mutex,
+                );
+            })
+            .map_err(|err| err.to_string())?;
+        parse_packets_handles.push(parse_packets_handle);
+    }
+
+    let interval = Duration::from_secs(options.summary_interval.max(1));
+    let mut last_summary = Instant::now();
+    while *current_capture_id.lock().unwrap() == capture_id
+        && !parse_packets_handles
+            .iter()
+            .all(thread::JoinHandle::is_finished)
+    {
+        thread::sleep(Duration::from_millis(200));
+        print_capture_failures(info_traffic);
+        // the seconds of an imported file are only used to update the GUI charts
+        info_traffic.lock().unwrap().replay_intervals.clear();
+        if last_summary.elapsed() >= interval {
+            print_summary(info_traffic);
+            export(options, info_traffic)?;
+            last_summary = Instant::now();
+        }
+    }
+    print_capture_failures(info_traffic);
+    print_summary(info_traffic);
+    export(options, info_traffic)
+}
+
+/// Prints the failures occurred while capturing since the last call to the standard error
+fn print_capture_failures(info_traffic: &Arc<Mutex<InfoTraffic>>) {
+    let capture_failures = std::mem::take(&mut info_traffic.lock().unwrap().capture_failures);
+    for (device, cause) in capture_failures {
+        eprintln!("sniffnet: {device}: {cause}");
+    }
+}
+
+/// Exports the connections table, if requested by the user
+fn export(options: &CliOptions, info_traffic: &Arc<Mutex<InfoTraffic>>) -> Result<(), String> {
+    match &options.export {
+        Some((path, format)) => export_connections(info_traffic, *format, path),
+        None => Ok(()),
+    }
+}
+
+/// Returns the network adapter with the given name
+fn get_device(name: &str) -> Result<MyDevice, String> {
+    Device::list()
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .find(|device| device.name.eq(name))
+        .map(|device| MyDevice {
+            name: device.name,
+            desc: device.desc,
+            addresses: Arc::new(Mutex::new(device.addresses)),
+        })
+        .ok_or_else(|| format!("network adapter '{name}' not found"))
+}
+
+/// Prints the total amount of filtered traffic, together with the top hosts,
+/// application protocols, processes and users by exchanged bytes
+fn print_summary(info_traffic: &Arc<Mutex<InfoTraffic>>) {
+    let info_traffic_lock = info_traffic.lock().unwrap();
+    let totals = DataInfo {
+        incoming_packets: info_traffic_lock.tot_received_packets,
+        outgoing_packets: info_traffic_lock.tot_sent_packets,
+        incoming_bytes: info_traffic_lock.tot_received_bytes,
+        outgoing_bytes: info_traffic_lock.tot_sent_bytes,
+    };
+    let all_packets = info_traffic_lock.all_packets;
+    let dropped_packets = info_traffic_lock.dropped_packets;
+    drop(info_traffic_lock);
+
+    println!(
+        "\n===== Sniffnet summary - {} =====",
+        Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+    println!(
+        "Filtered traffic: {} | all packets: {all_packets} | dropped packets: {dropped_packets}",
+        get_formatted_data_info(&totals)
+    );
+
+    println!("Top hosts:");
+    for (host, data_info_host) in get_host_entries(info_traffic, ChartType::Bytes)
+        .iter()
+        .take(SUMMARY_ENTRIES)
+    {
+        let mut host_string = if host.domain.is_empty() {
+            "?".to_string()
+        } else {
+            host.domain.clone()
+        };
+        if !host.asn.name.is_empty() {
+            host_string.push_str(&format!(" - {}", host.asn.name));
+        }
+        let country = host.country.to_string();
+        if !country.is_empty() {
+            host_string.push_str(&format!(" ({country})"));
+        }
+        println!(
+            "  {host_string:<50} {}",
+            get_formatted_data_info(&data_info_host.data_info)
+        );
+    }
+
+    println!("Top applications:");
+    for (app, data_info) in get_app_entries(info_traffic, ChartType::Bytes)
+        .iter()
+        .filter(|(_, data_info)| data_info.tot_packets() > 0)
+        .take(SUMMARY_ENTRIES)
+    {
+        println!(
+            "  {:<50} {}",
+            format!("{app:?}"),
+            get_formatted_data_info(data_info)
+        );
+    }
+
+    println!("Top processes:");
+    for (pid, process_name, data_info) in get_pid_entries(info_traffic, ChartType::Bytes)
+        .iter()
+        .take(SUMMARY_ENTRIES)
+    {
+        let process = match (pid, process_name) {
+            (0, _) => "Unknown".to_string(),
+            (pid, Some(process_name)) => format!("{process_name} ({pid})"),
+            (pid, None) => pid.to_string(),
+        };
+        println!("  {process:<50} {}", get_formatted_data_info(data_info));
+    }
+
+    println!("Top users:");
+    for user_summary in get_user_entries(info_traffic, ChartType::Bytes)
+        .iter()
+        .take(SUMMARY_ENTRIES)
+    {
+        println!(
+            "  {:<50} {}, {} hosts",
+            user_summary.get_user_string(),
+            get_formatted_data_info(&user_summary.data_info),
+            user_summary.distinct_hosts
+        );
+    }
+}
+
+fn get_formatted_data_info(data_info: &DataInfo) -> String {
+    format!(
+        "{} packets, {} (in: {}, out: {})",
+        data_info.tot_packets(),
+        get_formatted_bytes_string_with_b(data_info.tot_bytes()),
+        get_formatted_bytes_string_with_b(data_info.incoming_bytes),
+        get_formatted_bytes_string_with_b(data_info.outgoing_bytes),
+    )
+}
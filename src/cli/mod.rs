@@ -1,20 +1,270 @@
+use std::path::PathBuf;
+
+use crate::gui::types::message::Message;
+use crate::networking::manage_packets::{check_bpf_syntax, parse_local_addresses};
+use crate::networking::types::filters::Filters;
+use crate::networking::types::savefile_options::SavefileOptions;
+use crate::networking::types::traffic_control::TrafficControl;
+use crate::report::types::export_format::ExportFormat;
 use crate::utils::formatted_strings::APP_VERSION;
+use crate::{AppProtocol, IpVersion, TransProtocol};
+
+pub mod headless;
+
+/// Default number of seconds between two summaries printed in headless mode
+const DEFAULT_SUMMARY_INTERVAL: u64 = 5;
+
+/// Options supplied through the command line
+pub struct CliOptions {
+    /// Sniff without launching the GUI, printing periodic summaries to stdout
+    pub headless: bool,
+    /// Name of the network adapter to be sniffed
+    pub adapter: Option<String>,
+    /// Names of the network adapters to be sniffed together with the main one
+    pub additional_adapters: Vec<String>,
+    /// Path of a pcap/pcapng file to be imported instead of sniffing an adapter
+    pub import_path: Option<String>,
+    /// Local addresses of the host the imported file was recorded on; inferred from the file if missing
+    pub local_addresses: Option<String>,
+    /// Filters to be applied to the observed traffic
+    pub filters: Filters,
+    /// Bandwidth (in kbps) of the sniffed adapter, used to throttle it
+    pub interface_bandwidth: Option<String>,
+    /// Options to dump the sniffed packets into pcap savefiles
+    pub savefile_options: SavefileOptions,
+    /// Start sniffing as soon as the GUI is opened
+    pub start: bool,
+    /// Seconds between two summaries printed in headless mode
+    pub summary_interval: u64,
+    /// File where the connections table is exported in headless mode, with its format
+    pub export: Option<(PathBuf, ExportFormat)>,
+}
+
+impl Default for CliOptions {
+    fn default() -> Self {
+        Self {
+            headless: false,
+            adapter: None,
+            additional_adapters: Vec::new(),
+            import_path: None,
+            local_addresses: None,
+            filters: Filters::default(),
+            interface_bandwidth: None,
+            savefile_options: SavefileOptions::default(),
+            start: false,
+            summary_interval: DEFAULT_SUMMARY_INTERVAL,
+            export: None,
+        }
+    }
+}
+
+impl CliOptions {
+    /// Returns the GUI messages equivalent to the supplied options,
+    /// to be processed by the application before being displayed
+    pub fn get_gui_messages(&self) -> Vec<Message> {
+        let mut messages = Vec::new();
+        // the bandwidth must be known before selecting the adapter to be throttled
+        if let Some(bandwidth) = &self.interface_bandwidth {
+            messages.push(Message::InterfaceBandwidth(bandwidth.clone()));
+        }
+        if let Some(adapter) = &self.adapter {
+            messages.push(Message::AdapterSelection(adapter.clone()));
+        }
+        for adapter in &self.additional_adapters {
+            messages.push(Message::AdditionalAdapterSelection(adapter.clone(), true));
+        }
+        messages.push(Message::IpVersionSelection(self.filters.ip));
+        messages.push(Message::TransportProtocolSelection(self.filters.transport));
+        messages.push(Message::AppProtocolSelection(self.filters.application));
+        messages.push(Message::PidFilter(self.filters.pid.clone()));
+        messages.push(Message::UidFilter(self.filters.uid.clone()));
+        messages.push(Message::PortFilter(self.filters.port.clone()));
+        // without the option, the BPF expression stored in the configuration is kept
+        if !self.filters.bpf.is_empty() {
+            messages.push(Message::BpfFilter(self.filters.bpf.clone()));
+        }
+        if let Some(import_path) = &self.import_path {
+            messages.push(Message::PcapImportPath(import_path.clone()));
+        }
+        if let Some(local_addresses) = &self.local_addresses {
+            messages.push(Message::PcapLocalAddresses(local_addresses.clone()));
+        }
+        messages.push(Message::UpdateSavefileOptions(
+            self.savefile_options.clone(),
+        ));
+        if self.start {
+            messages.push(Message::Start);
+        }
+        messages
+    }
+}
+
+/// Outcome of the command line parsing
+enum CliCommand {
+    /// Start the application with the given options
+    Run(CliOptions),
+    /// Print help and exit
+    Help,
+    /// Print version info and exit
+    Version,
+    /// Remove the traffic control settings left by a previous run and exit
+    Cleanup,
+}
 
 /// Parse CLI arguments, and exit if `--help`, `--version`, or an
-/// unknown argument was supplied
-pub fn parse_cli_args() {
-    let args = std::env::args().skip(1);
-    for arg in args {
+/// unknown or malformed argument was supplied
+pub fn parse_cli_args() -> CliOptions {
+    match parse_args(std::env::args().skip(1)) {
+        Ok(CliCommand::Run(options)) => options,
+        Ok(CliCommand::Help) => {
+            print_help();
+            std::process::exit(0);
+        }
+        Ok(CliCommand::Version) => {
+            print_version();
+            std::process::exit(0);
+        }
+        Ok(CliCommand::Cleanup) => {
+            TrafficControl::recover();
+            std::process::exit(0);
+        }
+        Err(err) => {
+            eprintln!(
+                "sniffnet: {err}\n\
+                For more information, try 'sniffnet --help'"
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<CliCommand, String> {
+    let mut options = CliOptions::default();
+    while let Some(arg) = args.next() {
         match arg.as_str() {
-            "--help" | "-h" => print_help(),
-            "--version" | "-v" => print_version(),
-            _ => {
-                unknown_argument(&arg);
-                std::process::exit(1);
+            "--help" | "-h" => return Ok(CliCommand::Help),
+            "--version" | "-v" => return Ok(CliCommand::Version),
+            "--cleanup" => return Ok(CliCommand::Cleanup),
+            "--headless" => options.headless = true,
+            "--start" | "-s" => options.start = true,
+            "--interface" | "-i" => {
+                // the first adapter is the main one, the others are sniffed together with it
+                let adapter = next_value(&arg, &mut args)?;
+                if options.adapter.is_none() {
+                    options.adapter = Some(adapter);
+                } else {
+                    options.additional_adapters.push(adapter);
+                }
+            }
+            "--import" => options.import_path = Some(next_value(&arg, &mut args)?),
+            "--local-addresses" => {
+                let addresses = next_value(&arg, &mut args)?;
+                parse_local_addresses(&addresses).map_err(|err| {
+                    format!("invalid value '{addresses}' for option '--local-addresses' ({err})")
+                })?;
+                options.local_addresses = Some(addresses);
+            }
+            "--ip" => options.filters.ip = parse_ip_version(&next_value(&arg, &mut args)?)?,
+            "--transport" => {
+                options.filters.transport = parse_transport(&next_value(&arg, &mut args)?)?;
+            }
+            "--app" => {
+                options.filters.application = parse_application(&next_value(&arg, &mut args)?)?;
+            }
+            "--pid" => options.filters.pid = parse_number::<u32>(&arg, &mut args)?,
+            "--uid" => options.filters.uid = parse_number::<u32>(&arg, &mut args)?,
+            "--port" => options.filters.port = parse_number::<u16>(&arg, &mut args)?,
+            "--bpf" => {
+                // the expression is compiled for the link type of the adapters once they're opened
+                let expression = next_value(&arg, &mut args)?;
+                check_bpf_syntax(&expression).map_err(|err| {
+                    format!("invalid value '{expression}' for option '--bpf' ({err})")
+                })?;
+                options.filters.bpf = expression;
+            }
+            "--bandwidth" => {
+                options.interface_bandwidth = Some(parse_number::<usize>(&arg, &mut args)?);
+            }
+            "--savefile" => options.savefile_options.path = next_value(&arg, &mut args)?,
+            "--snaplen" => {
+                options.savefile_options.snaplen = parse_number::<i32>(&arg, &mut args)?;
+            }
+            "--rotate-mb" => {
+                options.savefile_options.rotate_megabytes = parse_number::<u64>(&arg, &mut args)?;
+            }
+            "--rotate-secs" => {
+                options.savefile_options.rotate_seconds = parse_number::<u64>(&arg, &mut args)?;
+            }
+            "--max-files" => {
+                options.savefile_options.max_files = parse_number::<usize>(&arg, &mut args)?;
+            }
+            "--export" => {
+                let path = PathBuf::from(next_value(&arg, &mut args)?);
+                let format = ExportFormat::from_path(&path).ok_or_else(|| {
+                    format!(
+                        "invalid value '{}' for option '--export' (expected a .csv or .json file)",
+                        path.display()
+                    )
+                })?;
+                options.export = Some((path, format));
+            }
+            "--interval" => {
+                let value = parse_number::<u64>(&arg, &mut args)?;
+                options.summary_interval = value.parse().unwrap_or(DEFAULT_SUMMARY_INTERVAL);
             }
+            _ => return Err(format!("unknown option '{arg}'")),
         }
-        std::process::exit(0);
     }
+    Ok(CliCommand::Run(options))
+}
+
+/// Returns the value following the given option
+fn next_value(option: &str, args: &mut impl Iterator<Item = String>) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("option '{option}' requires a value"))
+}
+
+/// Returns the value following the given option, checking that it's a valid number
+fn parse_number<T: std::str::FromStr>(
+    option: &str,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<String, String> {
+    let value = next_value(option, args)?;
+    if value.trim().parse::<T>().is_ok() {
+        Ok(value.trim().to_string())
+    } else {
+        Err(format!("invalid value '{value}' for option '{option}'"))
+    }
+}
+
+fn parse_ip_version(value: &str) -> Result<IpVersion, String> {
+    match value.to_lowercase().as_str() {
+        "4" | "ipv4" => Ok(IpVersion::IPv4),
+        "6" | "ipv6" => Ok(IpVersion::IPv6),
+        "all" | "both" => Ok(IpVersion::Other),
+        _ => Err(format!("invalid value '{value}' for option '--ip'")),
+    }
+}
+
+fn parse_transport(value: &str) -> Result<TransProtocol, String> {
+    match value.to_lowercase().as_str() {
+        "tcp" => Ok(TransProtocol::TCP),
+        "udp" => Ok(TransProtocol::UDP),
+        "icmp" => Ok(TransProtocol::ICMP),
+        "icmpv6" => Ok(TransProtocol::ICMPv6),
+        "all" | "both" => Ok(TransProtocol::Other),
+        _ => Err(format!("invalid value '{value}' for option '--transport'")),
+    }
+}
+
+fn parse_application(value: &str) -> Result<AppProtocol, String> {
+    if value.eq_ignore_ascii_case("all") {
+        return Ok(AppProtocol::Other);
+    }
+    AppProtocol::ALL
+        .into_iter()
+        .find(|app| format!("{app:?}").eq_ignore_ascii_case(value))
+        .ok_or_else(|| format!("invalid value '{value}' for option '--app'"))
 }
 
 fn print_help() {
@@ -22,8 +272,29 @@ fn print_help() {
         "Application to comfortably monitor your Internet traffic\n\
         Usage: sniffnet [OPTIONS]\n\
         Options:\n\
-        \t-h, --help      Print help\n\
-        \t-v, --version   Print version info\n\
+        \t-h, --help              Print help\n\
+        \t-v, --version           Print version info\n\
+        \t-s, --start             Start sniffing as soon as the app is opened\n\
+        \t    --headless          Sniff without GUI, printing periodic summaries\n\
+        \t    --cleanup           Remove the throttling settings left by a crashed run\n\
+        \t-i, --interface <NAME>  Network adapter to be sniffed (repeat to sniff more adapters)\n\
+        \t    --import <FILE>     Import a pcap/pcapng file instead of sniffing an adapter\n\
+        \t    --local-addresses <IPS> Local addresses of the imported file (default: inferred)\n\
+        \t    --ip <VERSION>      IP version filter (ipv4, ipv6, all)\n\
+        \t    --transport <PROTO> Transport protocol filter (tcp, udp, icmp, icmpv6, all)\n\
+        \t    --app <PROTO>       Application protocol filter (e.g. https, dns, all)\n\
+        \t    --pid <PID>         Process ID filter\n\
+        \t    --uid <UID>         User ID filter\n\
+        \t    --port <PORT>       Port filter\n\
+        \t    --bpf <EXPR>        BPF expression compiled into the capture (e.g. 'not port 22')\n\
+        \t    --bandwidth <KBPS>  Bandwidth of the adapter, used for throttling\n\
+        \t    --savefile <FILE>   Dump the filtered packets into a pcap file\n\
+        \t    --snaplen <BYTES>   Bytes captured for each dumped packet\n\
+        \t    --rotate-mb <MB>    Start a new savefile after the given size\n\
+        \t    --rotate-secs <S>   Start a new savefile after the given time\n\
+        \t    --max-files <N>     Maximum number of savefiles retained\n\
+        \t    --export <FILE>     Export the connections table in headless mode (.csv or .json)\n\
+        \t    --interval <SECS>   Seconds between two headless summaries (default: {DEFAULT_SUMMARY_INTERVAL})\n\
         (Run without options to start the app)"
     );
 }
@@ -32,9 +303,184 @@ fn print_version() {
     println!("sniffnet {APP_VERSION}");
 }
 
-fn unknown_argument(arg: &str) {
-    eprintln!(
-        "sniffnet: unknown option '{arg}'\n\
-        For more information, try 'sniffnet --help'"
-    );
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::cli::{parse_args, CliCommand, CliOptions};
+    use crate::gui::types::message::Message;
+    use crate::report::types::export_format::ExportFormat;
+    use crate::{AppProtocol, IpVersion, TransProtocol};
+
+    fn parse(args: &[&str]) -> Result<CliCommand, String> {
+        parse_args(args.iter().map(ToString::to_string))
+    }
+
+    fn parse_options(args: &[&str]) -> CliOptions {
+        match parse(args) {
+            Ok(CliCommand::Run(options)) => options,
+            _ => panic!("expected options to be parsed"),
+        }
+    }
+
+    #[test]
+    fn test_no_arguments_starts_gui() {
+        let options = parse_options(&[]);
+        assert!(!options.headless);
+        assert_eq!(options.adapter, None);
+        assert_eq!(options.filters.ip, IpVersion::Other);
+        assert_eq!(options.summary_interval, 5);
+    }
+
+    #[test]
+    fn test_help_and_version() {
+        assert!(matches!(parse(&["--help"]), Ok(CliCommand::Help)));
+        assert!(matches!(
+            parse(&["-v", "--headless"]),
+            Ok(CliCommand::Version)
+        ));
+        assert!(matches!(
+            parse(&["--cleanup", "--start"]),
+            Ok(CliCommand::Cleanup)
+        ));
+    }
+
+    #[test]
+    fn test_headless_with_filters() {
+        let options = parse_options(&[
+            "--headless",
+            "-i",
+            "eth0",
+            "--ip",
+            "IPv6",
+            "--transport",
+            "udp",
+            "--app",
+            "dns",
+            "--pid",
+            "1234",
+            "--uid",
+            "1000",
+            "--port",
+            "53",
+            "--bpf",
+            "not net 10.0.0.0/8",
+            "--interval",
+            "10",
+        ]);
+        assert!(options.headless);
+        assert_eq!(options.adapter, Some("eth0".to_string()));
+        assert_eq!(options.filters.ip, IpVersion::IPv6);
+        assert_eq!(options.filters.transport, TransProtocol::UDP);
+        assert_eq!(options.filters.application, AppProtocol::DNS);
+        assert_eq!(options.filters.pid, "1234");
+        assert_eq!(options.filters.uid, "1000");
+        assert_eq!(options.filters.port, "53");
+        assert_eq!(options.filters.bpf, "not net 10.0.0.0/8");
+        assert_eq!(options.summary_interval, 10);
+        assert_eq!(options.export, None);
+    }
+
+    #[test]
+    fn test_import_with_local_addresses() {
+        let options = parse_options(&[
+            "--import",
+            "/tmp/capture.pcapng",
+            "--local-addresses",
+            "192.168.1.10, fe80::1",
+        ]);
+        assert_eq!(
+            options.local_addresses,
+            Some("192.168.1.10, fe80::1".to_string())
+        );
+        let messages = options.get_gui_messages();
+        assert!(messages.iter().any(|message| matches!(
+            message,
+            Message::PcapLocalAddresses(addresses) if addresses == "192.168.1.10, fe80::1"
+        )));
+        assert!(parse(&["--local-addresses", "192.168.1"]).is_err());
+    }
+
+    #[test]
+    fn test_export() {
+        let options = parse_options(&["--headless", "--export", "/tmp/connections.csv"]);
+        assert_eq!(
+            options.export,
+            Some((PathBuf::from("/tmp/connections.csv"), ExportFormat::Csv))
+        );
+        assert!(parse(&["--export", "connections.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        assert_eq!(
+            parse(&["--foo"]).err(),
+            Some("unknown option '--foo'".to_string())
+        );
+        assert_eq!(
+            parse(&["--interface"]).err(),
+            Some("option '--interface' requires a value".to_string())
+        );
+        assert_eq!(
+            parse(&["--port", "70000"]).err(),
+            Some("invalid value '70000' for option '--port'".to_string())
+        );
+        assert_eq!(
+            parse(&["--transport", "sctp"]).err(),
+            Some("invalid value 'sctp' for option '--transport'".to_string())
+        );
+        assert!(parse(&["--bpf", "port 22 and"])
+            .err()
+            .is_some_and(|err| err.starts_with("invalid value 'port 22 and' for option '--bpf'")));
+    }
+
+    #[test]
+    fn test_gui_messages() {
+        let options = parse_options(&[
+            "-i",
+            "eth0",
+            "--bandwidth",
+            "2000",
+            "--port",
+            "443",
+            "--savefile",
+            "/tmp/dump.pcap",
+            "--rotate-mb",
+            "100",
+            "--start",
+        ]);
+        assert_eq!(options.savefile_options.path, "/tmp/dump.pcap");
+        assert_eq!(options.savefile_options.rotate_megabytes, "100");
+        let messages = options.get_gui_messages();
+        assert!(matches!(&messages[0], Message::InterfaceBandwidth(kbps) if kbps == "2000"));
+        assert!(matches!(&messages[1], Message::AdapterSelection(name) if name == "eth0"));
+        assert!(messages
+            .iter()
+            .any(|message| matches!(message, Message::PortFilter(port) if port == "443")));
+        assert!(matches!(messages.last(), Some(Message::Start)));
+        // the stored BPF expression is replaced only if the option is supplied
+        assert!(!messages
+            .iter()
+            .any(|message| matches!(message, Message::BpfFilter(_))));
+
+        let messages = parse_options(&[]).get_gui_messages();
+        assert!(!messages
+            .iter()
+            .any(|message| matches!(message, Message::Start | Message::AdapterSelection(_))));
+    }
+
+    #[test]
+    fn test_multiple_adapters() {
+        let options = parse_options(&["-i", "eth0", "--interface", "wg0", "-i", "docker0"]);
+        assert_eq!(options.adapter, Some("eth0".to_string()));
+        assert_eq!(options.additional_adapters, vec!["wg0", "docker0"]);
+        let messages = options.get_gui_messages();
+        assert!(matches!(&messages[0], Message::AdapterSelection(name) if name == "eth0"));
+        assert!(
+            matches!(&messages[1], Message::AdditionalAdapterSelection(name, true) if name == "wg0")
+        );
+        assert!(
+            matches!(&messages[2], Message::AdditionalAdapterSelection(name, true) if name == "docker0")
+        );
+    }
 }
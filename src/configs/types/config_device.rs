@@ -8,15 +8,23 @@
 
 use crate::networking::types::my_device::MyDevice;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct ConfigDevice {
     pub device_name: String,
+    /// Adapters sniffed together with the main one
+    #[serde(default)]
+    pub additional_device_names: Vec<String>,
+    /// Raw BPF expression compiled into the captures
+    #[serde(default)]
+    pub bpf_filter: String,
 }
 
 impl Default for ConfigDevice {
     fn default() -> Self {
         Self {
             device_name: Device::lookup().unwrap().unwrap().name,
+            additional_device_names: Vec::new(),
+            bpf_filter: String::new(),
         }
     }
 }
@@ -39,4 +47,21 @@ pub fn to_my_device(&self) -> MyDevice {
             addresses: Arc::new(Mutex::new(standard_device.addresses)),
         }
     }
+
+    /// Returns the additional adapters which are still available
+    pub fn to_additional_devices(&self) -> Vec<MyDevice> {
+        Device::list()
+            .unwrap()
+            .into_iter()
+            .filter(|device| {
+                device.name.ne(&self.device_name)
+                    && self.additional_device_names.contains(&device.name)
+            })
+            .map(|device| MyDevice {
+                name: device.name,
+                desc: device.desc,
+                addresses: Arc::new(Mutex::new(device.addresses)),
+            })
+            .collect()
+    }
 }
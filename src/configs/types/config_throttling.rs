@@ -0,0 +1,390 @@
+//! Module defining the `ConfigThrottling` struct, which allows to save and reload
+//! the throttling rules set by the user.
+
+use std::error::Error;
+use std::fmt;
+
+use chrono::NaiveDateTime;
+use confy::ConfyError;
+use serde::{Deserialize, Serialize};
+
+use crate::configs::types::data_quota::DataQuota;
+use crate::configs::types::throttling_profile::ThrottlingProfile;
+use crate::networking::types::host_selector::HostSelector;
+use crate::networking::types::port_selector::PortSelector;
+use crate::networking::types::traffic_direction::TrafficDirection;
+
+/// Target of a persistent throttling rule
+///
+/// Processes are identified by their executable, since their PID changes across restarts.
+/// Targets are stored as a `kind` and a `value`, since TOML has no representation
+/// for enum variants carrying data.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(tag = "kind", content = "value")]
+pub enum ThrottlingRuleTarget {
+    /// Path of the executable of the processes to throttle
+    Executable(String),
+    /// Local port to throttle
+    Port(u16),
+    /// Range of local ports to throttle in a single direction, optionally restricted to
+    /// a transport protocol
+    Ports {
+        // in TOML, plain values must precede the tables
+        direction: TrafficDirection,
+        ports: PortSelector,
+    },
+    /// User ID of the user to throttle
+    User(u32),
+    /// Remote hosts to throttle
+    Host(HostSelector),
+}
+
+impl fmt::Display for ThrottlingRuleTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThrottlingRuleTarget::Executable(executable) => write!(f, "Executable {executable}"),
+            ThrottlingRuleTarget::Port(port) => write!(f, "Port {port}"),
+            ThrottlingRuleTarget::Ports { ports, direction } => {
+                let direction = match direction {
+                    TrafficDirection::Incoming => "ingress",
+                    TrafficDirection::Outgoing => "egress",
+                };
+                write!(f, "Ports {ports} ({direction})")
+            }
+            ThrottlingRuleTarget::User(uid) => write!(f, "User {uid}"),
+            ThrottlingRuleTarget::Host(selector) => write!(f, "Host {selector}"),
+        }
+    }
+}
+
+/// Persistent throttling rule
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ThrottlingRule {
+    /// Maximum bandwidth allowed, in kilobytes per second
+    pub kbps: u32,
+    // in TOML, plain values must precede the tables
+    pub target: ThrottlingRuleTarget,
+}
+
+/// Saved throttling configuration
+///
+/// Empty lists are omitted, since in TOML they would be plain values following the tables
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Eq, Debug)]
+pub struct ConfigThrottling {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<ThrottlingRule>,
+    /// Profiles activated on a schedule; the first one scheduled at a given time is active
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<ThrottlingProfile>,
+    /// Data quotas, whose targets are throttled once exceeded; their usage is saved as well
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quotas: Vec<DataQuota>,
+}
+
+impl ConfigThrottling {
+    /// Loads the saved configuration, creating the default one if it doesn't exist yet;
+    /// a file that can't be read is left untouched, and the cause is returned
+    pub fn load() -> Result<Self, String> {
+        confy::load("sniffnet", "throttling").map_err(|err| get_confy_error_cause(&err))
+    }
+
+    /// Saves the configuration, returning the cause of the failure if it couldn't be written
+    pub fn store(&self) -> Result<(), String> {
+        confy::store("sniffnet", "throttling", self).map_err(|err| get_confy_error_cause(&err))
+    }
+
+    /// Adds or updates the rule for the given target, or removes it if the bandwidth
+    /// is not specified
+    pub fn set_rule(&mut self, target: ThrottlingRuleTarget, kbps: Option<u32>) {
+        let index = self.rules.iter().position(|rule| rule.target.eq(&target));
+        match (index, kbps) {
+            (Some(index), Some(kbps)) => self.rules[index].kbps = kbps,
+            (Some(index), None) => {
+                self.rules.remove(index);
+            }
+            (None, Some(kbps)) => self.rules.push(ThrottlingRule { target, kbps }),
+            (None, None) => {}
+        }
+    }
+
+    /// Returns the profile with the given name, if any
+    pub fn get_profile(&self, name: &str) -> Option<&ThrottlingProfile> {
+        self.profiles.iter().find(|profile| profile.name.eq(name))
+    }
+
+    /// Returns the first profile scheduled at the given local time, if any
+    pub fn get_scheduled_profile(&self, now: NaiveDateTime) -> Option<&ThrottlingProfile> {
+        self.profiles
+            .iter()
+            .find(|profile| profile.schedule.is_active(now))
+    }
+
+    /// Returns the rules in force while the given profile is active: the rules of the profile,
+    /// followed by the rules set by the user for the targets the profile doesn't limit
+    pub fn get_effective_rules(&self, profile: Option<&str>) -> Vec<ThrottlingRule> {
+        let mut rules = profile
+            .and_then(|name| self.get_profile(name))
+            .map(|profile| profile.rules.clone())
+            .unwrap_or_default();
+        for rule in &self.rules {
+            if !rules.iter().any(|other| other.target.eq(&rule.target)) {
+                rules.push(rule.clone());
+            }
+        }
+        rules
+    }
+
+    /// Returns the ingress bandwidth of the interface in force while the given profile is active:
+    /// the one of the profile, if specified, or the one set by the user
+    pub fn get_interface_kbps(
+        &self,
+        profile: Option<&str>,
+        user_kbps: Option<usize>,
+    ) -> Option<usize> {
+        profile
+            .and_then(|name| self.get_profile(name))
+            .and_then(|profile| profile.interface_kbps)
+            .map(|kbps| kbps.try_into().unwrap())
+            .or(user_kbps)
+    }
+
+    /// Returns the rules in force while the given profile is active, including the ones of
+    /// the exceeded quotas; the lowest bandwidth is applied to targets limited more than once
+    pub fn get_rules_in_force(&self, profile: Option<&str>) -> Vec<ThrottlingRule> {
+        let mut rules = self.get_effective_rules(profile);
+        for quota in self.quotas.iter().filter(|quota| quota.usage.exceeded) {
+            let quota_rule = quota.get_rule();
+            match rules
+                .iter_mut()
+                .find(|rule| rule.target.eq(&quota_rule.target))
+            {
+                Some(rule) => rule.kbps = rule.kbps.min(quota_rule.kbps),
+                None => rules.push(quota_rule),
+            }
+        }
+        rules
+    }
+
+    /// Forgets the totals observed by the quotas, before a new capture starts
+    pub fn reset_quota_totals(&mut self) {
+        for quota in &mut self.quotas {
+            quota.last_total = None;
+        }
+    }
+}
+
+/// Returns the bandwidth allowed by the given rules to the processes running an executable,
+/// if limited
+pub fn get_executable_kbps(rules: &[ThrottlingRule], executable: &str) -> Option<u32> {
+    rules.iter().find_map(|rule| match &rule.target {
+        ThrottlingRuleTarget::Executable(path) if path.eq(executable) => Some(rule.kbps),
+        _ => None,
+    })
+}
+
+/// Describes an error occurred while loading or storing a configuration file,
+/// including its source (e.g., the TOML error)
+fn get_confy_error_cause(error: &ConfyError) -> String {
+    match error.source() {
+        Some(source) => format!("{error}: {source}"),
+        None => error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime, Weekday};
+
+    use crate::configs::types::config_throttling::{
+        get_executable_kbps, ConfigThrottling, ThrottlingRule, ThrottlingRuleTarget,
+    };
+    use crate::configs::types::data_quota::{DataQuota, QuotaPeriod, QuotaTarget, QuotaUsage};
+    use crate::configs::types::throttling_profile::{ThrottlingProfile, ThrottlingSchedule};
+    use crate::networking::types::host_selector::HostSelector;
+    use crate::networking::types::ip_subnet::IpSubnet;
+    use crate::networking::types::port_selector::PortSelector;
+    use crate::networking::types::traffic_direction::TrafficDirection;
+    use crate::TransProtocol;
+
+    #[test]
+    fn test_set_throttling_rule() {
+        let mut config = ConfigThrottling::default();
+        let curl = ThrottlingRuleTarget::Executable("/usr/bin/curl".to_string());
+        config.set_rule(curl.clone(), Some(100));
+        config.set_rule(ThrottlingRuleTarget::Port(8080), Some(50));
+        config.set_rule(ThrottlingRuleTarget::User(1000), None);
+        assert_eq!(config.rules.len(), 2);
+        let rules = config.get_effective_rules(None);
+        assert_eq!(get_executable_kbps(&rules, "/usr/bin/curl"), Some(100));
+        assert_eq!(get_executable_kbps(&rules, "/usr/bin/wget"), None);
+
+        // updating a rule doesn't change its position
+        config.set_rule(curl.clone(), Some(200));
+        assert_eq!(
+            config.rules[0],
+            ThrottlingRule {
+                target: curl.clone(),
+                kbps: 200
+            }
+        );
+
+        config.set_rule(curl, None);
+        assert_eq!(
+            config.rules,
+            vec![ThrottlingRule {
+                target: ThrottlingRuleTarget::Port(8080),
+                kbps: 50
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rules_survive_a_toml_round_trip() {
+        let mut config = ConfigThrottling::default();
+        let targets = [
+            ThrottlingRuleTarget::Executable("/usr/bin/curl".to_string()),
+            ThrottlingRuleTarget::Port(8080),
+            ThrottlingRuleTarget::Ports {
+                direction: TrafficDirection::Incoming,
+                ports: PortSelector::new(6881, 6889, TransProtocol::UDP).unwrap(),
+            },
+            ThrottlingRuleTarget::User(1000),
+            ThrottlingRuleTarget::Host(HostSelector::Subnet(
+                IpSubnet::new("10.0.0.0".parse().unwrap(), 8).unwrap(),
+            )),
+            ThrottlingRuleTarget::Host(HostSelector::Domain("example.com".to_string())),
+            ThrottlingRuleTarget::Host(HostSelector::Asn(15169)),
+        ];
+        for (kbps, target) in (100..).zip(targets) {
+            config.set_rule(target, Some(kbps));
+        }
+
+        let stored = toml::to_string(&config).unwrap();
+        assert_eq!(toml::from_str::<ConfigThrottling>(&stored).unwrap(), config);
+    }
+
+    #[test]
+    fn test_profile_rules_take_precedence() {
+        let backup = ThrottlingRuleTarget::Executable("/usr/bin/rsync".to_string());
+        let mut config = ConfigThrottling::default();
+        config.set_rule(backup.clone(), Some(1000));
+        config.set_rule(ThrottlingRuleTarget::User(1000), Some(300));
+        config.profiles.push(ThrottlingProfile {
+            name: "work hours".to_string(),
+            schedule: ThrottlingSchedule {
+                weekdays: vec![Weekday::Mon],
+                start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+            },
+            interface_kbps: Some(5000),
+            rules: vec![ThrottlingRule {
+                target: backup,
+                kbps: 50,
+            }],
+        });
+
+        // the 2nd of October 2023 was a Monday
+        let monday = NaiveDate::from_ymd_opt(2023, 10, 2).unwrap();
+        let profile = config
+            .get_scheduled_profile(monday.and_hms_opt(10, 0, 0).unwrap())
+            .map(|profile| profile.name.as_str());
+        assert_eq!(profile, Some("work hours"));
+        assert_eq!(
+            config.get_scheduled_profile(monday.and_hms_opt(20, 0, 0).unwrap()),
+            None
+        );
+
+        assert_eq!(
+            get_executable_kbps(&config.get_effective_rules(profile), "/usr/bin/rsync"),
+            Some(50)
+        );
+        assert_eq!(
+            get_executable_kbps(&config.get_effective_rules(None), "/usr/bin/rsync"),
+            Some(1000)
+        );
+        assert_eq!(config.get_effective_rules(profile).len(), 2);
+        assert_eq!(
+            config.get_effective_rules(Some("night")),
+            config.get_effective_rules(None)
+        );
+    }
+
+    #[test]
+    fn test_scheduled_throttling_profile() {
+        let mut config = ConfigThrottling::default();
+        config.set_rule(ThrottlingRuleTarget::User(1000), Some(300));
+        config.profiles.push(ThrottlingProfile {
+            name: "night".to_string(),
+            interface_kbps: Some(10000),
+            schedule: ThrottlingSchedule {
+                weekdays: vec![],
+                start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+            },
+            rules: vec![ThrottlingRule {
+                kbps: 50,
+                target: ThrottlingRuleTarget::User(1000),
+            }],
+        });
+        let day = NaiveDate::from_ymd_opt(2023, 10, 2).unwrap();
+
+        let profile = config
+            .get_scheduled_profile(day.and_hms_opt(23, 0, 0).unwrap())
+            .map(|profile| profile.name.as_str());
+        assert_eq!(profile, Some("night"));
+        assert_eq!(config.get_interface_kbps(profile, Some(500)), Some(10000));
+        assert_eq!(config.get_rules_in_force(profile)[0].kbps, 50);
+
+        let profile = config
+            .get_scheduled_profile(day.and_hms_opt(8, 0, 0).unwrap())
+            .map(|profile| profile.name.as_str());
+        assert_eq!(profile, None);
+        assert_eq!(config.get_interface_kbps(profile, Some(500)), Some(500));
+        assert_eq!(config.get_interface_kbps(profile, None), None);
+        assert_eq!(config.get_rules_in_force(profile)[0].kbps, 300);
+    }
+
+    #[test]
+    fn test_exceeded_quotas_are_in_force() {
+        let mut config = ConfigThrottling::default();
+        config.set_rule(ThrottlingRuleTarget::User(1000), Some(300));
+        config.set_rule(ThrottlingRuleTarget::Port(8080), Some(10));
+        let quota = |target: QuotaTarget, exceeded: bool| DataQuota {
+            target,
+            limit_bytes: 2_000_000_000,
+            period: QuotaPeriod::Daily,
+            kbps: 50,
+            usage: QuotaUsage {
+                exceeded,
+                ..QuotaUsage::default()
+            },
+            last_total: None,
+        };
+        config.quotas = vec![
+            quota(QuotaTarget::User(1000), true),
+            quota(QuotaTarget::Port(8080), true),
+            quota(QuotaTarget::Executable("/usr/bin/curl".to_string()), true),
+            quota(QuotaTarget::Executable("/usr/bin/wget".to_string()), false),
+        ];
+
+        let rules = config.get_rules_in_force(None);
+        assert_eq!(
+            rules,
+            vec![
+                ThrottlingRule {
+                    target: ThrottlingRuleTarget::User(1000),
+                    kbps: 50
+                },
+                ThrottlingRule {
+                    target: ThrottlingRuleTarget::Port(8080),
+                    kbps: 10
+                },
+                ThrottlingRule {
+                    target: ThrottlingRuleTarget::Executable("/usr/bin/curl".to_string()),
+                    kbps: 50
+                },
+            ]
+        );
+        assert_eq!(get_executable_kbps(&rules, "/usr/bin/wget"), None);
+    }
+}
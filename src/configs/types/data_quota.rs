@@ -0,0 +1,583 @@
+//! Module defining the `DataQuota` struct, which limits the amount of data exchanged
+//! by a target in a period, throttling it once the quota is exceeded.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{Datelike, Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::configs::types::config_throttling::{ThrottlingRule, ThrottlingRuleTarget};
+use crate::networking::types::address_port_pair::AddressPortPair;
+use crate::networking::types::byte_multiple::{from_char_to_multiple, ByteMultiple};
+use crate::networking::types::info_address_port_pair::InfoAddressPortPair;
+use crate::networking::types::process_info::ProcessInfo;
+use crate::networking::types::traffic_direction::TrafficDirection;
+use crate::InfoTraffic;
+
+/// Target whose traffic is accounted for in a quota
+///
+/// Processes are identified by their executable, since their PID changes across restarts.
+/// Targets are stored as a `kind` and a `value`, as done for the targets of the throttling rules.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(tag = "kind", content = "value")]
+pub enum QuotaTarget {
+    /// Path of the executable of the processes
+    Executable(String),
+    /// User ID of the user
+    User(u32),
+    /// Local port
+    Port(u16),
+}
+
+impl QuotaTarget {
+    /// Returns the target of the throttling rule applied when the quota is exceeded
+    pub fn get_rule_target(&self) -> ThrottlingRuleTarget {
+        match self {
+            QuotaTarget::Executable(executable) => {
+                ThrottlingRuleTarget::Executable(executable.clone())
+            }
+            QuotaTarget::User(uid) => ThrottlingRuleTarget::User(*uid),
+            QuotaTarget::Port(port) => ThrottlingRuleTarget::Port(*port),
+        }
+    }
+
+    /// Returns the bytes exchanged by the target since the capture started
+    pub fn get_transmitted_bytes(&self, info_traffic: &InfoTraffic) -> u128 {
+        info_traffic
+            .map
+            .iter()
+            .filter(|(key, val)| self.matches(key, val, &info_traffic.processes))
+            .map(|(_, val)| val.transmitted_bytes)
+            .sum()
+    }
+
+    /// Returns true if the connection belongs to the target, false otherwise
+    fn matches(
+        &self,
+        key: &AddressPortPair,
+        val: &InfoAddressPortPair,
+        processes: &HashMap<u32, ProcessInfo>,
+    ) -> bool {
+        match self {
+            QuotaTarget::Executable(executable) => val.pids.as_ref().is_some_and(|pids| {
+                pids.iter().any(|pid| {
+                    processes
+                        .get(pid)
+                        .is_some_and(|process| process.exe.eq(executable))
+                })
+            }),
+            QuotaTarget::User(uid) => val.uid.eq(&Some(*uid)),
+            QuotaTarget::Port(port) => key.get_ports().is_some_and(|(port1, port2)| {
+                let local_port = match val.traffic_direction {
+                    TrafficDirection::Outgoing => port1,
+                    TrafficDirection::Incoming => port2,
+                };
+                local_port.eq(port)
+            }),
+        }
+    }
+}
+
+impl fmt::Display for QuotaTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaTarget::Executable(executable) => write!(f, "Executable {executable}"),
+            QuotaTarget::User(uid) => write!(f, "User {uid}"),
+            QuotaTarget::Port(port) => write!(f, "Port {port}"),
+        }
+    }
+}
+
+/// Kind of target of a quota, as selected in the GUI
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum QuotaTargetKind {
+    #[default]
+    Executable,
+    User,
+    Port,
+}
+
+impl QuotaTargetKind {
+    pub(crate) const ALL: [QuotaTargetKind; 3] = [
+        QuotaTargetKind::Executable,
+        QuotaTargetKind::User,
+        QuotaTargetKind::Port,
+    ];
+}
+
+/// Period after which the usage of a quota is reset
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum QuotaPeriod {
+    /// Reset at midnight
+    #[default]
+    Daily,
+    /// Reset at midnight between Sunday and Monday
+    Weekly,
+    /// Reset at midnight of the first day of the month
+    Monthly,
+}
+
+impl QuotaPeriod {
+    pub(crate) const ALL: [QuotaPeriod; 3] = [
+        QuotaPeriod::Daily,
+        QuotaPeriod::Weekly,
+        QuotaPeriod::Monthly,
+    ];
+
+    /// Returns the start of the period containing the given local time
+    pub fn get_start(self, now: NaiveDateTime) -> NaiveDateTime {
+        let today = now.date();
+        let first_day = match self {
+            QuotaPeriod::Daily => today,
+            QuotaPeriod::Weekly => {
+                today - Duration::days(today.weekday().num_days_from_monday().into())
+            }
+            QuotaPeriod::Monthly => today.with_day(1).unwrap(),
+        };
+        first_day.and_hms_opt(0, 0, 0).unwrap()
+    }
+}
+
+impl fmt::Display for QuotaPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let period = match self {
+            QuotaPeriod::Daily => "day",
+            QuotaPeriod::Weekly => "week",
+            QuotaPeriod::Monthly => "month",
+        };
+        write!(f, "{period}")
+    }
+}
+
+/// Data exchanged by the target of a quota in the current period
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Eq, Debug)]
+pub struct QuotaUsage {
+    pub bytes: u64,
+    /// Start of the period in which the bytes were exchanged
+    pub period_start: Option<NaiveDateTime>,
+    /// Whether the quota has been exceeded, and its target is throttled
+    pub exceeded: bool,
+}
+
+/// Change in the state of a quota
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum QuotaTransition {
+    /// The quota has been exceeded, so its target must be throttled
+    Exceeded,
+    /// A new period started after the quota was exceeded, so its target must be released
+    Reset,
+}
+
+/// Maximum amount of data a target can exchange in a period before being throttled
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct DataQuota {
+    /// Maximum amount of bytes exchanged in a period
+    pub limit_bytes: u64,
+    pub period: QuotaPeriod,
+    /// Bandwidth allowed once the quota is exceeded, in kilobytes per second
+    pub kbps: u32,
+    // in TOML, plain values must precede the tables
+    pub target: QuotaTarget,
+    #[serde(default)]
+    pub usage: QuotaUsage,
+    /// Bytes exchanged by the target in the current capture at the last update
+    #[serde(skip)]
+    pub last_total: Option<u128>,
+}
+
+impl DataQuota {
+    /// Accounts for the bytes exchanged by the target since the last update, given the total
+    /// exchanged in the current capture, starting a new period if needed
+    pub fn update(&mut self, total: u128, now: NaiveDateTime) -> Option<QuotaTransition> {
+        let was_exceeded = self.usage.exceeded;
+        let period_start = self.period.get_start(now);
+        if self.usage.period_start.ne(&Some(period_start)) {
+            self.usage = QuotaUsage {
+                bytes: 0,
+                period_start: Some(period_start),
+                exceeded: false,
+            };
+        }
+
+        // a lower total means that a new capture started in the meantime
+        let delta = match self.last_total {
+            Some(last_total) if last_total <= total => total - last_total,
+            _ => total,
+        };
+        self.last_total = Some(total);
+        self.usage.bytes = self
+            .usage
+            .bytes
+            .saturating_add(delta.try_into().unwrap_or(u64::MAX));
+        if self.usage.bytes > self.limit_bytes {
+            self.usage.exceeded = true;
+        }
+
+        match (was_exceeded, self.usage.exceeded) {
+            (false, true) => Some(QuotaTransition::Exceeded),
+            (true, false) => Some(QuotaTransition::Reset),
+            _ => None,
+        }
+    }
+
+    /// Returns the throttling rule applied while the quota is exceeded
+    pub fn get_rule(&self) -> ThrottlingRule {
+        ThrottlingRule {
+            target: self.target.get_rule_target(),
+            kbps: self.kbps,
+        }
+    }
+}
+
+/// Outcome of the update of the quotas usage
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct QuotasUpdate {
+    /// Quotas exceeded in this update, whose targets must be throttled
+    pub exceeded: Vec<DataQuota>,
+    /// Whether a quota has been exceeded or reset, changing the rules in force
+    pub changed: bool,
+    /// Whether the usage of a quota changed
+    pub usage_changed: bool,
+}
+
+/// Accounts for the data exchanged by the targets of the quotas at the given local time,
+/// given the traffic of the current capture
+pub fn update_quotas(
+    quotas: &mut [DataQuota],
+    info_traffic: &InfoTraffic,
+    now: NaiveDateTime,
+) -> QuotasUpdate {
+    let mut update = QuotasUpdate::default();
+    for quota in quotas {
+        let total = quota.target.get_transmitted_bytes(info_traffic);
+        let previous_bytes = quota.usage.bytes;
+        let transition = quota.update(total, now);
+        update.usage_changed |= quota.usage.bytes.ne(&previous_bytes);
+        match transition {
+            Some(QuotaTransition::Exceeded) => update.exceeded.push(quota.clone()),
+            Some(QuotaTransition::Reset) => {}
+            None => continue,
+        }
+        update.changed = true;
+    }
+    update
+}
+
+/// Data quota being created or edited in the GUI, with the fields typed by the user
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct DataQuotaDraft {
+    /// Position of the quota being edited, if it already exists
+    pub index: Option<usize>,
+    pub target_kind: QuotaTargetKind,
+    /// Executable path, user ID or port, depending on the kind of target
+    pub target: String,
+    /// Maximum amount of data exchanged in a period, optionally followed
+    /// by a K, M or G multiple (e.g. `500M`)
+    pub limit: String,
+    pub period: QuotaPeriod,
+    /// Bandwidth allowed once the quota is exceeded, in kilobytes per second
+    pub kbps: String,
+}
+
+impl DataQuotaDraft {
+    /// Returns the draft to edit the quota at the given position
+    pub fn from_quota(index: usize, quota: &DataQuota) -> Self {
+        let (target_kind, target) = match &quota.target {
+            QuotaTarget::Executable(executable) => {
+                (QuotaTargetKind::Executable, executable.clone())
+            }
+            QuotaTarget::User(uid) => (QuotaTargetKind::User, uid.to_string()),
+            QuotaTarget::Port(port) => (QuotaTargetKind::Port, port.to_string()),
+        };
+        // the largest multiple representing the limit exactly is used
+        let multiple = [ByteMultiple::GB, ByteMultiple::MB, ByteMultiple::KB]
+            .into_iter()
+            .find(|multiple| quota.limit_bytes.is_multiple_of(multiple.get_multiplier()))
+            .unwrap_or(ByteMultiple::B);
+        Self {
+            index: Some(index),
+            target_kind,
+            target,
+            limit: format!(
+                "{}{}",
+                quota.limit_bytes / multiple.get_multiplier(),
+                multiple.get_char()
+            ),
+            period: quota.period,
+            kbps: quota.kbps.to_string(),
+        }
+    }
+
+    /// Returns the quota described by the draft, if its fields are valid and its target
+    /// isn't limited by the other quotas; the usage is kept when editing a quota
+    /// without changing its target
+    pub fn to_quota(&self, quotas: &[DataQuota]) -> Option<DataQuota> {
+        let target = self.target.trim();
+        let target = match self.target_kind {
+            QuotaTargetKind::Executable if !target.is_empty() => {
+                QuotaTarget::Executable(target.to_string())
+            }
+            QuotaTargetKind::Executable => return None,
+            QuotaTargetKind::User => QuotaTarget::User(target.parse().ok()?),
+            QuotaTargetKind::Port => QuotaTarget::Port(target.parse().ok()?),
+        };
+        let target_taken = quotas
+            .iter()
+            .enumerate()
+            .any(|(i, quota)| quota.target.eq(&target) && self.index.ne(&Some(i)));
+        if target_taken {
+            return None;
+        }
+        let limit_bytes = parse_limit(&self.limit)?;
+        let kbps = self
+            .kbps
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|kbps| *kbps > 0)?;
+
+        let (mut usage, last_total) = match self.index.and_then(|i| quotas.get(i)) {
+            Some(quota) if quota.target.eq(&target) => (quota.usage.clone(), quota.last_total),
+            _ => (QuotaUsage::default(), None),
+        };
+        usage.exceeded = usage.period_start.is_some() && usage.bytes > limit_bytes;
+        Some(DataQuota {
+            target,
+            limit_bytes,
+            period: self.period,
+            kbps,
+            usage,
+            last_total,
+        })
+    }
+}
+
+/// Parses an amount of bytes greater than zero, optionally followed by a K, M or G multiple
+fn parse_limit(limit: &str) -> Option<u64> {
+    let limit = limit.trim();
+    let (value, multiple) = match limit.chars().last() {
+        Some(ch) if "KMGkmg".contains(ch) => (&limit[..limit.len() - 1], from_char_to_multiple(ch)),
+        _ => (limit, ByteMultiple::B),
+    };
+    value
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|value| *value > 0)?
+        .checked_mul(multiple.get_multiplier())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use serde::{Deserialize, Serialize};
+
+    use crate::configs::types::data_quota::{
+        update_quotas, DataQuota, DataQuotaDraft, QuotaPeriod, QuotaTarget, QuotaTargetKind,
+        QuotaTransition, QuotaUsage,
+    };
+    use crate::networking::types::address_port_pair::AddressPortPair;
+    use crate::networking::types::info_address_port_pair::InfoAddressPortPair;
+    use crate::{InfoTraffic, TransProtocol};
+
+    /// TOML documents are tables, so the quotas are stored in an array
+    #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+    struct QuotasFile {
+        quotas: Vec<DataQuota>,
+    }
+
+    fn at(month: u32, day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, month, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_quota_periods() {
+        // the 4th of October 2023 was a Wednesday
+        let now = at(10, 4, 15);
+        assert_eq!(QuotaPeriod::Daily.get_start(now), at(10, 4, 0));
+        assert_eq!(QuotaPeriod::Weekly.get_start(now), at(10, 2, 0));
+        assert_eq!(QuotaPeriod::Monthly.get_start(now), at(10, 1, 0));
+        assert_eq!(QuotaPeriod::Weekly.get_start(at(10, 1, 23)), at(9, 25, 0));
+    }
+
+    #[test]
+    fn test_quota_exceeded_and_reset() {
+        let mut quota = DataQuota {
+            target: QuotaTarget::Port(8080),
+            limit_bytes: 1000,
+            period: QuotaPeriod::Daily,
+            kbps: 10,
+            usage: QuotaUsage::default(),
+            last_total: None,
+        };
+        assert_eq!(quota.update(600, at(10, 4, 10)), None);
+        assert_eq!(quota.update(1000, at(10, 4, 11)), None);
+        assert_eq!(quota.usage.bytes, 1000);
+        assert_eq!(
+            quota.update(1001, at(10, 4, 12)),
+            Some(QuotaTransition::Exceeded)
+        );
+        assert_eq!(quota.update(5000, at(10, 4, 13)), None);
+        assert!(quota.usage.exceeded);
+
+        // only the bytes exchanged after midnight are accounted for in the new period
+        assert_eq!(
+            quota.update(5200, at(10, 5, 0)),
+            Some(QuotaTransition::Reset)
+        );
+        assert_eq!(quota.usage.bytes, 200);
+        assert_eq!(quota.usage.period_start, Some(at(10, 5, 0)));
+
+        // a new capture restarts the total from zero
+        assert_eq!(quota.update(300, at(10, 5, 1)), None);
+        assert_eq!(quota.usage.bytes, 500);
+    }
+
+    #[test]
+    fn test_usage_survives_restarts() {
+        let mut quota = DataQuota {
+            target: QuotaTarget::User(1000),
+            limit_bytes: 1000,
+            period: QuotaPeriod::Monthly,
+            kbps: 10,
+            usage: QuotaUsage {
+                bytes: 900,
+                period_start: Some(at(10, 1, 0)),
+                exceeded: false,
+            },
+            last_total: None,
+        };
+        assert_eq!(
+            quota.update(200, at(10, 20, 9)),
+            Some(QuotaTransition::Exceeded)
+        );
+        assert_eq!(quota.usage.bytes, 1100);
+    }
+
+    #[test]
+    fn test_quotas_survive_a_toml_round_trip() {
+        let quota = |target: QuotaTarget, usage: QuotaUsage| DataQuota {
+            limit_bytes: 500_000_000,
+            period: QuotaPeriod::Weekly,
+            kbps: 20,
+            target,
+            usage,
+            last_total: None,
+        };
+        let quotas = QuotasFile {
+            quotas: vec![
+                quota(
+                    QuotaTarget::Executable("/usr/bin/curl".to_string()),
+                    QuotaUsage {
+                        bytes: 600_000_000,
+                        period_start: Some(at(10, 2, 0)),
+                        exceeded: true,
+                    },
+                ),
+                quota(QuotaTarget::User(1000), QuotaUsage::default()),
+                quota(QuotaTarget::Port(8080), QuotaUsage::default()),
+            ],
+        };
+
+        let stored = toml::to_string(&quotas).unwrap();
+        assert_eq!(toml::from_str::<QuotasFile>(&stored).unwrap(), quotas);
+    }
+
+    #[test]
+    fn test_update_quotas() {
+        let mut quotas = vec![DataQuota {
+            limit_bytes: 1000,
+            period: QuotaPeriod::Daily,
+            kbps: 10,
+            target: QuotaTarget::User(1000),
+            usage: QuotaUsage::default(),
+            last_total: None,
+        }];
+        let set_transmitted_bytes = |transmitted_bytes: u128| {
+            let mut info_traffic = InfoTraffic::new();
+            info_traffic.map.insert(
+                AddressPortPair::new(
+                    "192.168.1.2".to_string(),
+                    40000,
+                    "93.184.216.34".to_string(),
+                    443,
+                    TransProtocol::TCP,
+                    "eth0".to_string(),
+                ),
+                InfoAddressPortPair {
+                    transmitted_bytes,
+                    uid: Some(1000),
+                    ..InfoAddressPortPair::default()
+                },
+            );
+            info_traffic
+        };
+
+        let update = update_quotas(&mut quotas, &set_transmitted_bytes(800), at(10, 2, 10));
+        assert!(update.exceeded.is_empty());
+        assert!(!update.changed);
+        assert!(update.usage_changed);
+        assert_eq!(quotas[0].usage.bytes, 800);
+
+        let update = update_quotas(&mut quotas, &set_transmitted_bytes(1200), at(10, 2, 11));
+        assert_eq!(update.exceeded, quotas);
+        assert!(update.changed);
+        assert!(quotas[0].usage.exceeded);
+
+        // nothing changes until the next day, when the quota is reset
+        let update = update_quotas(&mut quotas, &set_transmitted_bytes(1200), at(10, 2, 12));
+        assert!(!update.changed && !update.usage_changed);
+        let update = update_quotas(&mut quotas, &set_transmitted_bytes(1200), at(10, 3, 0));
+        assert!(update.exceeded.is_empty());
+        assert!(update.changed);
+        assert!(!quotas[0].usage.exceeded);
+    }
+
+    #[test]
+    fn test_quota_draft() {
+        let mut draft = DataQuotaDraft {
+            target_kind: QuotaTargetKind::Port,
+            target: "8080".to_string(),
+            limit: "500M".to_string(),
+            period: QuotaPeriod::Weekly,
+            kbps: "20".to_string(),
+            ..DataQuotaDraft::default()
+        };
+        let quota = draft.to_quota(&[]).unwrap();
+        assert_eq!(quota.target, QuotaTarget::Port(8080));
+        assert_eq!(quota.limit_bytes, 500_000_000);
+        assert_eq!(quota.period, QuotaPeriod::Weekly);
+        assert_eq!(quota.kbps, 20);
+
+        // the target of a new quota can't be limited twice
+        assert_eq!(draft.to_quota(std::slice::from_ref(&quota)), None);
+        for (limit, valid) in [("1234", true), ("2g", true), ("0K", false), ("5T", false)] {
+            draft.limit = limit.to_string();
+            assert_eq!(draft.to_quota(&[]).is_some(), valid);
+        }
+        draft.target = "not-a-port".to_string();
+        assert_eq!(draft.to_quota(&[]), None);
+
+        // editing a quota keeps its usage, which may exceed the new limit
+        let mut used = quota.clone();
+        used.usage = QuotaUsage {
+            bytes: 300_000_000,
+            period_start: Some(at(10, 2, 0)),
+            exceeded: false,
+        };
+        let mut draft = DataQuotaDraft::from_quota(0, &used);
+        assert_eq!(draft.limit, "500M");
+        draft.limit = "200M".to_string();
+        let edited = draft.to_quota(std::slice::from_ref(&used)).unwrap();
+        assert_eq!(edited.usage.bytes, 300_000_000);
+        assert!(edited.usage.exceeded);
+        draft.target_kind = QuotaTargetKind::User;
+        draft.target = "1000".to_string();
+        let edited = draft.to_quota(std::slice::from_ref(&used)).unwrap();
+        assert_eq!(edited.usage, QuotaUsage::default());
+    }
+}
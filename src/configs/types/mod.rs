@@ -1,2 +1,5 @@
 pub mod config_device;
 pub mod config_settings;
+pub mod config_throttling;
+pub mod data_quota;
+pub mod throttling_profile;
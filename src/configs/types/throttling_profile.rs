@@ -0,0 +1,297 @@
+//! Module defining the `ThrottlingProfile` struct, which groups throttling limits
+//! to be applied automatically on a weekly schedule.
+
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::configs::types::config_throttling::ThrottlingRule;
+
+/// Time window, repeated on the given days of the week, in which a profile is active
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ThrottlingSchedule {
+    /// Days of the week in which the window starts; every day if empty
+    pub weekdays: Vec<Weekday>,
+    /// Time of the day at which the window starts (e.g. "09:00:00")
+    pub start: NaiveTime,
+    /// Time of the day at which the window ends; if it's not after the start,
+    /// the window ends on the following day
+    pub end: NaiveTime,
+}
+
+impl ThrottlingSchedule {
+    /// Returns true if the given local time falls in the window, false otherwise
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        let time = now.time();
+        let day = now.weekday();
+        let starts_on = |day: Weekday| self.weekdays.is_empty() || self.weekdays.contains(&day);
+        if self.start < self.end {
+            starts_on(day) && self.start <= time && time < self.end
+        } else {
+            // the window spans midnight, so it may have started on the previous day
+            (starts_on(day) && time >= self.start) || (starts_on(day.pred()) && time < self.end)
+        }
+    }
+}
+
+/// Named set of throttling limits, applied while its schedule is active
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ThrottlingProfile {
+    pub name: String,
+    /// Maximum ingress bandwidth of the interface, in kilobytes per second
+    pub interface_kbps: Option<u32>,
+    // in TOML, plain values must precede the tables
+    pub schedule: ThrottlingSchedule,
+    /// Rules taking precedence over the ones with the same target set by the user;
+    /// omitted if empty, since in TOML it would be a plain value following the schedule
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<ThrottlingRule>,
+}
+
+/// Throttling profile being created or edited in the GUI, with the fields typed by the user
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct ThrottlingProfileDraft {
+    /// Position of the profile being edited, if it already exists
+    pub index: Option<usize>,
+    pub name: String,
+    /// Days of the week in which the window starts; every day if empty
+    pub weekdays: Vec<Weekday>,
+    /// Time of the day at which the window starts, formatted as `HH:MM`
+    pub start: String,
+    /// Time of the day at which the window ends, formatted as `HH:MM`
+    pub end: String,
+    /// Maximum ingress bandwidth of the interface, in kilobytes per second (optional)
+    pub interface_kbps: String,
+    pub rules: Vec<ThrottlingRule>,
+}
+
+impl ThrottlingProfileDraft {
+    /// Returns the draft to edit the profile at the given position
+    pub fn from_profile(index: usize, profile: &ThrottlingProfile) -> Self {
+        Self {
+            index: Some(index),
+            name: profile.name.clone(),
+            weekdays: profile.schedule.weekdays.clone(),
+            start: profile.schedule.start.format("%H:%M").to_string(),
+            end: profile.schedule.end.format("%H:%M").to_string(),
+            interface_kbps: profile
+                .interface_kbps
+                .map(|kbps| kbps.to_string())
+                .unwrap_or_default(),
+            rules: profile.rules.clone(),
+        }
+    }
+
+    /// Selects or deselects a day of the week, keeping the days sorted
+    pub fn set_weekday(&mut self, weekday: Weekday, selected: bool) {
+        self.weekdays.retain(|day| day.ne(&weekday));
+        if selected {
+            self.weekdays.push(weekday);
+            self.weekdays.sort_by_key(Weekday::num_days_from_monday);
+        }
+    }
+
+    /// Adds the given rules, replacing the ones of the draft with the same target
+    pub fn add_rules(&mut self, rules: &[ThrottlingRule]) {
+        for rule in rules {
+            match self
+                .rules
+                .iter_mut()
+                .find(|other| other.target.eq(&rule.target))
+            {
+                Some(other) => other.kbps = rule.kbps,
+                None => self.rules.push(rule.clone()),
+            }
+        }
+    }
+
+    /// Returns the profile described by the draft, if its fields are valid
+    /// and its name isn't used by the other profiles
+    pub fn to_profile(&self, profiles: &[ThrottlingProfile]) -> Option<ThrottlingProfile> {
+        let name = self.name.trim();
+        let name_taken = profiles
+            .iter()
+            .enumerate()
+            .any(|(i, profile)| profile.name.eq(name) && self.index.ne(&Some(i)));
+        if name.is_empty() || name_taken {
+            return None;
+        }
+        let interface_kbps = match self.interface_kbps.trim() {
+            "" => None,
+            kbps => Some(kbps.parse::<u32>().ok().filter(|kbps| *kbps > 0)?),
+        };
+        Some(ThrottlingProfile {
+            name: name.to_string(),
+            schedule: ThrottlingSchedule {
+                weekdays: self.weekdays.clone(),
+                start: parse_time(&self.start)?,
+                end: parse_time(&self.end)?,
+            },
+            interface_kbps,
+            rules: self.rules.clone(),
+        })
+    }
+}
+
+/// Parses a time of the day formatted as `HH:MM`
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+    use serde::{Deserialize, Serialize};
+
+    use crate::configs::types::config_throttling::{ThrottlingRule, ThrottlingRuleTarget};
+    use crate::configs::types::throttling_profile::{
+        ThrottlingProfile, ThrottlingProfileDraft, ThrottlingSchedule,
+    };
+
+    /// TOML documents are tables, so the profiles are stored in an array
+    #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+    struct ProfilesFile {
+        profiles: Vec<ThrottlingProfile>,
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // the 2nd of October 2023 was a Monday
+        NaiveDate::from_ymd_opt(2023, 10, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_work_hours_schedule() {
+        let work_hours = ThrottlingSchedule {
+            weekdays: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+        };
+        assert!(work_hours.is_active(at(2, 9, 0)));
+        assert!(work_hours.is_active(at(6, 17, 59)));
+        assert!(!work_hours.is_active(at(2, 8, 59)));
+        assert!(!work_hours.is_active(at(2, 18, 0)));
+        // weekend
+        assert!(!work_hours.is_active(at(7, 12, 0)));
+    }
+
+    #[test]
+    fn test_night_schedule() {
+        let night = ThrottlingSchedule {
+            weekdays: vec![Weekday::Sun],
+            start: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+        };
+        assert!(night.is_active(at(8, 23, 30)));
+        // the window started on Sunday
+        assert!(night.is_active(at(9, 5, 59)));
+        assert!(!night.is_active(at(9, 6, 0)));
+        assert!(!night.is_active(at(9, 23, 30)));
+        assert!(!night.is_active(at(8, 5, 0)));
+
+        let every_day = ThrottlingSchedule {
+            weekdays: vec![],
+            start: NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+        };
+        assert!(every_day.is_active(at(4, 0, 0)));
+        assert!(every_day.is_active(at(7, 23, 59)));
+    }
+
+    #[test]
+    fn test_profile_draft() {
+        let backup = ThrottlingRule {
+            target: ThrottlingRuleTarget::Executable("/usr/bin/rsync".to_string()),
+            kbps: 50,
+        };
+        let mut draft = ThrottlingProfileDraft {
+            name: " work hours ".to_string(),
+            start: "09:00".to_string(),
+            end: "18:30".to_string(),
+            ..ThrottlingProfileDraft::default()
+        };
+        draft.set_weekday(Weekday::Fri, true);
+        draft.set_weekday(Weekday::Mon, true);
+        draft.set_weekday(Weekday::Wed, true);
+        draft.set_weekday(Weekday::Wed, false);
+        draft.add_rules(std::slice::from_ref(&backup));
+        draft.add_rules(&[ThrottlingRule {
+            kbps: 80,
+            ..backup.clone()
+        }]);
+
+        let profile = draft.to_profile(&[]).unwrap();
+        assert_eq!(
+            profile,
+            ThrottlingProfile {
+                name: "work hours".to_string(),
+                schedule: ThrottlingSchedule {
+                    weekdays: vec![Weekday::Mon, Weekday::Fri],
+                    start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                    end: NaiveTime::from_hms_opt(18, 30, 0).unwrap(),
+                },
+                interface_kbps: None,
+                rules: vec![ThrottlingRule { kbps: 80, ..backup }],
+            }
+        );
+        assert_eq!(
+            ThrottlingProfileDraft::from_profile(0, &profile)
+                .to_profile(std::slice::from_ref(&profile)),
+            Some(profile.clone())
+        );
+
+        // the name of another profile can't be reused
+        assert_eq!(draft.to_profile(std::slice::from_ref(&profile)), None);
+        draft.index = Some(0);
+        assert!(draft.to_profile(&[profile]).is_some());
+
+        draft.interface_kbps = "5000".to_string();
+        assert_eq!(draft.to_profile(&[]).unwrap().interface_kbps, Some(5000));
+        draft.interface_kbps = "fast".to_string();
+        assert_eq!(draft.to_profile(&[]), None);
+        draft.interface_kbps = String::new();
+        draft.end = "25:00".to_string();
+        assert_eq!(draft.to_profile(&[]), None);
+    }
+
+    #[test]
+    fn test_profiles_survive_a_toml_round_trip() {
+        let profiles = ProfilesFile {
+            profiles: vec![
+                ThrottlingProfile {
+                    name: "night".to_string(),
+                    interface_kbps: Some(10000),
+                    schedule: ThrottlingSchedule {
+                        weekdays: vec![Weekday::Sat, Weekday::Sun],
+                        start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+                        end: NaiveTime::from_hms_opt(7, 30, 0).unwrap(),
+                    },
+                    rules: vec![ThrottlingRule {
+                        kbps: 50,
+                        target: ThrottlingRuleTarget::Executable("/usr/bin/rsync".to_string()),
+                    }],
+                },
+                ThrottlingProfile {
+                    name: "work hours".to_string(),
+                    interface_kbps: None,
+                    schedule: ThrottlingSchedule {
+                        weekdays: vec![],
+                        start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                        end: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+                    },
+                    rules: vec![],
+                },
+            ],
+        };
+
+        let stored = toml::to_string(&profiles).unwrap();
+        assert_eq!(toml::from_str::<ProfilesFile>(&stored).unwrap(), profiles);
+    }
+}
@@ -16,7 +16,10 @@
 use crate::gui::components::modal::{get_clear_all_overlay, get_exit_overlay, Modal};
 use crate::gui::components::types::my_modal::MyModal;
 use crate::gui::pages::connection_details_page::connection_details_page;
-use crate::gui::pages::throttling_page::throttling_page; //newly added
+use crate::gui::pages::throttling_dashboard_page::throttling_dashboard_page;
+use crate::gui::pages::throttling_profiles_page::throttling_profiles_page;
+use crate::gui::pages::throttling_quotas_page::throttling_quotas_page;
+use crate::gui::pages::throttling_page::{host_throttling_page, throttling_page}; //newly added
 use crate::gui::pages::initial_page::initial_page;
 use crate::gui::pages::inspect_page::inspect_page;
 use crate::gui::pages::notifications_page::notifications_page;
@@ -91,6 +94,7 @@ fn view(&self) -> Element<Message> {
             Status::Running => match self.running_page {
                 RunningPage::Overview => overview_page(self),
                 RunningPage::Inspect => inspect_page(self),
+                RunningPage::Throttling => throttling_dashboard_page(self),
                 RunningPage::Notifications => notifications_page(self),
             },
         };
@@ -134,6 +138,9 @@ fn view(&self) -> Element<Message> {
                     MyModal::ThorttlingModal(id, throttling_mode ) => {
                         throttling_page(self, id, throttling_mode)
                     }
+                    MyModal::HostThrottlingModal => host_throttling_page(self),
+                    MyModal::ProfilesModal => throttling_profiles_page(self),
+                    MyModal::QuotasModal => throttling_quotas_page(self),
                 };
 
                 Modal::new(content, overlay)
@@ -147,6 +154,7 @@ fn subscription(&self) -> Subscription<Message> {
         const NO_MODIFIER: Modifiers = Modifiers::empty();
         let hot_keys_subscription = subscription::events_with(|event, _| match event {
             Window(window::Event::Focused) => Some(Message::WindowFocused),
+            Window(window::Event::CloseRequested) => Some(Message::Quit),
             Keyboard(Event::KeyPressed {
                 key_code,
                 modifiers,
@@ -259,6 +259,40 @@ pub fn sound_process_threshold_radios(
     }
     ret_val
 }
+pub fn process_threshold_type_radios(
+    process_notification: ProcessNotification,
+    font: Font,
+    style: StyleType,
+    language: Language,
+) -> Row<'static, Message> {
+    let mut ret_val = Row::new().spacing(20);
+    for option in ChartType::ALL {
+        ret_val = ret_val.push(
+            Radio::new(
+                option.get_radio_label(language),
+                option,
+                Some(process_notification.threshold_type),
+                |value| {
+                    Message::UpdateNotificationSettings(
+                        Notification::Process(ProcessNotification {
+                            threshold_type: value,
+                            ..process_notification
+                        }),
+                        false,
+                    )
+                },
+            )
+            .spacing(7)
+            .font(font)
+            .size(15)
+            .style(<RadioStyleTuple as Into<iced::theme::Radio>>::into(
+                RadioStyleTuple(style, RadioType::Standard),
+            )),
+        );
+    }
+    ret_val
+}
+
 pub fn chart_radios(
     active: ChartType,
     font: Font,
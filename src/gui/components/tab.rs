@@ -41,7 +41,7 @@ pub fn get_settings_tabs(
 }
 
 pub fn get_pages_tabs(
-    labels: [RunningPage; 3],
+    labels: [RunningPage; 4],
     icons: &[&str],
     actions: &[Message],
     active: RunningPage,
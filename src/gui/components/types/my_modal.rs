@@ -10,4 +10,10 @@ pub enum MyModal {
     ConnectionDetails(usize),
     /// Process, port or user throttling modal which will be passed proccess id .
     ThorttlingModal(u32, ThrottlingMode),
+    /// Remote hosts throttling modal, listing the selectors stored in the sniffer.
+    HostThrottlingModal,
+    /// Throttling profiles modal, to create, edit and remove the scheduled profiles.
+    ProfilesModal,
+    /// Data quotas modal, to create, edit and remove the quotas and see their usage.
+    QuotasModal,
 }
@@ -19,16 +19,18 @@
 use crate::networking::manage_packets::{get_address_to_lookup, get_traffic_type, is_my_address};
 use crate::networking::types::address_port_pair::AddressPortPair;
 use crate::networking::types::host::Host;
+use crate::networking::types::host_selector::HostSelector;
 use crate::networking::types::info_address_port_pair::InfoAddressPortPair;
 use crate::networking::types::traffic_direction::TrafficDirection;
 use crate::translations::translations::{
-    application_protocol_translation, hide_translation, incoming_translation, outgoing_translation,
-    packets_translation, transport_protocol_translation,
+    application_protocol_translation, hide_translation, incoming_translation,
+    network_adapter_translation, outgoing_translation, packets_translation,
+    transport_protocol_translation,
 };
 use crate::translations::translations_2::{
     administrative_entity_translation, connection_details_translation, destination_translation,
-    fqdn_translation, mac_address_translation, socket_address_translation, source_translation,
-    transmitted_data_translation,
+    fqdn_translation, limit_host_translation, mac_address_translation, socket_address_translation,
+    source_translation, transmitted_data_translation,
 };
 use crate::utils::formatted_strings::{get_formatted_bytes_string_with_b, get_socket_address};
 use crate::{Language, Sniffer, StyleType};
@@ -61,6 +63,15 @@ fn page_content(sniffer: &Sniffer, connection_index: usize) -> Container<'static
         .get(&host_option.clone().unwrap_or_default().1)
         .cloned();
     drop(info_traffic_lock);
+    let host_selectors = address_to_lookup
+        .parse::<IpAddr>()
+        .map(|address| {
+            HostSelector::get_address_selectors(
+                address,
+                &host_option.clone().unwrap_or_default().1,
+            )
+        })
+        .unwrap_or_default();
 
     let header_and_content = Column::new().width(Length::Fill).push(page_header(
         sniffer.style,
@@ -102,10 +113,11 @@ fn page_content(sniffer: &Sniffer, connection_index: usize) -> Container<'static
         }
     }
 
+    let (port1, port2) = key.get_port_labels();
     let mut source_col = get_src_or_dest_col(
         source_caption,
         &key.address1,
-        key.port1,
+        &port1,
         &val.mac_address1,
         &val.pids,  // can be None
         val.uid,   
@@ -116,7 +128,7 @@ fn page_content(sniffer: &Sniffer, connection_index: usize) -> Container<'static
     let mut dest_col = get_src_or_dest_col(
         dest_caption,
         &key.address2,
-        key.port2,
+        &port2,
         &val.mac_address2,
         &val.pids,  // None since this is the destination
         val.uid,   
@@ -125,6 +137,10 @@ fn page_content(sniffer: &Sniffer, connection_index: usize) -> Container<'static
         sniffer.language,
     );
 
+    if !host_selectors.is_empty() {
+        host_info_col = host_info_col.push(limit_host_button(host_selectors, sniffer.style, sniffer.language));
+    }
+
     if address_to_lookup.eq(&key.address1) {
         source_col = source_col.push(host_info_col);
     } else {
@@ -221,6 +237,11 @@ fn col_info(
                 .font(font),
             ),
         )
+        .push(TextType::highlighted_subtitle_with_desc(
+            network_adapter_translation(language),
+            &key.interface,
+            style,
+        ))
         .push(TextType::highlighted_subtitle_with_desc(
             transport_protocol_translation(language),
             &key.trans_protocol.to_string(),
@@ -285,12 +306,40 @@ fn get_host_info_col(
     host_info_col
 }
 
+fn limit_host_button(
+    host_selectors: Vec<HostSelector>,
+    style: StyleType,
+    language: Language,
+) -> Container<'static, Message> {
+    Container::new(
+        button(
+            Text::new(limit_host_translation(language))
+                .font(get_font(style))
+                .vertical_alignment(Vertical::Center)
+                .horizontal_alignment(Horizontal::Center)
+                .size(15),
+        )
+        .padding([2, 10])
+        .style(ButtonStyleTuple(style, ButtonType::Standard).into())
+        .on_press(Message::ShowHostThrottlingModal(host_selectors)),
+    )
+    .padding([5, 0, 0, 0])
+    .width(Length::Fill)
+    .align_x(Horizontal::Center)
+}
+
 fn get_local_tooltip(
     sniffer: &Sniffer,
     address_to_lookup: &str,
     key: &AddressPortPair,
 ) -> Tooltip<'static, Message> {
-    let my_interface_addresses = &*sniffer.device.addresses.lock().unwrap();
+    // the connection may have been observed on one of the additional adapters
+    let device = sniffer
+        .get_sniffed_devices()
+        .into_iter()
+        .find(|device| device.name.eq(&key.interface))
+        .unwrap_or_else(|| sniffer.device.clone());
+    let my_interface_addresses = &*device.addresses.lock().unwrap();
     get_computer_tooltip(
         is_my_address(
             if address_to_lookup.eq(&key.address1) {
@@ -317,7 +366,7 @@ fn get_local_tooltip(
 fn get_src_or_dest_col(
     caption: Row<'static, Message>,
     ip: &String,
-    port: u16,
+    port: &str,
     mac: &str,
     pid: &Option<Vec<u32>>,
     uid: Option<u32>,
@@ -8,8 +8,8 @@
 use iced::widget::scrollable::Direction;
 use iced::widget::tooltip::Position;
 use iced::widget::{
-    button, horizontal_space, vertical_space, Button, Column, Container, PickList, Row, Scrollable,
-    Tooltip, TextInput
+    button, horizontal_space, vertical_space, Button, Checkbox, Column, Container, PickList, Row,
+    Scrollable, Tooltip, TextInput
 };
 use iced::Color;
 use iced::Renderer;
@@ -22,14 +22,18 @@
 
 use crate::gui::components::radio::{ip_version_radios, transport_protocol_radios};
 use crate::gui::styles::button::{ButtonStyleTuple, ButtonType};
+use crate::gui::styles::checkbox::{CheckboxStyleTuple, CheckboxType};
 use crate::gui::styles::container::{ContainerStyleTuple, ContainerType};
 use crate::gui::styles::picklist::{PicklistStyleTuple, PicklistType};
 use crate::gui::styles::scrollbar::{ScrollbarStyleTuple, ScrollbarType};
-use crate::gui::styles::style_constants::{get_font, FONT_SIZE_SUBTITLE, FONT_SIZE_TITLE, ICONS};
+use crate::gui::styles::style_constants::{
+    get_font, FONT_SIZE_FOOTER, FONT_SIZE_SUBTITLE, FONT_SIZE_TITLE, ICONS,
+};
 use crate::gui::styles::text::{TextStyleTuple, TextType};
 use crate::gui::styles::types::gradient_type::GradientType;
 use crate::gui::types::message::Message;
 use crate::gui::types::sniffer::Sniffer;
+use crate::networking::types::savefile_options::SavefileOptions;
 use crate::translations::translations::{
     address_translation, addresses_translation, all_translation, application_protocol_translation,
     choose_adapters_translation, select_filters_translation, start_translation,
@@ -83,6 +87,29 @@ pub fn initial_page(sniffer: &Sniffer) -> Container<Message> {
                 .size(FONT_SIZE_SUBTITLE),
         ).push(port_textbox);
     
+    // raw BPF expression compiled into the capture, validated while it's typed
+    let bpf_textbox: TextInput<Message,Renderer> = TextInput::new(
+        "net 10.0.0.0/8 and not port 22",
+        &sniffer.filters.bpf,
+    ).width(300).font(font).on_input(Message::BpfFilter).style(<TextInputStyleTuple as Into<iced::theme::TextInput>>::into(
+        TextInputStyleTuple(sniffer.style, TextInputType::Standard)));
+
+    let mut bpf_filter = Column::new(
+        ).spacing(5).push(
+            Text::new("BPF filter")
+                .font(font)
+                .style(TextStyleTuple(sniffer.style, TextType::Subtitle))
+                .size(FONT_SIZE_SUBTITLE),
+        ).push(bpf_textbox);
+    if let Some(bpf_error) = &sniffer.bpf_error {
+        bpf_filter = bpf_filter.push(
+            Text::new(bpf_error.clone())
+                .font(font)
+                .size(FONT_SIZE_FOOTER)
+                .style(iced::theme::Text::Color(Color::from_rgb(0.8, 0.15, 0.15))),
+        );
+    }
+
     let added_filters = Column::new()
         .spacing(20)
         .width(FillPortion(5))
@@ -91,7 +118,8 @@ pub fn initial_page(sniffer: &Sniffer) -> Container<Message> {
                 .push(pid_filter)
                 .push(uid_filter)
                 .push(port_filter
-        ).spacing(10).align_items(Alignment::Center));
+        ).spacing(10).align_items(Alignment::Center))
+        .push(bpf_filter);
     
     let ip_active = sniffer.filters.ip;
     let col_ip_radio = ip_version_radios(ip_active, font, sniffer.style, sniffer.language);
@@ -164,7 +192,12 @@ pub fn initial_page(sniffer: &Sniffer) -> Container<Message> {
         ).push(
             Row::new()
                 .push(
-                    button_start(sniffer.style, sniffer.language, sniffer.color_gradient)
+                    button_start(
+                        sniffer.style,
+                        sniffer.language,
+                        sniffer.color_gradient,
+                        sniffer.bpf_error.is_none() && sniffer.local_addresses_error.is_none(),
+                    )
                         
                 )
                 .align_items(Alignment::Center),
@@ -177,13 +210,51 @@ pub fn initial_page(sniffer: &Sniffer) -> Container<Message> {
         &sniffer.interface_bandwidth,
     ).width(80).on_input(Message::InterfaceBandwidth).style(<TextInputStyleTuple as Into<iced::theme::TextInput>>::into(
         TextInputStyleTuple(sniffer.style, TextInputType::Standard)));;
+    // path of a pcap/pcapng file to be replayed instead of sniffing the selected adapter
+    let pcap_import_textbox: TextInput<Message,Renderer> = TextInput::new(
+        "/path/to/capture.pcapng",
+        &sniffer.pcap_import_path,
+    ).width(200).on_input(Message::PcapImportPath).style(<TextInputStyleTuple as Into<iced::theme::TextInput>>::into(
+        TextInputStyleTuple(sniffer.style, TextInputType::Standard)));
+    // local addresses of the imported file, inferred from the file itself if left empty
+    let local_addresses_textbox: TextInput<Message,Renderer> = TextInput::new(
+        "192.168.1.10, fe80::1",
+        &sniffer.pcap_local_addresses,
+    ).width(200).on_input(Message::PcapLocalAddresses).style(<TextInputStyleTuple as Into<iced::theme::TextInput>>::into(
+        TextInputStyleTuple(sniffer.style, TextInputType::Standard)));
+    let mut import_file = Column::new()
+        .push(
+            Text::new("Import file")
+                .font(font)
+                .style(TextStyleTuple(sniffer.style, TextType::Subtitle))
+                .size(FONT_SIZE_SUBTITLE),
+        ).push(pcap_import_textbox)
+        .push(vertical_space(Length::Fixed(10.0)))
+        .push(
+            Text::new("Local addresses of the file")
+                .font(font)
+                .style(TextStyleTuple(sniffer.style, TextType::Subtitle))
+                .size(FONT_SIZE_SUBTITLE),
+        ).push(local_addresses_textbox);
+    if let Some(local_addresses_error) = &sniffer.local_addresses_error {
+        import_file = import_file.push(
+            Text::new(local_addresses_error.clone())
+                .font(font)
+                .size(FONT_SIZE_FOOTER)
+                .style(iced::theme::Text::Color(Color::from_rgb(0.8, 0.15, 0.15))),
+        );
+    }
     let throttling_interface = Column::new(
         ).push(
             Text::new("Throttling")
                 .font(font)
                 .style(TextStyleTuple(sniffer.style, TextType::Subtitle))
                 .size(FONT_SIZE_SUBTITLE),
-        ).push(throttling_textbox);
+        ).push(throttling_textbox)
+        .push(vertical_space(Length::Fixed(20.0)))
+        .push(import_file)
+        .push(vertical_space(Length::Fixed(20.0)))
+        .push(get_col_savefile(sniffer, font));
     
     let body = Column::new().push(vertical_space(Length::Fixed(5.0))).push(
         Row::new()
@@ -205,8 +276,9 @@ fn button_start(
     style: StyleType,
     language: Language,
     color_gradient: GradientType,
+    enabled: bool,
 ) -> Tooltip<'static, Message> {
-    let content = button(
+    let mut content = button(
         Text::new("S")
             .font(ICONS)
             .size(25)
@@ -216,8 +288,11 @@ fn button_start(
     .padding(10)
     .height(Length::Fixed(80.0))
     .width(Length::Fixed(160.0))
-    .style(ButtonStyleTuple(style, ButtonType::Gradient(color_gradient)).into())
-    .on_press(Message::Start);
+    .style(ButtonStyleTuple(style, ButtonType::Gradient(color_gradient)).into());
+    // sniffing can't start until the BPF filter is valid
+    if enabled {
+        content = content.on_press(Message::Start);
+    }
 
     let tooltip = start_translation(language).to_string();
     //tooltip.push_str(" [⏎]");
@@ -279,23 +354,32 @@ fn get_col_adapter(sniffer: &Sniffer, font: Font) -> Column<Message> {
                 |scroll_adapters, adapter| {
                     let name = adapter.0.clone();
                     let description = adapter.1.clone();
-                    scroll_adapters.push(
-                        Button::new(Text::new(description).font(font))
-                            .padding([20, 30])
-                            .width(Length::Fill)
-                            .style(
-                                ButtonStyleTuple(
-                                    sniffer.style,
-                                    if name == sniffer.device.name {
-                                        ButtonType::BorderedRoundSelected
-                                    } else {
-                                        ButtonType::BorderedRound
-                                    },
+                    let is_main = name == sniffer.device.name;
+                    let mut adapter_row = Row::new()
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .push(
+                            Button::new(Text::new(description).font(font))
+                                .padding([20, 30])
+                                .width(Length::Fill)
+                                .style(
+                                    ButtonStyleTuple(
+                                        sniffer.style,
+                                        if is_main {
+                                            ButtonType::BorderedRoundSelected
+                                        } else {
+                                            ButtonType::BorderedRound
+                                        },
+                                    )
+                                    .into(),
                                 )
-                                .into(),
-                            )
-                            .on_press(Message::AdapterSelection(name)),
-                    )
+                                .on_press(Message::AdapterSelection(name.clone())),
+                        );
+                    if !is_main {
+                        adapter_row =
+                            adapter_row.push(additional_adapter_checkbox(sniffer, name, font));
+                    }
+                    scroll_adapters.push(adapter_row)
                 },
             ))
             .direction(Direction::Vertical(ScrollbarType::properties()))
@@ -307,3 +391,153 @@ fn get_col_adapter(sniffer: &Sniffer, font: Font) -> Column<Message> {
             ),
         )
 }
+
+/// Checkbox to sniff an adapter together with the selected one
+fn additional_adapter_checkbox(
+    sniffer: &Sniffer,
+    name: String,
+    font: Font,
+) -> Tooltip<'static, Message> {
+    let is_selected = sniffer
+        .additional_devices
+        .iter()
+        .any(|device| device.name.eq(&name));
+    let checkbox = Checkbox::new("", is_selected, move |selected| {
+        Message::AdditionalAdapterSelection(name.clone(), selected)
+    })
+    .size(18)
+    .font(font)
+    .style(<CheckboxStyleTuple as Into<iced::theme::Checkbox>>::into(
+        CheckboxStyleTuple(sniffer.style, CheckboxType::Standard),
+    ));
+    Tooltip::new(
+        checkbox,
+        "Sniff together with the selected adapter",
+        Position::Left,
+    )
+    .gap(5)
+    .font(font)
+    .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+        ContainerStyleTuple(sniffer.style, ContainerType::Tooltip),
+    ))
+}
+
+/// Inputs to dump the filtered packets into rotating pcap savefiles
+fn get_col_savefile(sniffer: &Sniffer, font: Font) -> Column<'static, Message> {
+    let options = sniffer.savefile_options.clone();
+    let style = sniffer.style;
+
+    let path_options = options.clone();
+    let snaplen_options = options.clone();
+    let megabytes_options = options.clone();
+    let seconds_options = options.clone();
+    let max_files_options = options.clone();
+
+    Column::new()
+        .spacing(5)
+        .push(savefile_input(
+            "Save to pcap",
+            &options.path,
+            "/path/to/dump.pcap",
+            200.0,
+            move |path| {
+                Message::UpdateSavefileOptions(SavefileOptions {
+                    path,
+                    ..path_options.clone()
+                })
+            },
+            font,
+            style,
+        ))
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(savefile_input(
+                    "Snaplen",
+                    &options.snaplen,
+                    "65535",
+                    60.0,
+                    move |snaplen| {
+                        Message::UpdateSavefileOptions(SavefileOptions {
+                            snaplen: snaplen.trim().to_string(),
+                            ..snaplen_options.clone()
+                        })
+                    },
+                    font,
+                    style,
+                ))
+                .push(savefile_input(
+                    "Max files",
+                    &options.max_files,
+                    "-",
+                    60.0,
+                    move |max_files| {
+                        Message::UpdateSavefileOptions(SavefileOptions {
+                            max_files: max_files.trim().to_string(),
+                            ..max_files_options.clone()
+                        })
+                    },
+                    font,
+                    style,
+                )),
+        )
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(savefile_input(
+                    "Rotate MB",
+                    &options.rotate_megabytes,
+                    "-",
+                    60.0,
+                    move |megabytes| {
+                        Message::UpdateSavefileOptions(SavefileOptions {
+                            rotate_megabytes: megabytes.trim().to_string(),
+                            ..megabytes_options.clone()
+                        })
+                    },
+                    font,
+                    style,
+                ))
+                .push(savefile_input(
+                    "Rotate s",
+                    &options.rotate_seconds,
+                    "-",
+                    60.0,
+                    move |seconds| {
+                        Message::UpdateSavefileOptions(SavefileOptions {
+                            rotate_seconds: seconds.trim().to_string(),
+                            ..seconds_options.clone()
+                        })
+                    },
+                    font,
+                    style,
+                )),
+        )
+}
+
+fn savefile_input(
+    caption: &str,
+    value: &str,
+    placeholder: &str,
+    width: f32,
+    on_input: impl Fn(String) -> Message + 'static,
+    font: Font,
+    style: StyleType,
+) -> Column<'static, Message> {
+    Column::new()
+        .push(
+            Text::new(caption.to_string())
+                .font(font)
+                .style(TextStyleTuple(style, TextType::Subtitle))
+                .size(FONT_SIZE_SUBTITLE),
+        )
+        .push(
+            TextInput::new(placeholder, value)
+                .width(Length::Fixed(width))
+                .font(font)
+                .on_input(on_input)
+                .style(<TextInputStyleTuple as Into<iced::theme::TextInput>>::into(
+                    TextInputStyleTuple(style, TextInputType::Standard),
+                )),
+        )
+}
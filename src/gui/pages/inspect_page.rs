@@ -9,10 +9,10 @@
 use iced::widget::Radio;
 use iced::widget::radio::Appearance;
 // use crate::gui::components::custom_radio::CustomRadio;
-use iced::{alignment, Alignment, Font, Length, Renderer};
+use iced::{alignment, Alignment, Color, Font, Length, Renderer};
 
 use std::collections::HashMap;
-use std::u32::MAX;
+use std::path::PathBuf;
 
 use crate::gui::components::radio::report_view_radios;
 use crate::gui::components::tab::get_pages_tabs;
@@ -29,19 +29,28 @@
 use crate::gui::styles::text::{TextStyleTuple, TextType};
 use crate::gui::styles::text_input::{TextInputStyleTuple, TextInputType};
 use crate::gui::types::message::Message;
+use crate::networking::types::process_info::{get_process_tree, ProcessInfo};
 use crate::networking::types::search_parameters::{FilterInputType, SearchParameters};
 use crate::networking::types::traffic_direction::TrafficDirection;
 use crate::networking::types::trans_protocol::TransProtocol;
-use crate::report::get_report_entries::get_searched_entries;
-use crate::translations::translations::application_protocol_translation;
+use crate::report::get_report_entries::{get_searched_entries, get_user_entries};
+use crate::report::types::export_format::ExportFormat;
+use crate::translations::translations::{
+    application_protocol_translation, network_adapter_translation,
+};
 use crate::translations::translations_2::{
     administrative_entity_translation, country_translation, domain_name_translation,
     no_search_results_translation, only_show_favorites_translation, search_filters_translation,
     showing_results_translation, sort_by_translation,
 };
+use crate::translations::translations_3::{
+    connections_exported_translation, export_failed_translation,
+};
 use crate::utils::formatted_strings::get_formatted_bytes_string;
-use crate::utils::formatted_strings::{get_connection_color, get_open_report_tooltip};
-use crate::{Language, ReportSortType, RunningPage, Sniffer, StyleType};
+use crate::utils::formatted_strings::{
+    get_connection_color, get_export_path, get_open_report_tooltip,
+};
+use crate::{ChartType, Language, ReportSortType, RunningPage, Sniffer, StyleType};
 use crate::gui::components::types::throttling_mode::ThrottlingMode;
 
 use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo};
@@ -63,12 +72,14 @@ pub fn inspect_page(sniffer: &Sniffer) -> Container<Message> {
         [
             RunningPage::Overview,
             RunningPage::Inspect,
+            RunningPage::Throttling,
             RunningPage::Notifications,
         ],
-        &["d ", "5 ", "7 "],
+        &["d ", "5 ", "T ", "7 "],
         &[
             Message::ChangeRunningPage(RunningPage::Overview),
             Message::TickInit,
+            Message::ChangeRunningPage(RunningPage::Throttling),
             Message::ChangeRunningPage(RunningPage::Notifications),
         ],
         RunningPage::Inspect,
@@ -122,8 +133,6 @@ pub fn inspect_page(sniffer: &Sniffer) -> Container<Message> {
     );
 
 
-    // dbg!(sn);
-
     body = body
         .push(
             Container::new(
@@ -151,7 +160,7 @@ pub fn inspect_page(sniffer: &Sniffer) -> Container<Message> {
                             .push(picklist_sort),
                     ),
             )
-            .height(Length::Fixed(165.0))
+            .height(Length::Fixed(200.0))
             .padding(10)
             .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
                 ContainerStyleTuple(sniffer.style, ContainerType::BorderedRound),
@@ -188,9 +197,10 @@ fn lazy_report(sniffer: &Sniffer, report_view: ReportView) -> Row<'static, Messa
                     .align_items(Alignment::Center)
                     .push(
                         Text::new(format!(
-                            "          {}{}",
+                            "          {}{}   {}",
                             key.print_gui(),
                             val.print_gui(),
+                            key.interface,
                         ))
                         .style(iced::theme::Text::Color(entry_color))
                         .font(font),
@@ -205,7 +215,7 @@ fn lazy_report(sniffer: &Sniffer, report_view: ReportView) -> Row<'static, Messa
             }
             if results_number > 0 {
                 col_report = col_report
-                    .push(Text::new("      Src IP address       Src port      Dst IP address       Dst port  Layer4   Layer7     Packets     Bytes    pid     uid").vertical_alignment(Vertical::Center).height(Length::FillPortion(2)).font(font))
+                    .push(Text::new("      Src IP address       Src port      Dst IP address       Dst port  Layer4   Layer7     Packets     Bytes    pid     uid   Adapter").vertical_alignment(Vertical::Center).height(Length::FillPortion(2)).font(font))
                     .push(Rule::horizontal(5).style(<RuleStyleTuple as Into<iced::theme::Rule>>::into(RuleStyleTuple(
                         sniffer.style,
                         RuleType::Standard,
@@ -256,9 +266,13 @@ fn lazy_report(sniffer: &Sniffer, report_view: ReportView) -> Row<'static, Messa
             // loop over all search results, and get pid from info_address_port_pair
             // Aggregate data by PID
             let mut pid_stats: HashMap<u32, (u128, u128, u128, u128)> = HashMap::new();
+            let mut pid_names: HashMap<u32, String> = HashMap::new();
             for (_, val, _) in &search_results {
                 if let Some(pids) = &val.pids {
                     for pid in pids {
+                        if let Some(process_name) = &val.process_name {
+                            pid_names.entry(*pid).or_insert_with(|| process_name.clone());
+                        }
                         let (total_bytes_in, total_packets_in, total_bytes_out, total_packets_out) = pid_stats
                             .entry(*pid)
                             .or_insert((0, 0, 0, 0));
@@ -293,10 +307,46 @@ fn lazy_report(sniffer: &Sniffer, report_view: ReportView) -> Row<'static, Messa
                 ReportSortType::MostPackets => (b.1 + b.3).cmp(&(a.1 + a.3)),
                 _ => std::cmp::Ordering::Equal,
             });
+            // retrieve the details of the listed processes, and arrange them as a tree
+            let pids: Vec<u32> = sorted_pids_stats_vec.iter().map(|(pid, _)| *pid).collect();
+            let info_traffic_lock = sniffer.info_traffic.lock().unwrap();
+            let processes: HashMap<u32, ProcessInfo> = pids
+                .iter()
+                .filter_map(|pid| info_traffic_lock.processes.get(pid))
+                .map(|process| (process.pid, process.clone()))
+                .collect();
+            drop(info_traffic_lock);
+            let pid_stats: HashMap<u32, (u128, u128, u128, u128)> =
+                sorted_pids_stats_vec.iter().copied().collect();
+
             let mut scroll_report = Column::new();
-            for (pid, (total_bytes_in, total_packets_in, total_bytes_out, total_packets_out)) in &sorted_pids_stats_vec {
+            for (pid, depth) in get_process_tree(&pids, &processes) {
+                let (total_bytes_in, total_packets_in, total_bytes_out, total_packets_out) = pid_stats[&pid];
+                let process = processes.get(&pid);
+                let name = process
+                    .map(|process| process.name.clone())
+                    .or_else(|| pid_names.get(&pid).cloned())
+                    .unwrap_or_default();
+                let prefix = if depth == 0 {
+                    String::new()
+                } else {
+                    format!("{}└─ ", "   ".repeat(depth - 1))
+                };
+                let process_name: String = format!("{prefix}{name}").chars().take(25).collect();
+                let user = match process {
+                    Some(ProcessInfo { username: Some(username), .. }) => username.clone(),
+                    Some(process) => process.uid.to_string(),
+                    None => "?".to_string(),
+                };
+                let user: String = user.chars().take(12).collect();
+                let start_time = process
+                    .and_then(|process| process.start_time)
+                    .map_or("?".to_string(), |start_time| {
+                        start_time.format("%Y-%m-%d %H:%M:%S").to_string()
+                    });
+                let exe = process.map_or("", |process| process.exe.as_str());
                 // if pid is 0, mark as unknown
-                let pid = if *pid == 0 {
+                let pid_string = if pid == 0 {
                     "Unknown".to_string()
                 } else {
                     pid.to_string()
@@ -305,13 +355,13 @@ fn lazy_report(sniffer: &Sniffer, report_view: ReportView) -> Row<'static, Messa
                 let entry_row = Row::new()
                     .align_items(Alignment::Center)
                     .push(
-                        Text::new(format!("{:25} {:<10}    ", " ", pid))
+                        Text::new(format!("{:<25} {:<10} {:<12} {:<19}    ", process_name, pid_string, user, start_time))
                             .style(iced::Color::from_rgb(1.0, 0.5, 0.0))
                             .font(font) // Use a fixed-width font
                             .horizontal_alignment(iced::alignment::Horizontal::Left)
                     )
                     .push(
-                        Text::new(format!("{:<15}        {:<15}        {:<15}        {:<15}", get_formatted_bytes_string(*total_bytes_in), total_packets_in, get_formatted_bytes_string(*total_bytes_out), total_packets_out))
+                        Text::new(format!("{:<15}        {:<15}        {:<15}        {:<15}    {}", get_formatted_bytes_string(total_bytes_in), total_packets_in, get_formatted_bytes_string(total_bytes_out), total_packets_out, exe))
                             .style(iced::Color::from_rgb(1.0, 0.5, 0.0))
                             .font(font) // Use a fixed-width font
                             .horizontal_alignment(iced::alignment::Horizontal::Left)
@@ -319,28 +369,17 @@ fn lazy_report(sniffer: &Sniffer, report_view: ReportView) -> Row<'static, Messa
                     );
 
                 // add entry_row as button to scroll_report
-                //convert pid from string to num if possible, if not store value -1 in pid_num
-                let pid_num = pid.parse::<u32>().unwrap_or(0);
-
-
-
-
                 scroll_report = scroll_report.push(
                     button(entry_row)
                         .padding(2)
-                        .on_press(Message::ShowModal(MyModal::ThorttlingModal(pid_num, ThrottlingMode::Process))) //ProcessThrottle(*pid)
+                        .on_press(Message::ShowModal(MyModal::ThorttlingModal(pid, ThrottlingMode::Process))) //ProcessThrottle(*pid)
                         .style(ButtonStyleTuple(sniffer.style, ButtonType::Neutral).into()),
                 );
-                //for some wierd the hover effect is not working for the button
-                
-                
-                // scroll_report = scroll_report.push(
-                //     entry_row);
             }
 
             if !sorted_pids_stats_vec.is_empty() {
                 col_report = col_report
-                    .push(Text::new("  PID       Total Bytes(in)       Total Packets(in)       Total Bytes(out)       Total Packets(out)")
+                    .push(Text::new("Process                   PID        User         Started                Total Bytes(in)       Total Packets(in)       Total Bytes(out)       Total Packets(out)   Executable")
                         .vertical_alignment(Vertical::Center)
                         .horizontal_alignment(Horizontal::Center)
                         .height(Length::FillPortion(2))
@@ -397,9 +436,13 @@ fn lazy_report(sniffer: &Sniffer, report_view: ReportView) -> Row<'static, Messa
             let mut port_stats: HashMap<u16, (u128, u128, u128, u128)> = HashMap::new();
             let mut port = 0;
             for (key, val, _) in &search_results {
+                // ICMP messages have no ports
+                let Some((port1, port2)) = key.get_ports() else {
+                    continue;
+                };
                 port = match val.traffic_direction {
-                    TrafficDirection::Incoming => key.port2,
-                    TrafficDirection::Outgoing => key.port1,
+                    TrafficDirection::Incoming => port2,
+                    TrafficDirection::Outgoing => port1,
                 };
                 let (total_bytes_in, total_packets_in, total_bytes_out, total_packets_out) = port_stats
                     .entry(port)
@@ -506,83 +549,54 @@ fn lazy_report(sniffer: &Sniffer, report_view: ReportView) -> Row<'static, Messa
             }
         }
         ReportView::User => {
-            // Aggregate data by UID
-            let mut uid_stats: HashMap<u32, (u128, u128, u128, u128)> = HashMap::new();
-            for (_, val, _) in &search_results {
-                if let Some(uid) = val.uid {
-                    let (total_bytes_in, total_packets_in, total_bytes_out, total_packets_out) = uid_stats
-                        .entry(uid)
-                        .or_insert((0, 0, 0, 0));
-                    if val.traffic_direction == TrafficDirection::Incoming {
-                        *total_bytes_in += val.transmitted_bytes;
-                        *total_packets_in += val.transmitted_packets;
-                    } else {
-                        *total_bytes_out += val.transmitted_bytes;
-                        *total_packets_out += val.transmitted_packets;
-                    }
-                }
-                else {
-                    let (total_bytes_in, total_packets_in, total_bytes_out, total_packets_out) = uid_stats
-                        .entry(MAX) // max value of u32 
-                        .or_insert((0, 0, 0, 0));
-                    if val.traffic_direction == TrafficDirection::Incoming {
-                        *total_bytes_in += val.transmitted_bytes;
-                        *total_packets_in += val.transmitted_packets;
-                    } else {
-                        *total_bytes_out += val.transmitted_bytes;
-                        *total_packets_out += val.transmitted_packets;
-                    }
-                }
-            }
-            
-            let mut sorted_users_stats_vec: Vec<(u32, (u128, u128, u128, u128))> = uid_stats.into_iter().collect();
-
-            sorted_users_stats_vec.sort_by(|&(_, a), &(_, b)| match sniffer.report_sort_type {
-                ReportSortType::MostBytes => (b.0 + b.2).cmp(&(a.0 + a.2)),
-                ReportSortType::MostPackets => (b.1 + b.3).cmp(&(a.1 + a.3)),
-                _ => std::cmp::Ordering::Equal,
-            });
-
+            // Aggregate all the connections by UID
+            let chart_type = match sniffer.report_sort_type {
+                ReportSortType::MostPackets => ChartType::Packets,
+                _ => ChartType::Bytes,
+            };
+            let user_entries = get_user_entries(&sniffer.info_traffic, chart_type);
 
             let mut scroll_report = Column::new();
-            for (uid, (total_bytes_in, total_packets_in, total_bytes_out, total_packets_out)) in &sorted_users_stats_vec {
-                // if uid is MAX, mark as unknown
-                let uid = if *uid == MAX {
-                    "Unknown".to_string()
-                } else {
-                    uid.to_string()
-                };
+            for user_summary in &user_entries {
+                let user: String = user_summary.get_user_string().chars().take(15).collect();
+                // connections without owner are marked as unknown
+                let uid = user_summary.uid.map_or("Unknown".to_string(), |uid| uid.to_string());
+                let top_processes = user_summary
+                    .top_processes
+                    .iter()
+                    .map(|(name, bytes)| format!("{name} ({})", get_formatted_bytes_string(*bytes)))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                let data_info = &user_summary.data_info;
 
                 let entry_row = Row::new()
                     .align_items(Alignment::Center)
                     .push(
-                        Text::new(format!("{:25} {:<10}    ", " ", uid))
+                        Text::new(format!("{:<15} {:<10}    ", user, uid))
                             .style(iced::Color::from_rgb(1.0, 0.5, 0.0))
                             .font(font) // Use a fixed-width font
                             .horizontal_alignment(iced::alignment::Horizontal::Left)
                     )
                     .push(
-                        Text::new(format!("{:<15}        {:<15}        {:<15}        {:<15}", get_formatted_bytes_string(*total_bytes_in), total_packets_in, get_formatted_bytes_string(*total_bytes_out), total_packets_out))
+                        Text::new(format!("{:<15}        {:<15}        {:<15}        {:<15}        {:<8}    {}", get_formatted_bytes_string(data_info.incoming_bytes), data_info.incoming_packets, get_formatted_bytes_string(data_info.outgoing_bytes), data_info.outgoing_packets, user_summary.distinct_hosts, top_processes))
                             .style(iced::Color::from_rgb(1.0, 0.5, 0.0))
                             .font(font) // Use a fixed-width font
                             .horizontal_alignment(iced::alignment::Horizontal::Left)
                             .width(Length::Fill),
                     );
-                
-                let int_uid = match uid.parse::<u32>() {
-                    Ok(uid) => uid,
-                    Err(_) => 0,
-                };
-                scroll_report = scroll_report.push(
-                    button(entry_row)
-                        .padding(2)
-                        .on_press(Message::ShowModal(MyModal::ThorttlingModal(int_uid, ThrottlingMode::User))) //ProcessThrottle(*pid)
-                        .style(ButtonStyleTuple(sniffer.style, ButtonType::Neutral).into()),
-                );
+
+                // connections without owner can't be throttled
+                let mut entry_button = button(entry_row)
+                    .padding(2)
+                    .style(ButtonStyleTuple(sniffer.style, ButtonType::Neutral).into());
+                if let Some(uid) = user_summary.uid {
+                    entry_button = entry_button.on_press(Message::ShowModal(MyModal::ThorttlingModal(uid, ThrottlingMode::User)));
+                }
+                scroll_report = scroll_report.push(entry_button);
             }
-            if !sorted_users_stats_vec.is_empty() {
+            if !user_entries.is_empty() {
                 col_report = col_report
-                    .push(Text::new("  UID       Total Bytes(in)       Total Packets(in)       Total Bytes(out)       Total Packets(out)")
+                    .push(Text::new("User            UID           Total Bytes(in)       Total Packets(in)       Total Bytes(out)       Total Packets(out)       Hosts       Top processes")
                         .vertical_alignment(Vertical::Center)
                         .horizontal_alignment(Horizontal::Center)
                         .height(Length::FillPortion(2))
@@ -616,8 +630,8 @@ fn lazy_report(sniffer: &Sniffer, report_view: ReportView) -> Row<'static, Messa
                         sniffer.language,
                         sniffer.page_number,
                         1,  // Update start entry num
-                        sorted_users_stats_vec.len(),  // Update end entry num
-                        sorted_users_stats_vec.len(),
+                        user_entries.len(),  // Update end entry num
+                        user_entries.len(),
                     ));
             } else {
                 col_report = col_report.push(
@@ -650,11 +664,22 @@ fn lazy_report(sniffer: &Sniffer, report_view: ReportView) -> Row<'static, Messa
                 )),
         )
         .push(
-            Container::new(get_button_open_report(
-                sniffer.style,
-                sniffer.language,
-                font,
-            ))
+            Container::new(
+                Column::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(get_button_open_report(
+                        sniffer.style,
+                        sniffer.language,
+                        font,
+                    ))
+                    .push(get_buttons_export(
+                        sniffer.export_result.as_ref(),
+                        sniffer.style,
+                        sniffer.language,
+                        font,
+                    )),
+            )
             .width(Length::FillPortion(1)),
         )
 }
@@ -699,7 +724,6 @@ fn lazy_report(sniffer: &Sniffer, report_view: ReportView) -> Row<'static, Messa
 //             Some(s) => (Some(s.uid), Some(s.associated_pids.clone())),
 //             None => (None, None),
 //         };
-//         dbg!(uid, pids, key.port1);
 //         let entry_color = get_connection_color(val.traffic_direction, sniffer.style);
 //         let entry_row = Row::new()
 //             .align_items(Alignment::Center)
@@ -898,6 +922,15 @@ fn filters_col(
                     style,
                 )),
         )
+        .push(filter_input(
+            FilterInputType::Interface,
+            &search_params.interface,
+            network_adapter_translation(language),
+            80.0,
+            search_params.clone(),
+            font,
+            style,
+        ))
 }
 
 fn filter_input(
@@ -929,6 +962,10 @@ fn filter_input(
                 as_name: String::new(),
                 ..search_params.clone()
             },
+            FilterInputType::Interface => SearchParameters {
+                interface: String::new(),
+                ..search_params.clone()
+            },
         },
         style,
         font,
@@ -953,6 +990,10 @@ fn filter_input(
                     as_name: new_value.trim().to_string(),
                     ..search_params.clone()
                 },
+                FilterInputType::Interface => SearchParameters {
+                    interface: new_value.trim().to_string(),
+                    ..search_params.clone()
+                },
             })
         })
         .padding([0, 5])
@@ -1067,6 +1108,65 @@ fn get_button_open_report(
         ))
 }
 
+fn get_buttons_export(
+    export_result: Option<&Result<PathBuf, String>>,
+    style: StyleType,
+    language: Language,
+    font: Font,
+) -> Column<'static, Message> {
+    let mut col = Column::new().spacing(5).align_items(Alignment::Center);
+    for format in ExportFormat::ALL {
+        let content = button(
+            Text::new(format!("Export {format}"))
+                .font(font)
+                .size(12)
+                .horizontal_alignment(alignment::Horizontal::Center)
+                .vertical_alignment(alignment::Vertical::Center),
+        )
+        .padding(2)
+        .height(Length::Fixed(25.0))
+        .width(Length::Fixed(95.0))
+        .style(ButtonStyleTuple(style, ButtonType::Standard).into())
+        .on_press(Message::ExportConnections(format));
+
+        let export_path = get_export_path(format).to_string_lossy().to_string();
+        col = col.push(
+            Tooltip::new(content, export_path, Position::Top)
+                .gap(5)
+                .font(font)
+                .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+                    ContainerStyleTuple(style, ContainerType::Tooltip),
+                )),
+        );
+    }
+    // the outcome of the last export is reported below the buttons
+    match export_result {
+        Some(Ok(path)) => {
+            col = col.push(
+                Text::new(format!(
+                    "{}\n{}",
+                    connections_exported_translation(language),
+                    path.display()
+                ))
+                .font(font)
+                .size(12)
+                .horizontal_alignment(alignment::Horizontal::Center),
+            );
+        }
+        Some(Err(err)) => {
+            col = col.push(
+                Text::new(format!("{}: {err}", export_failed_translation(language)))
+                    .font(font)
+                    .size(12)
+                    .horizontal_alignment(alignment::Horizontal::Center)
+                    .style(iced::theme::Text::Color(Color::from_rgb(0.8, 0.15, 0.15))),
+            );
+        }
+        None => {}
+    }
+    col
+}
+
 fn button_clear_filter(
     new_search_parameters: SearchParameters,
     style: StyleType,
@@ -7,4 +7,7 @@
 pub mod settings_language_page;
 pub mod settings_notifications_page;
 pub mod settings_style_page;
+pub mod throttling_dashboard_page;
+pub mod throttling_profiles_page;
+pub mod throttling_quotas_page;
 pub mod types;
@@ -19,17 +19,23 @@
 use crate::gui::styles::text::{TextStyleTuple, TextType};
 use crate::gui::types::message::Message;
 use crate::notifications::types::logged_notification::{
-    BytesThresholdExceeded, FavoriteTransmitted, LoggedNotification, PacketsThresholdExceeded,
+    BytesThresholdExceeded, CaptureFailed, FavoriteTransmitted, LoggedNotification,
+    PacketsThresholdExceeded, ProcessThresholdExceeded, QuotaExceeded, ThrottlingFailed,
 };
 use crate::translations::translations::{
     bytes_exceeded_translation, bytes_exceeded_value_translation, clear_all_translation,
-    favorite_transmitted_translation, incoming_translation, no_notifications_received_translation,
-    no_notifications_set_translation, only_last_30_translation, outgoing_translation,
-    packets_exceeded_translation, packets_exceeded_value_translation, per_second_translation,
-    threshold_translation,
+    favorite_transmitted_translation, incoming_translation, network_adapter_translation,
+    no_notifications_received_translation, no_notifications_set_translation,
+    only_last_30_translation, outgoing_translation, packets_exceeded_translation,
+    packets_exceeded_value_translation, per_second_translation, threshold_translation,
+};
+use crate::translations::translations_3::{
+    capture_failed_translation, data_quota_exceeded_translation, failed_step_translation,
+    per_period_translation, quota_target_translation, quota_translation, throttled_to_translation,
+    throttling_failed_translation,
 };
 use crate::utils::formatted_strings::get_formatted_bytes_string_with_b;
-use crate::{Language, RunningPage, Sniffer, StyleType};
+use crate::{ChartType, Language, RunningPage, Sniffer, StyleType};
 
 /// Computes the body of gui notifications page
 pub fn notifications_page(sniffer: &Sniffer) -> Container<Message> {
@@ -44,12 +50,14 @@ pub fn notifications_page(sniffer: &Sniffer) -> Container<Message> {
         [
             RunningPage::Overview,
             RunningPage::Inspect,
+            RunningPage::Throttling,
             RunningPage::Notifications,
         ],
-        &["d ", "5 ", "7 "],
+        &["d ", "5 ", "T ", "7 "],
         &[
             Message::ChangeRunningPage(RunningPage::Overview),
             Message::ChangeRunningPage(RunningPage::Inspect),
+            Message::ChangeRunningPage(RunningPage::Throttling),
             Message::TickInit,
         ],
         RunningPage::Notifications,
@@ -405,6 +413,302 @@ fn favorite_notification_log(
         ))
 }
 
+fn process_notification_log(
+    logged_notification: ProcessThresholdExceeded,
+    language: Language,
+    style: StyleType,
+) -> Container<'static, Message> {
+    let font = get_font(style);
+    let (threshold_str, value_str) = match logged_notification.threshold_type {
+        ChartType::Packets => (
+            logged_notification.threshold.to_string(),
+            logged_notification.value.to_string(),
+        ),
+        ChartType::Bytes => (
+            get_formatted_bytes_string_with_b(u128::from(logged_notification.threshold)),
+            get_formatted_bytes_string_with_b(logged_notification.value),
+        ),
+    };
+    let unit = logged_notification.threshold_type.get_radio_label(language);
+    let pids_str = logged_notification
+        .pids
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(", ");
+    let content = Row::new()
+        .spacing(30)
+        .align_items(Alignment::Center)
+        .height(Length::Fill)
+        .push(
+            Tooltip::new(
+                Text::new("e").font(ICONS).size(80),
+                "Process threshold exceeded",
+                Position::FollowCursor,
+            )
+            .font(font)
+            .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+                ContainerStyleTuple(style, ContainerType::Tooltip),
+            )),
+        )
+        .push(
+            Column::new()
+                .width(Length::Fixed(250.0))
+                .spacing(7)
+                .push(
+                    Row::new()
+                        .spacing(5)
+                        .push(Text::new("9").font(ICONS))
+                        .push(Text::new(logged_notification.timestamp).font(font)),
+                )
+                .push(
+                    Text::new("Process threshold exceeded")
+                        .style(TextStyleTuple(style, TextType::Title))
+                        .font(font),
+                )
+                .push(
+                    Text::new(format!(
+                        "{}: {threshold_str} {unit} {}",
+                        threshold_translation(language),
+                        per_second_translation(language)
+                    ))
+                    .size(FONT_SIZE_FOOTER)
+                    .style(TextStyleTuple(style, TextType::Subtitle))
+                    .font(font),
+                ),
+        )
+        .push(
+            Column::new()
+                .spacing(7)
+                .width(Length::Fill)
+                .push(
+                    Text::new(format!(
+                        "{} ({pids_str}): {value_str} {unit}",
+                        logged_notification.name
+                    ))
+                    .font(font),
+                )
+                .push(
+                    Text::new(format!(" - User: {}", logged_notification.users.join(", ")))
+                        .font(font),
+                )
+                .push(
+                    Text::new(format!(
+                        " - Top hosts: {}",
+                        logged_notification.top_hosts.join(", ")
+                    ))
+                    .font(font),
+                ),
+        );
+    Container::new(content)
+        .height(Length::Fixed(120.0))
+        .width(Length::Fixed(800.0))
+        .padding(10)
+        .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+            ContainerStyleTuple(style, ContainerType::BorderedRound),
+        ))
+}
+
+fn quota_notification_log(
+    logged_notification: QuotaExceeded,
+    language: Language,
+    style: StyleType,
+) -> Container<'static, Message> {
+    let font = get_font(style);
+    let quota_str = format!(
+        "{}: {} {}",
+        quota_translation(language),
+        get_formatted_bytes_string_with_b(u128::from(logged_notification.limit_bytes)),
+        per_period_translation(language, logged_notification.period)
+    );
+    let content = Row::new()
+        .spacing(30)
+        .align_items(Alignment::Center)
+        .height(Length::Fill)
+        .push(
+            Tooltip::new(
+                Text::new("f").font(ICONS).size(80),
+                data_quota_exceeded_translation(language),
+                Position::FollowCursor,
+            )
+            .font(font)
+            .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+                ContainerStyleTuple(style, ContainerType::Tooltip),
+            )),
+        )
+        .push(
+            Column::new()
+                .width(Length::Fixed(250.0))
+                .spacing(7)
+                .push(
+                    Row::new()
+                        .spacing(5)
+                        .push(Text::new("9").font(ICONS))
+                        .push(Text::new(logged_notification.timestamp).font(font)),
+                )
+                .push(
+                    Text::new(data_quota_exceeded_translation(language))
+                        .style(TextStyleTuple(style, TextType::Title))
+                        .font(font),
+                )
+                .push(
+                    Text::new(quota_str)
+                        .size(FONT_SIZE_FOOTER)
+                        .style(TextStyleTuple(style, TextType::Subtitle))
+                        .font(font),
+                ),
+        )
+        .push(
+            Column::new()
+                .spacing(7)
+                .width(Length::Fill)
+                .push(
+                    Text::new(quota_target_translation(
+                        language,
+                        &logged_notification.target,
+                    ))
+                    .font(font),
+                )
+                .push(
+                    Text::new(throttled_to_translation(language, logged_notification.kbps))
+                        .font(font),
+                ),
+        );
+    Container::new(content)
+        .height(Length::Fixed(120.0))
+        .width(Length::Fixed(800.0))
+        .padding(10)
+        .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+            ContainerStyleTuple(style, ContainerType::BorderedRound),
+        ))
+}
+
+fn throttling_failed_notification_log(
+    logged_notification: ThrottlingFailed,
+    language: Language,
+    style: StyleType,
+) -> Container<'static, Message> {
+    let font = get_font(style);
+    let content = Row::new()
+        .spacing(30)
+        .align_items(Alignment::Center)
+        .height(Length::Fill)
+        .push(
+            Tooltip::new(
+                Text::new("T").font(ICONS).size(80),
+                throttling_failed_translation(language),
+                Position::FollowCursor,
+            )
+            .font(font)
+            .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+                ContainerStyleTuple(style, ContainerType::Tooltip),
+            )),
+        )
+        .push(
+            Column::new()
+                .width(Length::Fixed(250.0))
+                .spacing(7)
+                .push(
+                    Row::new()
+                        .spacing(5)
+                        .push(Text::new("9").font(ICONS))
+                        .push(Text::new(logged_notification.timestamp).font(font)),
+                )
+                .push(
+                    Text::new(throttling_failed_translation(language))
+                        .style(TextStyleTuple(style, TextType::Title))
+                        .font(font),
+                ),
+        )
+        .push(
+            Column::new()
+                .spacing(7)
+                .width(Length::Fill)
+                .push(
+                    Text::new(failed_step_translation(language, &logged_notification.step))
+                        .font(font),
+                )
+                .push(
+                    Text::new(logged_notification.cause)
+                        .size(FONT_SIZE_FOOTER)
+                        .style(TextStyleTuple(style, TextType::Subtitle))
+                        .font(font),
+                ),
+        );
+    Container::new(content)
+        .height(Length::Fixed(120.0))
+        .width(Length::Fixed(800.0))
+        .padding(10)
+        .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+            ContainerStyleTuple(style, ContainerType::BorderedRound),
+        ))
+}
+
+fn capture_failed_notification_log(
+    logged_notification: CaptureFailed,
+    language: Language,
+    style: StyleType,
+) -> Container<'static, Message> {
+    let font = get_font(style);
+    let content = Row::new()
+        .spacing(30)
+        .align_items(Alignment::Center)
+        .height(Length::Fill)
+        .push(
+            Tooltip::new(
+                Text::new("T").font(ICONS).size(80),
+                capture_failed_translation(language),
+                Position::FollowCursor,
+            )
+            .font(font)
+            .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+                ContainerStyleTuple(style, ContainerType::Tooltip),
+            )),
+        )
+        .push(
+            Column::new()
+                .width(Length::Fixed(250.0))
+                .spacing(7)
+                .push(
+                    Row::new()
+                        .spacing(5)
+                        .push(Text::new("9").font(ICONS))
+                        .push(Text::new(logged_notification.timestamp).font(font)),
+                )
+                .push(
+                    Text::new(capture_failed_translation(language))
+                        .style(TextStyleTuple(style, TextType::Title))
+                        .font(font),
+                ),
+        )
+        .push(
+            Column::new()
+                .spacing(7)
+                .width(Length::Fill)
+                .push(
+                    Text::new(format!(
+                        "{}: {}",
+                        network_adapter_translation(language),
+                        logged_notification.device
+                    ))
+                    .font(font),
+                )
+                .push(
+                    Text::new(logged_notification.cause)
+                        .size(FONT_SIZE_FOOTER)
+                        .style(TextStyleTuple(style, TextType::Subtitle))
+                        .font(font),
+                ),
+        );
+    Container::new(content)
+        .height(Length::Fixed(120.0))
+        .width(Length::Fixed(800.0))
+        .padding(10)
+        .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+            ContainerStyleTuple(style, ContainerType::BorderedRound),
+        ))
+}
+
 fn get_button_clear_all(style: StyleType, language: Language) -> Tooltip<'static, Message> {
     let content = button(
         Text::new('h'.to_string())
@@ -457,6 +761,28 @@ fn lazy_logged_notifications(sniffer: &Sniffer) -> Column<'static, Message> {
                     sniffer.style,
                 )
             }
+            LoggedNotification::ProcessThresholdExceeded(process_threshold_exceeded) => {
+                process_notification_log(
+                    process_threshold_exceeded.clone(),
+                    sniffer.language,
+                    sniffer.style,
+                )
+            }
+            LoggedNotification::QuotaExceeded(quota_exceeded) => {
+                quota_notification_log(quota_exceeded.clone(), sniffer.language, sniffer.style)
+            }
+            LoggedNotification::ThrottlingFailed(throttling_failed) => {
+                throttling_failed_notification_log(
+                    throttling_failed.clone(),
+                    sniffer.language,
+                    sniffer.style,
+                )
+            }
+            LoggedNotification::CaptureFailed(capture_failed) => capture_failed_notification_log(
+                capture_failed.clone(),
+                sniffer.language,
+                sniffer.style,
+            ),
         });
     }
     ret_val
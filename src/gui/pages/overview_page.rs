@@ -24,6 +24,7 @@
 use crate::gui::types::sniffer::Sniffer;
 use crate::networking::types::data_info::DataInfo;
 use crate::networking::types::filters::Filters;
+use crate::networking::types::host_selector::HostSelector;
 use crate::networking::types::my_device::MyDevice;
 use crate::networking::types::search_parameters::SearchParameters;
 use crate::report::get_report_entries::{get_app_entries, get_host_entries};
@@ -78,12 +79,14 @@ pub fn overview_page(sniffer: &Sniffer) -> Container<Message> {
                     [
                         RunningPage::Overview,
                         RunningPage::Inspect,
+                        RunningPage::Throttling,
                         RunningPage::Notifications,
                     ],
-                    &["d ", "5 ", "7 "],
+                    &["d ", "5 ", "T ", "7 "],
                     &[
                         Message::TickInit,
                         Message::ChangeRunningPage(RunningPage::Inspect),
+                        Message::ChangeRunningPage(RunningPage::Throttling),
                         Message::ChangeRunningPage(RunningPage::Notifications),
                     ],
                     RunningPage::Overview,
@@ -373,6 +376,22 @@ fn col_host(width: f32, sniffer: &Sniffer) -> Column<'static, Message> {
             !data_info_host.is_favorite,
         ));
 
+        let host_selectors = HostSelector::get_host_selectors(host);
+        let mut limit_button = button(
+            Text::new('T'.to_string())
+                .font(ICONS)
+                .size(15)
+                .horizontal_alignment(Horizontal::Center)
+                .vertical_alignment(Vertical::Center),
+        )
+        .padding(0)
+        .height(Length::Fixed(FLAGS_WIDTH_BIG * 0.75))
+        .width(Length::Fixed(FLAGS_WIDTH_BIG * 0.75))
+        .style(ButtonStyleTuple(sniffer.style, ButtonType::Standard).into());
+        if !host_selectors.is_empty() {
+            limit_button = limit_button.on_press(Message::ShowHostThrottlingModal(host_selectors));
+        }
+
         // normalize smaller values
         if incoming_bar_len > 0.0 && incoming_bar_len < 3.0 {
             incoming_bar_len = 3.0;
@@ -437,6 +456,7 @@ fn col_host(width: f32, sniffer: &Sniffer) -> Column<'static, Message> {
             .align_items(Alignment::Center)
             .spacing(5)
             .push(star_button)
+            .push(limit_button)
             .push(get_flag_tooltip(
                 host.country,
                 FLAGS_WIDTH_BIG,
@@ -606,7 +626,7 @@ fn lazy_col_info(
         sniffer.language,
         sniffer.style,
         &sniffer.filters,
-        &sniffer.device,
+        &sniffer.get_sniffed_devices(),
     );
 
     let col_data_representation = col_data_representation(
@@ -670,19 +690,26 @@ fn col_device_filters(
     language: Language,
     style: StyleType,
     filters: &Filters,
-    device: &MyDevice,
+    devices: &[MyDevice],
 ) -> Column<'static, Message> {
-    #[cfg(not(target_os = "windows"))]
-    let adapter_info = &device.name;
-    #[cfg(target_os = "windows")]
-    let adapter_name = &device.name;
-    #[cfg(target_os = "windows")]
-    let adapter_info = device.desc.as_ref().unwrap_or(adapter_name);
+    let adapter_info = devices
+        .iter()
+        .map(|device| {
+            #[cfg(not(target_os = "windows"))]
+            let adapter_info = &device.name;
+            #[cfg(target_os = "windows")]
+            let adapter_name = &device.name;
+            #[cfg(target_os = "windows")]
+            let adapter_info = device.desc.as_ref().unwrap_or(adapter_name);
+            adapter_info.clone()
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
 
     Column::new()
         .push(TextType::highlighted_subtitle_with_desc(
             network_adapter_translation(language),
-            adapter_info,
+            &adapter_info,
             style,
         ))
         .push(vertical_space(15))
@@ -10,7 +10,8 @@
 use iced::{Alignment, Length};
 
 use crate::gui::components::radio::{
-    sound_bytes_threshold_radios, sound_favorite_radios, sound_packets_threshold_radios, sound_process_threshold_radios,
+    process_threshold_type_radios, sound_bytes_threshold_radios, sound_favorite_radios,
+    sound_packets_threshold_radios, sound_process_threshold_radios,
 };
 use crate::gui::components::tab::get_settings_tabs;
 use crate::gui::pages::types::settings_page::SettingsPage;
@@ -383,7 +384,14 @@ fn get_process_notify(
         let input_row = Row::new()
             .push(horizontal_space(Fixed(50.0)))
             .push(Text::new(format!("{}: ", threshold_translation(language))).font(font))
-            .push(input_group_process(process_notification, style, language));
+            .push(input_group_process(process_notification, style, language))
+            .push(horizontal_space(Fixed(20.0)))
+            .push(process_threshold_type_radios(
+                process_notification,
+                font,
+                style,
+                language,
+            ));
         let sound_row =
             Row::new()
                 .push(horizontal_space(Fixed(50.0)))
@@ -0,0 +1,314 @@
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::scrollable::Direction;
+use iced::widget::{button, vertical_space, Button, Rule};
+use iced::widget::{Column, Container, Row, Scrollable, Text};
+use iced::{Alignment, Font, Length};
+
+use crate::gui::components::tab::get_pages_tabs;
+use crate::gui::components::types::my_modal::MyModal;
+use crate::gui::components::types::throttling_mode::ThrottlingMode;
+use crate::gui::styles::button::{ButtonStyleTuple, ButtonType};
+use crate::gui::styles::container::{ContainerStyleTuple, ContainerType};
+use crate::gui::styles::rule::{RuleStyleTuple, RuleType};
+use crate::gui::styles::scrollbar::{ScrollbarStyleTuple, ScrollbarType};
+use crate::gui::styles::style_constants::{get_font, ICONS};
+use crate::gui::types::message::Message;
+use crate::networking::types::host_selector::HostSelector;
+use crate::networking::types::ip_subnet::IpSubnet;
+use crate::networking::types::port_selector::PortSelector;
+use crate::networking::types::traffic_control::{ThrottlingLimit, ThrottlingTarget};
+use crate::networking::types::traffic_direction::TrafficDirection;
+use crate::translations::translations_3::{
+    active_profile_translation, profiles_translation, quotas_translation,
+};
+use crate::utils::formatted_strings::get_formatted_bytes_string_with_b;
+use crate::{RunningPage, Sniffer, StyleType};
+
+/// Computes the body of gui throttling page, listing all the limits currently applied
+pub fn throttling_dashboard_page(sniffer: &Sniffer) -> Container<Message> {
+    let font = get_font(sniffer.style);
+
+    let mut tab_and_body = Column::new().height(Length::Fill);
+
+    let tabs = get_pages_tabs(
+        [
+            RunningPage::Overview,
+            RunningPage::Inspect,
+            RunningPage::Throttling,
+            RunningPage::Notifications,
+        ],
+        &["d ", "5 ", "T ", "7 "],
+        &[
+            Message::ChangeRunningPage(RunningPage::Overview),
+            Message::ChangeRunningPage(RunningPage::Inspect),
+            Message::TickInit,
+            Message::ChangeRunningPage(RunningPage::Notifications),
+        ],
+        RunningPage::Throttling,
+        sniffer.style,
+        sniffer.language,
+        sniffer.unread_notifications,
+    );
+
+    tab_and_body = tab_and_body.push(tabs);
+
+    let mut profile_row = Row::new().spacing(10).align_items(Alignment::Center);
+    if let Some(profile) = &sniffer.active_profile {
+        profile_row = profile_row.push(
+            Text::new(format!(
+                "{}: {profile}",
+                active_profile_translation(sniffer.language)
+            ))
+            .font(font),
+        );
+    }
+    profile_row = profile_row
+        .push(action_button(
+            profiles_translation(sniffer.language),
+            Message::ShowModal(MyModal::ProfilesModal),
+            sniffer.style,
+            font,
+        ))
+        .push(action_button(
+            quotas_translation(sniffer.language),
+            Message::ShowModal(MyModal::QuotasModal),
+            sniffer.style,
+            font,
+        ));
+    tab_and_body = tab_and_body.push(Container::new(profile_row).padding([10, 10, 0, 10]));
+
+    let limits = sniffer
+        .traffic_controller
+        .lock()
+        .unwrap()
+        .get_throttling_limits();
+    let body = if limits.is_empty() {
+        body_no_limits(font)
+    } else {
+        let mut scroll_limits = Column::new();
+        for (target, limit) in limits {
+            scroll_limits = scroll_limits.push(limit_row(sniffer, target, limit, font));
+        }
+        Column::new()
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .padding(10)
+            .push(
+                Text::new(format!(
+                    "{:<35} {:>12} {:>10} {:>15} {:>15}",
+                    "Target", "Rate", "Burst", "Egress", "Ingress"
+                ))
+                .vertical_alignment(Vertical::Center)
+                .font(font),
+            )
+            .push(
+                Rule::horizontal(5).style(<RuleStyleTuple as Into<iced::theme::Rule>>::into(
+                    RuleStyleTuple(sniffer.style, RuleType::Standard),
+                )),
+            )
+            .push(
+                Scrollable::new(scroll_limits)
+                    .width(Length::Fill)
+                    .direction(Direction::Vertical(ScrollbarType::properties()))
+                    .style(
+                        <ScrollbarStyleTuple as Into<iced::theme::Scrollable>>::into(
+                            ScrollbarStyleTuple(sniffer.style, ScrollbarType::Standard),
+                        ),
+                    ),
+            )
+    };
+
+    Container::new(tab_and_body.push(body))
+        .height(Length::Fill)
+        .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+            ContainerStyleTuple(sniffer.style, ContainerType::Standard),
+        ))
+}
+
+fn limit_row(
+    sniffer: &Sniffer,
+    target: ThrottlingTarget,
+    limit: ThrottlingLimit,
+    font: Font,
+) -> Row<'static, Message> {
+    let (egress, ingress) = sniffer
+        .traffic_controller
+        .lock()
+        .unwrap()
+        .get_throughput(target);
+    let format_throughput = |throughput: Option<u64>| {
+        throughput.map_or("-".to_string(), |bytes| {
+            format!("{}/s", get_formatted_bytes_string_with_b(u128::from(bytes)))
+        })
+    };
+    let burst = limit
+        .burst_kb
+        .map_or("default".to_string(), |burst_kb| format!("{burst_kb} kB"));
+
+    let mut row = Row::new().align_items(Alignment::Center).spacing(10).push(
+        Text::new(format!(
+            "{:<35} {:>12} {:>10} {:>15} {:>15}",
+            get_target_label(sniffer, target),
+            format!("{} kB/s", limit.kbps),
+            burst,
+            format_throughput(egress),
+            format_throughput(ingress),
+        ))
+        .font(font),
+    );
+
+    // the interface limit can only be changed from the initial page
+    if let Some((id, throttling_mode)) = get_throttling_mode(target) {
+        row = row
+            .push(action_button(
+                "Edit",
+                Message::ShowModal(MyModal::ThorttlingModal(id, throttling_mode)),
+                sniffer.style,
+                font,
+            ))
+            .push(action_button(
+                "Unthrottle",
+                Message::Unthrottle(id, throttling_mode),
+                sniffer.style,
+                font,
+            ));
+    } else if let Some((ports, direction)) = get_ports_and_direction(target) {
+        // port ranges can only be edited from the throttling configuration file
+        row = row.push(action_button(
+            "Unthrottle",
+            Message::UnthrottlePorts(ports, direction),
+            sniffer.style,
+            font,
+        ));
+    } else if let Some(selector) =
+        get_subnet(target).and_then(|subnet| get_host_selector(sniffer, subnet))
+    {
+        row = row
+            .push(action_button(
+                "Edit",
+                Message::ShowHostThrottlingModal(vec![selector.clone()]),
+                sniffer.style,
+                font,
+            ))
+            .push(action_button(
+                "Unthrottle",
+                Message::ThrottleHost(None, selector),
+                sniffer.style,
+                font,
+            ));
+    }
+    row
+}
+
+fn action_button(
+    label: &'static str,
+    message: Message,
+    style: StyleType,
+    font: Font,
+) -> Button<'static, Message> {
+    button(
+        Text::new(label)
+            .horizontal_alignment(Horizontal::Center)
+            .vertical_alignment(Vertical::Center)
+            .font(font)
+            .size(15),
+    )
+    .padding([2, 10])
+    .style(ButtonStyleTuple(style, ButtonType::Standard).into())
+    .on_press(message)
+}
+
+/// Returns a description of the throttled target, including process and user names if known
+fn get_target_label(sniffer: &Sniffer, target: ThrottlingTarget) -> String {
+    let info_traffic = sniffer.info_traffic.lock().unwrap();
+    let label = match target {
+        ThrottlingTarget::Pid(pid) => match info_traffic.processes.get(&pid) {
+            Some(process) => format!("Process {pid} ({})", process.name),
+            None => format!("Process {pid}"),
+        },
+        ThrottlingTarget::Uid(uid) => match info_traffic.usernames.get(&uid) {
+            Some(username) => format!("User {uid} ({username})"),
+            None => format!("User {uid}"),
+        },
+        ThrottlingTarget::PortEgress(ports) => match ports.get_single_port() {
+            Some(port) => format!("Port {port} (egress)"),
+            None => format!("Ports {ports} (egress)"),
+        },
+        ThrottlingTarget::PortIngress(ports) => match ports.get_single_port() {
+            Some(port) => format!("Port {port} (ingress)"),
+            None => format!("Ports {ports} (ingress)"),
+        },
+        ThrottlingTarget::HostEgress(subnet) => get_host_label(sniffer, subnet, "egress"),
+        ThrottlingTarget::HostIngress(subnet) => get_host_label(sniffer, subnet, "ingress"),
+        ThrottlingTarget::Interface => format!("Interface {} (ingress)", sniffer.device.name),
+    };
+    label.chars().take(35).collect()
+}
+
+/// Returns a description of a throttled remote subnet, including the domain or the
+/// Autonomous System it was throttled for
+fn get_host_label(sniffer: &Sniffer, subnet: IpSubnet, direction: &str) -> String {
+    match get_host_selector(sniffer, subnet) {
+        Some(HostSelector::Subnet(_)) | None => format!("Host {subnet} ({direction})"),
+        Some(selector) => format!("{selector}: {subnet} ({direction})"),
+    }
+}
+
+/// Returns the remote subnet of the targets made of remote hosts
+fn get_subnet(target: ThrottlingTarget) -> Option<IpSubnet> {
+    match target {
+        ThrottlingTarget::HostEgress(subnet) | ThrottlingTarget::HostIngress(subnet) => {
+            Some(subnet)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the selector of the rule a remote subnet was throttled for
+fn get_host_selector(sniffer: &Sniffer, subnet: IpSubnet) -> Option<HostSelector> {
+    sniffer
+        .throttled_hosts
+        .iter()
+        .find(|(_, subnets)| subnets.contains(&subnet))
+        .map(|(selector, _)| selector.clone())
+}
+
+/// Returns the identifier and the mode used to edit or remove the limit of a target
+fn get_throttling_mode(target: ThrottlingTarget) -> Option<(u32, ThrottlingMode)> {
+    match target {
+        ThrottlingTarget::Pid(pid) => Some((pid, ThrottlingMode::Process)),
+        ThrottlingTarget::Uid(uid) => Some((uid, ThrottlingMode::User)),
+        ThrottlingTarget::PortEgress(ports) | ThrottlingTarget::PortIngress(ports) => ports
+            .get_single_port()
+            .map(|port| (u32::from(port), ThrottlingMode::Port)),
+        ThrottlingTarget::HostEgress(_)
+        | ThrottlingTarget::HostIngress(_)
+        | ThrottlingTarget::Interface => None,
+    }
+}
+
+/// Returns the ports and the direction of the targets made of port ranges
+fn get_ports_and_direction(target: ThrottlingTarget) -> Option<(PortSelector, TrafficDirection)> {
+    match target {
+        ThrottlingTarget::PortEgress(ports) => Some((ports, TrafficDirection::Outgoing)),
+        ThrottlingTarget::PortIngress(ports) => Some((ports, TrafficDirection::Incoming)),
+        _ => None,
+    }
+}
+
+fn body_no_limits(font: Font) -> Column<'static, Message> {
+    Column::new()
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding(20)
+        .align_items(Alignment::Center)
+        .push(vertical_space(Length::FillPortion(1)))
+        .push(Text::new('V'.to_string()).font(ICONS).size(60))
+        .push(vertical_space(Length::Fixed(15.0)))
+        .push(
+            Text::new("No throttling limits are applied")
+                .horizontal_alignment(Horizontal::Center)
+                .font(font),
+        )
+        .push(vertical_space(Length::FillPortion(2)))
+}
@@ -133,3 +133,71 @@ pub fn throttling_page(sniffer: &Sniffer, id: u32, throttling_mode: ThrottlingMo
             ContainerStyleTuple(sniffer.style, ContainerType::Standard),
         ))
 }
+
+/// Computes the modal to throttle the remote hosts, offering a choice among the selectors
+/// stored in the sniffer (e.g. the address of a host, its subnet, its domain or its ASN)
+pub fn host_throttling_page(sniffer: &Sniffer) -> Container<Message> {
+    let font = get_font(sniffer.style);
+    let style = sniffer.style;
+    let bandwidth = sniffer.throttling_bandwidth.trim().parse::<u32>().ok();
+
+    let mut column = Column::new()
+        .width(Length::Fixed(600.0))
+        .spacing(10)
+        .padding(10)
+        .align_items(Alignment::Center)
+        .push(Text::new("Please enter throttling value for host").size(20))
+        .push(
+            TextInput::new("Throttling value", &sniffer.throttling_bandwidth)
+                .on_input(Message::ThrottlingBandwidth)
+                .padding([10, 10, 10, 10])
+                .font(font)
+                .width(Length::Fixed(200.0))
+                .style(<TextInputStyleTuple as Into<iced::theme::TextInput>>::into(
+                    TextInputStyleTuple(style, TextInputType::Standard),
+                )),
+        );
+
+    for selector in &sniffer.host_throttling_selectors {
+        let mut throttle_button = button(
+            Text::new("Throttle")
+                .horizontal_alignment(Horizontal::Center)
+                .vertical_alignment(Vertical::Center)
+                .font(font)
+                .size(15),
+        )
+        .style(ButtonStyleTuple(style, ButtonType::Standard).into())
+        .width(Length::Fixed(120.0));
+        if bandwidth.is_some() {
+            throttle_button =
+                throttle_button.on_press(Message::ThrottleHost(bandwidth, selector.clone()));
+        }
+        column = column.push(
+            Row::new()
+                .spacing(10)
+                .align_items(Alignment::Center)
+                .push(
+                    Text::new(selector.to_string())
+                        .font(font)
+                        .width(Length::Fill),
+                )
+                .push(throttle_button)
+                .push(
+                    button(
+                        Text::new("Unthrottle")
+                            .horizontal_alignment(Horizontal::Center)
+                            .vertical_alignment(Vertical::Center)
+                            .font(font)
+                            .size(15),
+                    )
+                    .style(ButtonStyleTuple(style, ButtonType::Standard).into())
+                    .on_press(Message::ThrottleHost(None, selector.clone()))
+                    .width(Length::Fixed(120.0)),
+                ),
+        );
+    }
+
+    Container::new(column).style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+        ContainerStyleTuple(sniffer.style, ContainerType::Standard),
+    ))
+}
@@ -0,0 +1,295 @@
+use chrono::Weekday;
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::scrollable::Direction;
+use iced::widget::{button, Button, Checkbox, Rule};
+use iced::widget::{Column, Container, Row, Scrollable, Text, TextInput};
+use iced::{Alignment, Font, Length};
+
+use crate::configs::types::throttling_profile::{ThrottlingProfile, ThrottlingProfileDraft};
+use crate::gui::styles::button::{ButtonStyleTuple, ButtonType};
+use crate::gui::styles::checkbox::{CheckboxStyleTuple, CheckboxType};
+use crate::gui::styles::container::{ContainerStyleTuple, ContainerType};
+use crate::gui::styles::rule::{RuleStyleTuple, RuleType};
+use crate::gui::styles::scrollbar::{ScrollbarStyleTuple, ScrollbarType};
+use crate::gui::styles::style_constants::get_font;
+use crate::gui::styles::text_input::{TextInputStyleTuple, TextInputType};
+use crate::gui::types::message::Message;
+use crate::translations::translations_3::{
+    add_current_limits_translation, edit_translation, end_time_translation, every_day_translation,
+    interface_limit_translation, name_translation, new_translation, no_profiles_translation,
+    remove_translation, rules_translation, save_translation, start_time_translation,
+    throttling_profiles_translation, weekday_translation,
+};
+use crate::{Language, Sniffer, StyleType};
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Computes the body of the throttling profiles modal, listing the existing profiles
+/// and the form to create or edit one
+pub fn throttling_profiles_page(sniffer: &Sniffer) -> Container<'_, Message> {
+    let font = get_font(sniffer.style);
+    let style = sniffer.style;
+    let language = sniffer.language;
+    let profiles = &sniffer.config_throttling.profiles;
+
+    let mut profiles_column = Column::new().spacing(5);
+    if profiles.is_empty() {
+        profiles_column =
+            profiles_column.push(Text::new(no_profiles_translation(language)).font(font));
+    }
+    for (index, profile) in profiles.iter().enumerate() {
+        profiles_column = profiles_column.push(profile_row(index, profile, style, language, font));
+    }
+
+    let column = Column::new()
+        .width(Length::Fixed(700.0))
+        .spacing(10)
+        .padding(10)
+        .align_items(Alignment::Center)
+        .push(Text::new(throttling_profiles_translation(language)).size(20))
+        .push(profiles_column)
+        .push(
+            Rule::horizontal(10).style(<RuleStyleTuple as Into<iced::theme::Rule>>::into(
+                RuleStyleTuple(style, RuleType::Standard),
+            )),
+        )
+        .push(profile_form(sniffer, font));
+
+    Container::new(
+        Scrollable::new(column)
+            .height(Length::Fixed(500.0))
+            .direction(Direction::Vertical(ScrollbarType::properties()))
+            .style(
+                <ScrollbarStyleTuple as Into<iced::theme::Scrollable>>::into(ScrollbarStyleTuple(
+                    style,
+                    ScrollbarType::Standard,
+                )),
+            ),
+    )
+    .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+        ContainerStyleTuple(style, ContainerType::Standard),
+    ))
+}
+
+fn profile_row(
+    index: usize,
+    profile: &ThrottlingProfile,
+    style: StyleType,
+    language: Language,
+    font: Font,
+) -> Row<'static, Message> {
+    let schedule = &profile.schedule;
+    let weekdays = if schedule.weekdays.is_empty() {
+        every_day_translation(language).to_string()
+    } else {
+        schedule
+            .weekdays
+            .iter()
+            .map(|day| weekday_translation(language, *day))
+            .collect::<Vec<&str>>()
+            .join(" ")
+    };
+    Row::new()
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .push(
+            Text::new(format!(
+                "{}: {weekdays} {}-{}",
+                profile.name,
+                schedule.start.format("%H:%M"),
+                schedule.end.format("%H:%M")
+            ))
+            .font(font)
+            .width(Length::Fill),
+        )
+        .push(action_button(
+            edit_translation(language),
+            Some(Message::UpdateProfileDraft(
+                ThrottlingProfileDraft::from_profile(index, profile),
+            )),
+            style,
+            font,
+        ))
+        .push(action_button(
+            remove_translation(language),
+            Some(Message::RemoveProfile(index)),
+            style,
+            font,
+        ))
+}
+
+fn profile_form(sniffer: &Sniffer, font: Font) -> Column<'_, Message> {
+    let style = sniffer.style;
+    let language = sniffer.language;
+    let draft = &sniffer.profile_draft;
+
+    let mut weekdays_row = Row::new().spacing(10).align_items(Alignment::Center);
+    for weekday in WEEKDAYS {
+        let draft = draft.clone();
+        weekdays_row = weekdays_row.push(
+            Checkbox::new(
+                weekday_translation(language, weekday),
+                draft.weekdays.contains(&weekday),
+                move |selected| {
+                    let mut draft = draft.clone();
+                    draft.set_weekday(weekday, selected);
+                    Message::UpdateProfileDraft(draft)
+                },
+            )
+            .size(18)
+            .font(font)
+            .style(<CheckboxStyleTuple as Into<iced::theme::Checkbox>>::into(
+                CheckboxStyleTuple(style, CheckboxType::Standard),
+            )),
+        );
+    }
+
+    let mut rules_column = Column::new()
+        .spacing(5)
+        .push(Text::new(rules_translation(language)).font(font));
+    for (index, rule) in draft.rules.iter().enumerate() {
+        let mut without_rule = draft.clone();
+        without_rule.rules.remove(index);
+        rules_column = rules_column.push(
+            Row::new()
+                .spacing(10)
+                .align_items(Alignment::Center)
+                .push(
+                    Text::new(format!("{}: {} KB/s", rule.target, rule.kbps))
+                        .font(font)
+                        .width(Length::Fill),
+                )
+                .push(action_button(
+                    remove_translation(language),
+                    Some(Message::UpdateProfileDraft(without_rule)),
+                    style,
+                    font,
+                )),
+        );
+    }
+    let mut with_current_limits = draft.clone();
+    with_current_limits.add_rules(&sniffer.config_throttling.rules);
+
+    let save_message = draft
+        .to_profile(&sniffer.config_throttling.profiles)
+        .map(|_| Message::SaveProfile);
+
+    Column::new()
+        .spacing(10)
+        .push(draft_input(
+            name_translation(language),
+            &draft.name,
+            draft,
+            |draft, name| draft.name = name,
+            style,
+            font,
+        ))
+        .push(weekdays_row)
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(draft_input(
+                    start_time_translation(language),
+                    &draft.start,
+                    draft,
+                    |draft, start| draft.start = start,
+                    style,
+                    font,
+                ))
+                .push(draft_input(
+                    end_time_translation(language),
+                    &draft.end,
+                    draft,
+                    |draft, end| draft.end = end,
+                    style,
+                    font,
+                )),
+        )
+        .push(draft_input(
+            interface_limit_translation(language),
+            &draft.interface_kbps,
+            draft,
+            |draft, kbps| draft.interface_kbps = kbps,
+            style,
+            font,
+        ))
+        .push(rules_column)
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(action_button(
+                    add_current_limits_translation(language),
+                    Some(Message::UpdateProfileDraft(with_current_limits)),
+                    style,
+                    font,
+                ))
+                .push(action_button(
+                    new_translation(language),
+                    Some(Message::UpdateProfileDraft(
+                        ThrottlingProfileDraft::default(),
+                    )),
+                    style,
+                    font,
+                ))
+                .push(action_button(
+                    save_translation(language),
+                    save_message,
+                    style,
+                    font,
+                )),
+        )
+}
+
+/// Text input editing a field of the draft profile
+fn draft_input<'a>(
+    placeholder: &str,
+    value: &str,
+    draft: &ThrottlingProfileDraft,
+    update: fn(&mut ThrottlingProfileDraft, String),
+    style: StyleType,
+    font: Font,
+) -> TextInput<'a, Message> {
+    let draft = draft.clone();
+    TextInput::new(placeholder, value)
+        .on_input(move |input| {
+            let mut draft = draft.clone();
+            update(&mut draft, input);
+            Message::UpdateProfileDraft(draft)
+        })
+        .padding([5, 10])
+        .font(font)
+        .width(Length::Fill)
+        .style(<TextInputStyleTuple as Into<iced::theme::TextInput>>::into(
+            TextInputStyleTuple(style, TextInputType::Standard),
+        ))
+}
+
+/// Button sending the given message, disabled if there is none
+fn action_button(
+    label: &'static str,
+    message: Option<Message>,
+    style: StyleType,
+    font: Font,
+) -> Button<'static, Message> {
+    let mut button = button(
+        Text::new(label)
+            .horizontal_alignment(Horizontal::Center)
+            .vertical_alignment(Vertical::Center)
+            .font(font)
+            .size(15),
+    )
+    .padding([2, 10])
+    .style(ButtonStyleTuple(style, ButtonType::Standard).into());
+    if let Some(message) = message {
+        button = button.on_press(message);
+    }
+    button
+}
@@ -0,0 +1,263 @@
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::scrollable::Direction;
+use iced::widget::{button, Button, Radio, Rule};
+use iced::widget::{Column, Container, Row, Scrollable, Text, TextInput};
+use iced::{Alignment, Color, Font, Length};
+
+use crate::configs::types::data_quota::{DataQuota, DataQuotaDraft, QuotaPeriod, QuotaTargetKind};
+use crate::gui::styles::button::{ButtonStyleTuple, ButtonType};
+use crate::gui::styles::container::{ContainerStyleTuple, ContainerType};
+use crate::gui::styles::radio::{RadioStyleTuple, RadioType};
+use crate::gui::styles::rule::{RuleStyleTuple, RuleType};
+use crate::gui::styles::scrollbar::{ScrollbarStyleTuple, ScrollbarType};
+use crate::gui::styles::style_constants::get_font;
+use crate::gui::styles::text::{TextStyleTuple, TextType};
+use crate::gui::styles::text_input::{TextInputStyleTuple, TextInputType};
+use crate::gui::types::message::Message;
+use crate::translations::translations_3::{
+    data_quota_exceeded_translation, data_quotas_translation, edit_translation,
+    exceeded_bandwidth_translation, new_translation, no_quotas_translation, per_period_translation,
+    quota_limit_translation, quota_period_translation, quota_target_kind_translation,
+    quota_target_translation, remove_translation, save_translation, target_translation,
+};
+use crate::utils::formatted_strings::get_formatted_bytes_string_with_b;
+use crate::{Language, Sniffer, StyleType};
+
+/// Computes the body of the data quotas modal, listing the existing quotas with their usage
+/// and the form to create or edit one
+pub fn throttling_quotas_page(sniffer: &Sniffer) -> Container<'_, Message> {
+    let font = get_font(sniffer.style);
+    let style = sniffer.style;
+    let language = sniffer.language;
+    let quotas = &sniffer.config_throttling.quotas;
+
+    let mut quotas_column = Column::new().spacing(5);
+    if quotas.is_empty() {
+        quotas_column = quotas_column.push(Text::new(no_quotas_translation(language)).font(font));
+    }
+    for (index, quota) in quotas.iter().enumerate() {
+        quotas_column = quotas_column.push(quota_row(index, quota, style, language, font));
+    }
+
+    let column = Column::new()
+        .width(Length::Fixed(700.0))
+        .spacing(10)
+        .padding(10)
+        .align_items(Alignment::Center)
+        .push(Text::new(data_quotas_translation(language)).size(20))
+        .push(quotas_column)
+        .push(
+            Rule::horizontal(10).style(<RuleStyleTuple as Into<iced::theme::Rule>>::into(
+                RuleStyleTuple(style, RuleType::Standard),
+            )),
+        )
+        .push(quota_form(sniffer, font));
+
+    Container::new(
+        Scrollable::new(column)
+            .height(Length::Fixed(500.0))
+            .direction(Direction::Vertical(ScrollbarType::properties()))
+            .style(
+                <ScrollbarStyleTuple as Into<iced::theme::Scrollable>>::into(ScrollbarStyleTuple(
+                    style,
+                    ScrollbarType::Standard,
+                )),
+            ),
+    )
+    .style(<ContainerStyleTuple as Into<iced::theme::Container>>::into(
+        ContainerStyleTuple(style, ContainerType::Standard),
+    ))
+}
+
+fn quota_row(
+    index: usize,
+    quota: &DataQuota,
+    style: StyleType,
+    language: Language,
+    font: Font,
+) -> Row<'static, Message> {
+    let mut description = Column::new()
+        .spacing(3)
+        .width(Length::Fill)
+        .push(Text::new(quota_target_translation(language, &quota.target)).font(font))
+        .push(
+            Text::new(format!(
+                "{} / {} {}",
+                get_formatted_bytes_string_with_b(u128::from(quota.usage.bytes)),
+                get_formatted_bytes_string_with_b(u128::from(quota.limit_bytes)),
+                per_period_translation(language, quota.period)
+            ))
+            .style(TextStyleTuple(style, TextType::Subtitle))
+            .font(font),
+        );
+    if quota.usage.exceeded {
+        description = description.push(
+            Text::new(data_quota_exceeded_translation(language))
+                .style(iced::theme::Text::Color(Color::from_rgb(0.8, 0.15, 0.15)))
+                .font(font),
+        );
+    }
+    Row::new()
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .push(description)
+        .push(action_button(
+            edit_translation(language),
+            Some(Message::UpdateQuotaDraft(DataQuotaDraft::from_quota(
+                index, quota,
+            ))),
+            style,
+            font,
+        ))
+        .push(action_button(
+            remove_translation(language),
+            Some(Message::RemoveQuota(index)),
+            style,
+            font,
+        ))
+}
+
+fn quota_form(sniffer: &Sniffer, font: Font) -> Column<'_, Message> {
+    let style = sniffer.style;
+    let language = sniffer.language;
+    let draft = &sniffer.quota_draft;
+
+    let mut kinds_row = Row::new().spacing(20).align_items(Alignment::Center);
+    for kind in QuotaTargetKind::ALL {
+        let mut with_kind = draft.clone();
+        with_kind.target_kind = kind;
+        kinds_row = kinds_row.push(
+            Radio::new(
+                quota_target_kind_translation(language, kind),
+                kind,
+                Some(draft.target_kind),
+                move |_| Message::UpdateQuotaDraft(with_kind),
+            )
+            .spacing(7)
+            .font(font)
+            .size(15)
+            .style(<RadioStyleTuple as Into<iced::theme::Radio>>::into(
+                RadioStyleTuple(style, RadioType::Standard),
+            )),
+        );
+    }
+
+    let mut periods_row = Row::new().spacing(20).align_items(Alignment::Center);
+    for period in QuotaPeriod::ALL {
+        let mut with_period = draft.clone();
+        with_period.period = period;
+        periods_row = periods_row.push(
+            Radio::new(
+                quota_period_translation(language, period),
+                period,
+                Some(draft.period),
+                move |_| Message::UpdateQuotaDraft(with_period),
+            )
+            .spacing(7)
+            .font(font)
+            .size(15)
+            .style(<RadioStyleTuple as Into<iced::theme::Radio>>::into(
+                RadioStyleTuple(style, RadioType::Standard),
+            )),
+        );
+    }
+
+    let save_message = draft
+        .to_quota(&sniffer.config_throttling.quotas)
+        .map(|_| Message::SaveQuota);
+
+    Column::new()
+        .spacing(10)
+        .push(kinds_row)
+        .push(draft_input(
+            target_translation(language),
+            &draft.target,
+            draft,
+            |draft, target| draft.target = target,
+            style,
+            font,
+        ))
+        .push(periods_row)
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(draft_input(
+                    quota_limit_translation(language),
+                    &draft.limit,
+                    draft,
+                    |draft, limit| draft.limit = limit,
+                    style,
+                    font,
+                ))
+                .push(draft_input(
+                    exceeded_bandwidth_translation(language),
+                    &draft.kbps,
+                    draft,
+                    |draft, kbps| draft.kbps = kbps,
+                    style,
+                    font,
+                )),
+        )
+        .push(
+            Row::new()
+                .spacing(10)
+                .push(action_button(
+                    new_translation(language),
+                    Some(Message::UpdateQuotaDraft(DataQuotaDraft::default())),
+                    style,
+                    font,
+                ))
+                .push(action_button(
+                    save_translation(language),
+                    save_message,
+                    style,
+                    font,
+                )),
+        )
+}
+
+/// Text input editing a field of the draft quota
+fn draft_input<'a>(
+    placeholder: &str,
+    value: &str,
+    draft: &DataQuotaDraft,
+    update: fn(&mut DataQuotaDraft, String),
+    style: StyleType,
+    font: Font,
+) -> TextInput<'a, Message> {
+    let draft = draft.clone();
+    TextInput::new(placeholder, value)
+        .on_input(move |input| {
+            let mut draft = draft.clone();
+            update(&mut draft, input);
+            Message::UpdateQuotaDraft(draft)
+        })
+        .padding([5, 10])
+        .font(font)
+        .width(Length::Fill)
+        .style(<TextInputStyleTuple as Into<iced::theme::TextInput>>::into(
+            TextInputStyleTuple(style, TextInputType::Standard),
+        ))
+}
+
+/// Button sending the given message, disabled if there is none
+fn action_button(
+    label: &'static str,
+    message: Option<Message>,
+    style: StyleType,
+    font: Font,
+) -> Button<'static, Message> {
+    let mut button = button(
+        Text::new(label)
+            .horizontal_alignment(Horizontal::Center)
+            .vertical_alignment(Vertical::Center)
+            .font(font)
+            .size(15),
+    )
+    .padding([2, 10])
+    .style(ButtonStyleTuple(style, ButtonType::Standard).into());
+    if let Some(message) = message {
+        button = button.on_press(message);
+    }
+    button
+}
@@ -1,5 +1,5 @@
 use crate::translations::translations::{notifications_translation, overview_translation};
-use crate::translations::translations_2::inspect_translation;
+use crate::translations::translations_2::{inspect_translation, throttling_translation};
 use crate::Language;
 
 /// This enum defines the current running page.
@@ -9,6 +9,8 @@ pub enum RunningPage {
     Overview,
     /// Inspect page.
     Inspect,
+    /// Throttling page.
+    Throttling,
     /// Notifications page.
     Notifications,
 }
@@ -18,6 +20,7 @@ pub fn get_tab_label(&self, language: Language) -> &str {
         match self {
             RunningPage::Overview => overview_translation(language),
             RunningPage::Inspect => inspect_translation(language),
+            RunningPage::Throttling => throttling_translation(language),
             RunningPage::Notifications => notifications_translation(language),
         }
     }
@@ -25,7 +28,8 @@ pub fn get_tab_label(&self, language: Language) -> &str {
     pub fn next(self) -> Self {
         match self {
             RunningPage::Overview => RunningPage::Inspect,
-            RunningPage::Inspect => RunningPage::Notifications,
+            RunningPage::Inspect => RunningPage::Throttling,
+            RunningPage::Throttling => RunningPage::Notifications,
             RunningPage::Notifications => RunningPage::Overview,
         }
     }
@@ -34,7 +38,8 @@ pub fn previous(self) -> Self {
         match self {
             RunningPage::Overview => RunningPage::Notifications,
             RunningPage::Inspect => RunningPage::Overview,
-            RunningPage::Notifications => RunningPage::Inspect,
+            RunningPage::Throttling => RunningPage::Inspect,
+            RunningPage::Notifications => RunningPage::Throttling,
         }
     }
 }
@@ -46,14 +51,19 @@ mod tests {
     #[test]
     fn test_previous_running_page() {
         assert_eq!(RunningPage::Overview.previous(), RunningPage::Notifications);
-        assert_eq!(RunningPage::Notifications.previous(), RunningPage::Inspect);
+        assert_eq!(
+            RunningPage::Notifications.previous(),
+            RunningPage::Throttling
+        );
+        assert_eq!(RunningPage::Throttling.previous(), RunningPage::Inspect);
         assert_eq!(RunningPage::Inspect.previous(), RunningPage::Overview);
     }
 
     #[test]
     fn test_next_running_page() {
         assert_eq!(RunningPage::Overview.next(), RunningPage::Inspect);
-        assert_eq!(RunningPage::Inspect.next(), RunningPage::Notifications);
+        assert_eq!(RunningPage::Inspect.next(), RunningPage::Throttling);
+        assert_eq!(RunningPage::Throttling.next(), RunningPage::Notifications);
         assert_eq!(RunningPage::Notifications.next(), RunningPage::Overview);
     }
 }
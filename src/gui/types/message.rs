@@ -1,5 +1,7 @@
 use iced::font;
 
+use crate::configs::types::data_quota::DataQuotaDraft;
+use crate::configs::types::throttling_profile::ThrottlingProfileDraft;
 use crate::gui::components::types::my_modal::MyModal;
 use crate::gui::components::types::report_view::ReportView;
 use crate::gui::components::types::throttling_mode::ThrottlingMode;
@@ -7,8 +9,13 @@
 use crate::gui::pages::types::settings_page::SettingsPage;
 use crate::gui::styles::types::gradient_type::GradientType;
 use crate::networking::types::host::Host;
+use crate::networking::types::host_selector::HostSelector;
+use crate::networking::types::port_selector::PortSelector;
+use crate::networking::types::savefile_options::SavefileOptions;
 use crate::networking::types::search_parameters::SearchParameters;
+use crate::networking::types::traffic_direction::TrafficDirection;
 use crate::notifications::types::notifications::Notification;
+use crate::report::types::export_format::ExportFormat;
 use crate::utils::types::web_page::WebPage;
 use crate::{
     AppProtocol, ChartType, IpVersion, Language, ReportSortType, StyleType, TransProtocol,
@@ -23,6 +30,8 @@ pub enum Message {
     TickRun,
     /// Select adapter
     AdapterSelection(String),
+    /// Add (true) or remove (false) an adapter to be sniffed together with the selected one
+    AdditionalAdapterSelection(String, bool),
     /// Select IP filter
     IpVersionSelection(IpVersion),
     /// Select transport filter
@@ -37,6 +46,8 @@ pub enum Message {
     AddOrRemoveFavorite(Host, bool),
     /// Open Sniffnet's complete textual report
     OpenReport,
+    /// Export the connections table in the given format
+    ExportConnections(ExportFormat),
     /// Open the supplied web page
     OpenWebPage(WebPage),
     /// Start sniffing packets
@@ -101,6 +112,14 @@ pub enum Message {
     UidFilter(String),
     /// port filter
     PortFilter(String),
+    /// Raw BPF expression compiled into the captures
+    BpfFilter(String),
+    /// Path of the pcap/pcapng file to be imported instead of sniffing an adapter
+    PcapImportPath(String),
+    /// Local addresses of the host the imported file was recorded on, separated by commas
+    PcapLocalAddresses(String),
+    /// Update the options to dump the sniffed packets into pcap savefiles
+    UpdateSavefileOptions(SavefileOptions),
     /// Throttling Interface
     InterfaceBandwidth(String),
     /// General Bandwidth for throttling, for process, port and user
@@ -109,4 +128,22 @@ pub enum Message {
     Throttle(Option<u32>, u32, ThrottlingMode),
     /// Unthrottle
     Unthrottle(u32, ThrottlingMode),
+    /// Unthrottle a range of ports in the given direction
+    UnthrottlePorts(PortSelector, TrafficDirection),
+    /// Show the modal to throttle the remote hosts, choosing among the given selectors
+    ShowHostThrottlingModal(Vec<HostSelector>),
+    /// Throttle the selected remote hosts, or unthrottle them if the bandwidth is not specified
+    ThrottleHost(Option<u32>, HostSelector),
+    /// Update the throttling profile being created or edited
+    UpdateProfileDraft(ThrottlingProfileDraft),
+    /// Save the throttling profile being created or edited
+    SaveProfile,
+    /// Remove the throttling profile at the given position
+    RemoveProfile(usize),
+    /// Update the data quota being created or edited
+    UpdateQuotaDraft(DataQuotaDraft),
+    /// Save the data quota being created or edited
+    SaveQuota,
+    /// Remove the data quota at the given position
+    RemoveQuota(usize),
 }
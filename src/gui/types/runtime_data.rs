@@ -1,6 +1,6 @@
 //! Module defining the `RunTimeData` struct, useful to to generate chart and to display statistics about network traffic
 //!
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 
 use crate::notifications::types::logged_notification::LoggedNotification;
 
@@ -28,6 +28,8 @@ pub struct RunTimeData {
     pub tot_sent_packets_prev: u128,
     /// Total received packets filtered before the current time interval
     pub tot_received_packets_prev: u128,
+    /// Packets and bytes exchanged by each process (indexed by PID) before the current time interval
+    pub process_traffic_prev: HashMap<u32, (u128, u128)>,
     /// Log of the received notifications
     pub logged_notifications: VecDeque<LoggedNotification>,
     /// The total number of emitted notifications
@@ -49,6 +51,7 @@ pub fn new() -> Self {
             tot_received_bytes_prev: 0,
             tot_sent_packets_prev: 0,
             tot_received_packets_prev: 0,
+            process_traffic_prev: HashMap::new(),
             logged_notifications: VecDeque::default(),
             tot_emitted_notifications: 0,
         }
@@ -1,15 +1,24 @@
 //! Module defining the `Sniffer` struct, which trace gui's component statuses and permits
 //! to share data among the different threads.
 
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
 use std::time::Duration;
 
+use chrono::{DateTime, Local, NaiveDateTime};
 use iced::{window, Command};
-use pcap::Device;
+use pcap::{Device, Linktype};
 
 use crate::chart::manage_chart_data::update_charts_data;
+use crate::configs::types::config_throttling::{
+    get_executable_kbps, ConfigThrottling, ThrottlingRule, ThrottlingRuleTarget,
+};
+use crate::configs::types::data_quota::{update_quotas, DataQuotaDraft, QuotasUpdate};
+use crate::configs::types::throttling_profile::ThrottlingProfileDraft;
 use crate::gui::components::types::my_modal::MyModal;
 use crate::gui::components::types::report_view::ReportView;
 
@@ -19,20 +28,35 @@
 use crate::gui::styles::types::gradient_type::GradientType;
 use crate::gui::types::message::Message;
 use crate::gui::types::status::Status;
-use crate::networking::manage_packets::get_capture_result;
+use crate::networking::manage_packets::{
+    check_bpf_filter, get_datalink, get_file_local_addresses, open_capture, parse_local_addresses,
+};
+use crate::networking::types::capture_source::CaptureSource;
 use crate::networking::types::filters::Filters;
 use crate::networking::types::host::Host;
+use crate::networking::types::host_selector::HostSelector;
+use crate::networking::types::ip_subnet::IpSubnet;
 use crate::networking::types::my_device::MyDevice;
+use crate::networking::types::port_selector::PortSelector;
+use crate::networking::types::savefile_options::SavefileOptions;
 use crate::networking::types::search_parameters::SearchParameters;
 use crate::networking::types::traffic_control::{IngressThrottleConfig, TrafficControl};
-use crate::notifications::notify_and_log::notify_and_log;
+use crate::networking::types::traffic_control_error::TrafficControlError;
+use crate::networking::types::traffic_direction::TrafficDirection;
+use crate::notifications::notify_and_log::{
+    log_capture_failed, log_quota_exceeded, log_throttling_failed, notify_and_log,
+};
+use crate::notifications::types::logged_notification::LoggedNotification;
 use crate::notifications::types::notifications::{Notification, Notifications};
 use crate::notifications::types::sound::{play, Sound};
+use crate::report::export_connections::export_connections;
 use crate::report::get_report_entries::get_searched_entries;
+use crate::report::types::export_format::ExportFormat;
 use crate::report::types::report_sort_type::ReportSortType;
 use crate::secondary_threads::parse_packets::parse_packets;
+use crate::secondary_threads::refresh_socket_cache::start_socket_cache;
 use crate::translations::types::language::Language;
-use crate::utils::formatted_strings::get_report_path;
+use crate::utils::formatted_strings::{get_export_path, get_report_path};
 use crate::utils::types::web_page::WebPage;
 use crate::{ConfigDevice, ConfigSettings, InfoTraffic, RunTimeData, StyleType, TrafficChart};
 
@@ -53,12 +77,18 @@ pub struct Sniffer {
     // TODO: add map or vector for UIDs and PIDs
     /// Network adapter to be analyzed
     pub device: MyDevice,
-    /// Last network adapter name for which packets were observed; saved into config file
-    pub last_device_name_sniffed: String,
+    /// Network adapters sniffed together with the main one; throttling only applies to the main one
+    pub additional_devices: Vec<MyDevice>,
+    /// Last network adapters for which packets were observed; saved into config file
+    pub last_config_device: ConfigDevice,
     /// Active filters on the observed traffic
     pub filters: Filters,
     /// Signals if a pcap error occurred
     pub pcap_error: Option<String>,
+    /// Error compiling the BPF expression of the filters, if it's not valid
+    pub bpf_error: Option<String>,
+    /// Link types of the adapters (or of the imported file), for which the BPF expression is compiled
+    pub datalinks: Vec<Linktype>,
     /// Application style
     pub style: StyleType,
     /// Wether gradients are enabled by the user
@@ -95,10 +125,40 @@ pub struct Sniffer {
     pub last_focus_time: std::time::Instant,
     /// Bandwidth of the selected interface, which can be used to throttle the interface
     pub interface_bandwidth: String,
-    /// Provides the interface for throttling connections
-    pub traffic_controller: TrafficControl,
+    /// Provides the interface for throttling connections, shared with the handlers removing
+    /// its settings when the app is interrupted or panics
+    pub traffic_controller: Arc<Mutex<TrafficControl>>,
     /// General bandwidth throttling value
     pub throttling_bandwidth: String,
+    /// Path of the pcap/pcapng file to be imported; live capture if empty
+    pub pcap_import_path: String,
+    /// Local addresses of the host the imported file was recorded on; inferred from the file if empty
+    pub pcap_local_addresses: String,
+    /// Error of the local addresses inserted by the user, if they aren't valid
+    pub local_addresses_error: Option<String>,
+    /// Options to dump the sniffed packets into pcap savefiles
+    pub savefile_options: SavefileOptions,
+    /// Throttling rules set by the user; saved into config file
+    pub config_throttling: ConfigThrottling,
+    /// Processes to which the saved throttling rules have already been applied
+    pub restored_pids: HashSet<u32>,
+    /// Remote subnets throttled for each host selector
+    pub throttled_hosts: HashMap<HostSelector, HashSet<IpSubnet>>,
+    /// Host selectors listed in the remote hosts throttling modal
+    pub host_throttling_selectors: Vec<HostSelector>,
+    /// Name of the throttling profile currently active, if any
+    pub active_profile: Option<String>,
+    /// Throttling profile being created or edited in the profiles modal
+    pub profile_draft: ThrottlingProfileDraft,
+    /// Outcome of the last export of the connections table: the path written, or the error
+    pub export_result: Option<Result<PathBuf, String>>,
+    /// Data quota being created or edited in the quotas modal
+    pub quota_draft: DataQuotaDraft,
+    /// Local time of the oldest change to the usage of the quotas not saved yet, if any
+    pub quotas_unsaved_since: Option<NaiveDateTime>,
+    /// Set when the app is interrupted (e.g., by Ctrl-C), to quit at the next tick after
+    /// cleaning up and saving the usage of the quotas
+    pub quit_requested: Arc<AtomicBool>,
 }
 
 impl Sniffer {
@@ -108,20 +168,27 @@ pub fn new(
         status_pair: Arc<(Mutex<Status>, Condvar)>,
         config_settings: &ConfigSettings,
         config_device: &ConfigDevice,
+        config_throttling: &ConfigThrottling,
         newer_release_available: Arc<Mutex<Result<bool, String>>>,
     ) -> Self {
         let traffic_control: TrafficControl =
             TrafficControl::new(config_device.device_name.to_owned(), None);
-        Self {
+        let mut sniffer = Self {
             current_capture_id,
             info_traffic,
             status_pair,
             newer_release_available,
             runtime_data: RunTimeData::new(),
             device: config_device.to_my_device(),
-            last_device_name_sniffed: config_device.device_name.clone(),
-            filters: Filters::default(),
+            additional_devices: config_device.to_additional_devices(),
+            last_config_device: config_device.clone(),
+            filters: Filters {
+                bpf: config_device.bpf_filter.clone(),
+                ..Filters::default()
+            },
             pcap_error: None,
+            bpf_error: None,
+            datalinks: Vec::new(),
             style: config_settings.style,
             color_gradient: config_settings.color_gradient,
             waiting: ".".to_string(),
@@ -140,9 +207,33 @@ pub fn new(
             selected_connection: 0,
             last_focus_time: std::time::Instant::now(),
             interface_bandwidth: String::new(),
-            traffic_controller: traffic_control,
+            traffic_controller: Arc::new(Mutex::new(traffic_control)),
             throttling_bandwidth: String::new(),
+            pcap_import_path: String::new(),
+            pcap_local_addresses: String::new(),
+            local_addresses_error: None,
+            savefile_options: SavefileOptions::default(),
+            config_throttling: config_throttling.clone(),
+            restored_pids: HashSet::new(),
+            throttled_hosts: HashMap::new(),
+            host_throttling_selectors: Vec::new(),
+            active_profile: None,
+            profile_draft: ThrottlingProfileDraft::default(),
+            export_result: None,
+            quota_draft: DataQuotaDraft::default(),
+            quotas_unsaved_since: None,
+            quit_requested: Arc::new(AtomicBool::new(false)),
+        };
+        sniffer.update_datalinks();
+        let setup_error = sniffer
+            .traffic_controller
+            .lock()
+            .unwrap()
+            .take_setup_error();
+        if let Some(err) = setup_error {
+            sniffer.report_throttling_error(&err);
         }
+        sniffer
     }
 
     pub fn update(&mut self, message: Message) -> Command<Message> {
@@ -150,61 +241,62 @@ pub fn update(&mut self, message: Message) -> Command<Message> {
             Message::InterfaceBandwidth(bandwidth) => {
                 self.interface_bandwidth = bandwidth.trim().to_string()
             }
-            Message::Unthrottle(id, throttling_mode) => match throttling_mode {
-                ThrottlingMode::Process => {
-                    self.traffic_controller
-                        .unthrottle_pid(id)
-                        .unwrap_or_else(|_| panic!("Couldn't unthrottle process {}", id));
-                }
-                ThrottlingMode::Port => {
-                    self.traffic_controller
-                        .unthrottle_port(id.try_into().unwrap())
-                        .unwrap_or_else(|_| panic!("Couldn't unthrottle port {}", id));
-                }
-                ThrottlingMode::User => todo!(),
-            },
-            Message::Throttle(bandwidth, id, throttle_mode) => match throttle_mode {
-                ThrottlingMode::Process => {
-                    if let Some(b) = bandwidth {
-                        self.traffic_controller
-                            .throttle_pid(id, b.try_into().unwrap())
-                            .unwrap_or_else(|_| {
-                                panic!("Couldn't throttle process with PID {}", id)
-                            });
-                    } else {
-                        self.traffic_controller
-                            .unthrottle_pid(id)
-                            .unwrap_or_else(|_| panic!("Couldn't unthrottle process {}", id));
-                    }
-                }
-                ThrottlingMode::Port => {
-                    if let Some(b) = bandwidth {
-                        self.traffic_controller
-                            .throttle_port(id.try_into().unwrap(), b.try_into().unwrap(), None)
-                            .unwrap_or_else(|_| panic!("Couldn't throttle port {}", id));
-                    } else {
-                        self.traffic_controller
-                            .unthrottle_port(id.try_into().unwrap())
-                            .unwrap_or_else(|_| panic!("Couldn't unthrottle port {}", id));
-                    }
+            Message::Unthrottle(id, throttling_mode) => self.throttle(None, id, throttling_mode),
+            Message::UnthrottlePorts(ports, direction) => self.unthrottle_ports(ports, direction),
+            Message::ShowHostThrottlingModal(selectors) => {
+                // the modal can replace the details of the connection it was opened from
+                if self.settings_page.is_none()
+                    && matches!(self.modal, None | Some(MyModal::ConnectionDetails(_)))
+                {
+                    self.host_throttling_selectors = selectors;
+                    self.modal = Some(MyModal::HostThrottlingModal);
                 }
-                ThrottlingMode::User => todo!(),
-            },
+            }
+            Message::ThrottleHost(bandwidth, selector) => self.throttle_host(bandwidth, selector),
+            Message::UpdateProfileDraft(draft) => self.profile_draft = draft,
+            Message::SaveProfile => self.save_profile(),
+            Message::RemoveProfile(index) => self.remove_profile(index),
+            Message::UpdateQuotaDraft(draft) => self.quota_draft = draft,
+            Message::SaveQuota => self.save_quota(),
+            Message::RemoveQuota(index) => self.remove_quota(index),
+            Message::Throttle(bandwidth, id, throttle_mode) => {
+                self.throttle(bandwidth, id, throttle_mode);
+            }
             Message::ThrottlingBandwidth(bandwidth) => {
                 self.throttling_bandwidth = bandwidth.trim().to_string()
             }
             Message::UidFilter(uid) => self.filters.uid = uid,
             Message::PidFilter(pid) => self.filters.pid = pid,
             Message::PortFilter(port) => self.filters.port = port,
+            Message::BpfFilter(expression) => {
+                self.bpf_error = check_bpf_filter(&expression, &self.datalinks).err();
+                self.filters.bpf = expression;
+            }
+            Message::PcapImportPath(path) => {
+                self.pcap_import_path = path;
+                self.update_datalinks();
+            }
+            Message::PcapLocalAddresses(addresses) => {
+                self.local_addresses_error = parse_local_addresses(&addresses).err();
+                self.pcap_local_addresses = addresses;
+            }
+            Message::UpdateSavefileOptions(options) => self.savefile_options = options,
             Message::ReportViewSelection(view) => self.report_view = view,
+            Message::TickRun | Message::TickInit if self.quit_requested.load(Ordering::Relaxed) => {
+                return self.update(Message::Quit);
+            }
             Message::TickRun => return self.refresh_data(),
             Message::AdapterSelection(name) => self.set_adapter(&name),
+            Message::AdditionalAdapterSelection(name, selected) => {
+                self.set_additional_adapter(&name, selected);
+            }
             Message::IpVersionSelection(version) => self.filters.ip = version,
             Message::TransportProtocolSelection(protocol) => self.filters.transport = protocol,
             Message::AppProtocolSelection(protocol) => self.filters.application = protocol,
             Message::ChartSelection(unit) => self.traffic_chart.change_kind(unit),
             Message::ReportSortSelection(sort) => self.report_sort_type = sort,
             Message::OpenReport => self.open_report_file(),
+            Message::ExportConnections(format) => self.export_connections(format),
             Message::OpenWebPage(web_page) => Self::open_web(&web_page),
             Message::Start => self.start(),
             Message::Reset => return self.reset(),
@@ -252,7 +344,13 @@ pub fn update(&mut self, message: Message) -> Command<Message> {
                 self.runtime_data.logged_notifications = VecDeque::new();
                 return self.update(Message::HideModal);
             }
-            Message::Quit => return window::close(),
+            Message::Quit => {
+                self.traffic_controller.lock().unwrap().clean_up();
+                if !self.config_throttling.quotas.is_empty() {
+                    self.store_config_throttling();
+                }
+                return window::close();
+            }
             Message::SwitchPage(next) => {
                 // To prevent SwitchPage be triggered when using `Alt` + `Tab` to switch back,
                 // first check if user switch back just now, and ignore the request for a short time.
@@ -300,43 +398,75 @@ pub fn update(&mut self, message: Message) -> Command<Message> {
     }
 
     fn refresh_data(&mut self) -> Command<Message> {
-        let info_traffic_lock = self.info_traffic.lock().unwrap();
+        let now = Local::now().naive_local();
+        self.update_throttling_profile(now);
+        self.update_quotas(now);
+        // the failures are reported even if no packet has been parsed (e.g., because they
+        // couldn't be decoded)
+        let capture_failures =
+            std::mem::take(&mut self.info_traffic.lock().unwrap().capture_failures);
+        for (device, cause) in capture_failures {
+            self.report_capture_failure(device, cause);
+        }
+        let mut info_traffic_lock = self.info_traffic.lock().unwrap();
         self.runtime_data.all_packets = info_traffic_lock.all_packets;
         if info_traffic_lock.tot_received_packets + info_traffic_lock.tot_sent_packets == 0 {
             drop(info_traffic_lock);
             return self.update(Message::Waiting);
         }
-        self.runtime_data.tot_sent_packets = info_traffic_lock.tot_sent_packets;
-        self.runtime_data.tot_received_packets = info_traffic_lock.tot_received_packets;
-        self.runtime_data.all_bytes = info_traffic_lock.all_bytes;
-        self.runtime_data.tot_received_bytes = info_traffic_lock.tot_received_bytes;
-        self.runtime_data.tot_sent_bytes = info_traffic_lock.tot_sent_bytes;
         self.runtime_data.dropped_packets = info_traffic_lock.dropped_packets;
-        drop(info_traffic_lock);
-        let emitted_notifications = notify_and_log(
-            &mut self.runtime_data,
-            self.notifications,
-            &self.info_traffic.clone(),
-        );
-        self.info_traffic.lock().unwrap().favorites_last_interval = HashSet::new();
-        self.runtime_data.tot_emitted_notifications += emitted_notifications;
-        if self.running_page.ne(&RunningPage::Notifications) {
-            self.unread_notifications += emitted_notifications;
+        if CaptureSource::from_import_path(&self.pcap_import_path).is_offline() {
+            // imported files are parsed at full speed: charts and notifications are updated
+            // for each second of the file parsed since the last refresh
+            let mut replay_intervals = std::mem::take(&mut info_traffic_lock.replay_intervals);
+            // favorites resolved after their second was closed are notified with the last one
+            if let Some(last_interval) = replay_intervals.back_mut() {
+                last_interval.favorites.extend(std::mem::take(
+                    &mut info_traffic_lock.favorites_last_interval,
+                ));
+            }
+            drop(info_traffic_lock);
+            let mut notifications = self.notifications;
+            for interval in replay_intervals {
+                self.runtime_data.all_packets = interval.all_packets;
+                self.runtime_data.all_bytes = interval.all_bytes;
+                self.runtime_data.tot_sent_packets = interval.tot_sent_packets;
+                self.runtime_data.tot_received_packets = interval.tot_received_packets;
+                self.runtime_data.tot_received_bytes = interval.tot_received_bytes;
+                self.runtime_data.tot_sent_bytes = interval.tot_sent_bytes;
+                let emitted_notifications =
+                    self.log_notifications(notifications, &interval.favorites, interval.timestamp);
+                // a single sound is played for all the seconds parsed since the last refresh
+                if emitted_notifications > 0 {
+                    notifications.volume = 0;
+                }
+                update_charts_data(&mut self.runtime_data, &mut self.traffic_chart);
+            }
+        } else {
+            self.runtime_data.tot_sent_packets = info_traffic_lock.tot_sent_packets;
+            self.runtime_data.tot_received_packets = info_traffic_lock.tot_received_packets;
+            self.runtime_data.all_bytes = info_traffic_lock.all_bytes;
+            self.runtime_data.tot_received_bytes = info_traffic_lock.tot_received_bytes;
+            self.runtime_data.tot_sent_bytes = info_traffic_lock.tot_sent_bytes;
+            let favorites = std::mem::take(&mut info_traffic_lock.favorites_last_interval);
+            drop(info_traffic_lock);
+            self.log_notifications(self.notifications, &favorites, Local::now());
+            update_charts_data(&mut self.runtime_data, &mut self.traffic_chart);
         }
-        update_charts_data(&mut self.runtime_data, &mut self.traffic_chart);
-
-        let current_device_name = self.device.name.clone();
-        // update ConfigDevice stored if different from last sniffed device
-        if current_device_name.ne(&self.last_device_name_sniffed) {
-            self.last_device_name_sniffed = current_device_name.clone();
-            confy::store(
-                "sniffnet",
-                "device",
-                ConfigDevice {
-                    device_name: current_device_name,
-                },
-            )
-            .unwrap_or(());
+        self.restore_process_throttling_rules();
+        self.restore_host_throttling_rules();
+        if self.running_page.eq(&RunningPage::Throttling) {
+            self.traffic_controller
+                .lock()
+                .unwrap()
+                .refresh_class_statistics();
+        }
+
+        let current_config_device = self.get_config_device();
+        // update ConfigDevice stored if different from the last stored one (devices and BPF filter)
+        if current_config_device.ne(&self.last_config_device) {
+            self.last_config_device = current_config_device.clone();
+            confy::store("sniffnet", "device", current_config_device).unwrap_or(());
         }
         // waiting notifications
         if self.running_page.eq(&RunningPage::Notifications)
@@ -347,6 +477,27 @@ fn refresh_data(&mut self) -> Command<Message> {
         Command::none()
     }
 
+    /// Emits and logs the notifications of the last interval, returning how many were emitted
+    fn log_notifications(
+        &mut self,
+        notifications: Notifications,
+        favorites: &HashSet<Host>,
+        timestamp: DateTime<Local>,
+    ) -> usize {
+        let emitted_notifications = notify_and_log(
+            &mut self.runtime_data,
+            notifications,
+            &self.info_traffic.clone(),
+            favorites,
+            timestamp,
+        );
+        self.runtime_data.tot_emitted_notifications += emitted_notifications;
+        if self.running_page.ne(&RunningPage::Notifications) {
+            self.unread_notifications += emitted_notifications;
+        }
+        emitted_notifications
+    }
+
     fn open_report_file(&mut self) {
         if self.status_pair.0.lock().unwrap().eq(&Status::Running) {
             let report_path = get_report_path();
@@ -369,6 +520,15 @@ fn open_report_file(&mut self) {
         }
     }
 
+    fn export_connections(&mut self, format: ExportFormat) {
+        if self.status_pair.0.lock().unwrap().eq(&Status::Running) {
+            let export_path = get_export_path(format);
+            self.export_result = Some(
+                export_connections(&self.info_traffic, format, &export_path).map(|()| export_path),
+            );
+        }
+    }
+
     fn open_web(web_page: &WebPage) {
         let url = web_page.get_url();
         #[cfg(target_os = "windows")]
@@ -397,44 +557,156 @@ fn start(&mut self) {
         //     orig_hook(panic_info);
         //     process::exit(1);
         // }));
-        // dbg!(&self.traffic_controller);
         let current_device_name = &*self.device.name.clone();
-        // dbg!(&self.traffic_controller);
         self.set_adapter(current_device_name);
-        let device = self.device.clone();
-        let (pcap_error, cap) = get_capture_result(&device);
+        let capture_source = CaptureSource::from_import_path(&self.pcap_import_path);
+        // the traffic of an imported file is classified according to the addresses of
+        // the host it was recorded on, rather than the ones of the selected adapter
+        if let CaptureSource::File(path) = &capture_source {
+            self.device
+                .set_addresses(&get_file_local_addresses(path, &self.pcap_local_addresses));
+        }
+        let devices = self.get_capture_devices(&capture_source);
+        let mut pcap_error = None;
+        let mut captures = Vec::new();
+        for device in devices {
+            // the packets of the additional adapters are dumped into savefiles of their own
+            let savefile_options = if device.name.eq(&self.device.name) {
+                self.savefile_options.clone()
+            } else {
+                self.savefile_options.for_device(&device.name)
+            };
+            match open_capture(
+                &device,
+                &capture_source,
+                &self.filters.bpf,
+                &savefile_options,
+            ) {
+                Ok((cap, savefile)) => captures.push((device, cap, savefile)),
+                Err(err) => {
+                    pcap_error = Some(err);
+                    break;
+                }
+            }
+        }
         self.pcap_error = pcap_error.clone();
         *self.status_pair.0.lock().unwrap() = Status::Running;
         let info_traffic_mutex = self.info_traffic.clone();
         *info_traffic_mutex.lock().unwrap() = InfoTraffic::new();
-        self.runtime_data = RunTimeData::new();
+        self.config_throttling.reset_quota_totals();
+        // the throttling failures are kept, since they may have occurred while
+        // the capture was being set up
+        let mut runtime_data = RunTimeData::new();
+        runtime_data.logged_notifications =
+            std::mem::take(&mut self.runtime_data.logged_notifications)
+                .into_iter()
+                .filter(|notification| {
+                    matches!(notification, LoggedNotification::ThrottlingFailed(_))
+                })
+                .collect();
+        runtime_data.tot_emitted_notifications = runtime_data.logged_notifications.len();
+        self.runtime_data = runtime_data;
         self.traffic_chart = TrafficChart::new(self.style, self.language);
 
         if pcap_error.is_none() {
             // no pcap error
-            let current_capture_id = self.current_capture_id.clone();
-            let filters = self.filters.clone();
             self.status_pair.1.notify_all();
-            thread::Builder::new()
-                .name("thread_parse_packets".to_string())
-                .spawn(move || {
-                    parse_packets(
-                        &current_capture_id,
-                        &device,
-                        cap.unwrap(),
-                        &filters,
-                        &info_traffic_mutex,
-                    );
-                })
-                .unwrap();
+            // packets read from a file can't be attributed to the local processes
+            let socket_cache = (!capture_source.is_offline())
+                .then(|| start_socket_cache(&self.current_capture_id));
+            // each adapter is parsed by its own thread, all of them updating the same traffic data
+            for (device, cap, savefile) in captures {
+                let current_capture_id = self.current_capture_id.clone();
+                let socket_cache = socket_cache.clone();
+                let capture_source = capture_source.clone();
+                let filters = self.filters.clone();
+                let info_traffic_mutex = info_traffic_mutex.clone();
+                thread::Builder::new()
+                    .name("thread_parse_packets".to_string())
+                    .spawn(move || {
+                        parse_packets(
+                            &current_capture_id,
+                            &device,
+                            cap,
+                            &capture_source,
+                            &filters,
+                            savefile,
+                            socket_cache.as_ref(),
+                            &info_traffic_mutex,
+                        );
+                    })
+                    .unwrap();
+            }
+        }
+    }
+
+    /// Returns the adapters to be sniffed: the main one followed by the additional ones
+    pub fn get_sniffed_devices(&self) -> Vec<MyDevice> {
+        let mut devices = vec![self.device.clone()];
+        devices.extend(self.additional_devices.iter().cloned());
+        devices
+    }
+
+    /// Returns the adapters to be opened for the given capture source;
+    /// an imported file replaces all the adapters
+    fn get_capture_devices(&self, capture_source: &CaptureSource) -> Vec<MyDevice> {
+        match capture_source {
+            CaptureSource::Live => self.get_sniffed_devices(),
+            CaptureSource::File(_) => vec![self.device.clone()],
         }
     }
 
+    /// Reads the link types of the adapters to be opened, and validates the BPF expression
+    /// against them, since some expressions are only valid for specific link types
+    fn update_datalinks(&mut self) {
+        let capture_source = CaptureSource::from_import_path(&self.pcap_import_path);
+        self.datalinks = self
+            .get_capture_devices(&capture_source)
+            .iter()
+            .map(|device| get_datalink(device, &capture_source))
+            .collect();
+        self.bpf_error = check_bpf_filter(&self.filters.bpf, &self.datalinks).err();
+    }
+
+    /// Returns the configuration of the currently selected adapters
+    fn get_config_device(&self) -> ConfigDevice {
+        ConfigDevice {
+            device_name: self.device.name.clone(),
+            additional_device_names: self
+                .additional_devices
+                .iter()
+                .map(|device| device.name.clone())
+                .collect(),
+            bpf_filter: self.filters.bpf.clone(),
+        }
+    }
+
+    /// Adds or removes an adapter to be sniffed together with the main one
+    fn set_additional_adapter(&mut self, name: &str, selected: bool) {
+        self.additional_devices
+            .retain(|device| device.name.ne(name));
+        if selected && name.ne(&self.device.name) {
+            if let Some(dev) = Device::list()
+                .expect("Error retrieving device list\r\n")
+                .into_iter()
+                .find(|dev| dev.name.eq(name))
+            {
+                self.additional_devices.push(MyDevice {
+                    name: dev.name,
+                    desc: dev.desc,
+                    addresses: Arc::new(Mutex::new(dev.addresses)),
+                });
+            }
+        }
+        self.update_datalinks();
+    }
+
     fn reset(&mut self) -> Command<Message> {
         *self.status_pair.0.lock().unwrap() = Status::Init;
         self.running_page = RunningPage::Overview;
         *self.current_capture_id.lock().unwrap() += 1; //change capture id to kill previous capture and to rewrite output file
         self.pcap_error = None;
+        self.export_result = None;
         self.report_sort_type = ReportSortType::MostRecent;
         self.unread_notifications = 0;
         self.search = SearchParameters::default();
@@ -453,19 +725,560 @@ fn set_adapter(&mut self, name: &str) {
                     desc: dev.desc,
                     addresses: self.device.addresses.clone(),
                 };
-                let kbps = str::parse::<usize>(&self.interface_bandwidth).ok();
+                // the main adapter can't be an additional one too
+                self.additional_devices
+                    .retain(|device| device.name.ne(&self.device.name));
+                let kbps = self.get_interface_kbps();
                 let ingress_throttle_config: Option<IngressThrottleConfig> =
                     kbps.map(|k| IngressThrottleConfig {
                         kbps: k,
                         burst_kb: 250,
                     });
-                self.traffic_controller =
+                // the previous settings must be removed before the new ones are created,
+                // since the interface may be the same
+                let mut traffic_controller = self.traffic_controller.lock().unwrap();
+                traffic_controller.clean_up();
+                *traffic_controller =
                     TrafficControl::new(name.to_string(), ingress_throttle_config);
+                let setup_error = traffic_controller.take_setup_error();
+                drop(traffic_controller);
+                if let Some(err) = setup_error {
+                    self.report_throttling_error(&err);
+                }
+                self.restored_pids.clear();
+                self.throttled_hosts.clear();
+                self.restore_throttling_rules();
+                self.update_datalinks();
                 break;
             }
         }
     }
 
+    /// Applies the throttling rule for the given target, or removes it if the bandwidth
+    /// is not specified; in case of failure, the step that couldn't be completed is reported
+    fn throttle(&mut self, bandwidth: Option<u32>, id: u32, throttling_mode: ThrottlingMode) {
+        let mut traffic_controller = self.traffic_controller.lock().unwrap();
+        let result = match (throttling_mode, bandwidth) {
+            (ThrottlingMode::Process, Some(b)) => {
+                traffic_controller.throttle_pid(id, b.try_into().unwrap())
+            }
+            (ThrottlingMode::Process, None) => traffic_controller.unthrottle_pid(id),
+            (ThrottlingMode::Port, Some(b)) => traffic_controller.throttle_port(
+                id.try_into().unwrap(),
+                b.try_into().unwrap(),
+                None,
+            ),
+            (ThrottlingMode::Port, None) => {
+                traffic_controller.unthrottle_port(id.try_into().unwrap())
+            }
+            (ThrottlingMode::User, Some(b)) => {
+                traffic_controller.throttle_uid(id, b.try_into().unwrap())
+            }
+            (ThrottlingMode::User, None) => traffic_controller.unthrottle_uid(id),
+        };
+        drop(traffic_controller);
+        if let Err(err) = result {
+            self.report_throttling_error(&err);
+            return;
+        }
+
+        // processes are remembered by their executable, since PIDs change across restarts
+        let rule_target = match throttling_mode {
+            ThrottlingMode::Process => self
+                .info_traffic
+                .lock()
+                .unwrap()
+                .processes
+                .get(&id)
+                .map(|process| process.exe.clone())
+                .filter(|exe| !exe.is_empty())
+                .map(ThrottlingRuleTarget::Executable),
+            ThrottlingMode::Port => Some(ThrottlingRuleTarget::Port(id.try_into().unwrap())),
+            ThrottlingMode::User => Some(ThrottlingRuleTarget::User(id)),
+        };
+        if let Some(rule_target) = rule_target {
+            self.config_throttling.set_rule(rule_target, bandwidth);
+            self.store_config_throttling();
+        }
+    }
+
+    /// Removes the limit of a range of ports in the given direction, together with its rule
+    fn unthrottle_ports(&mut self, ports: PortSelector, direction: TrafficDirection) {
+        let result = self
+            .traffic_controller
+            .lock()
+            .unwrap()
+            .unthrottle_ports(ports, direction);
+        if let Err(err) = result {
+            self.report_throttling_error(&err);
+            return;
+        }
+        self.config_throttling
+            .set_rule(ThrottlingRuleTarget::Ports { ports, direction }, None);
+        self.store_config_throttling();
+    }
+
+    /// Applies the throttling rule for the given remote hosts, or removes it if the bandwidth
+    /// is not specified; domains and Autonomous Systems are throttled address by address,
+    /// starting from the ones already resolved
+    fn throttle_host(&mut self, bandwidth: Option<u32>, selector: HostSelector) {
+        let mut subnets = self.throttled_hosts.remove(&selector).unwrap_or_default();
+        let result = if let Some(b) = bandwidth {
+            subnets.extend(self.get_selected_subnets(&selector));
+            let result = subnets.iter().try_for_each(|subnet| {
+                self.traffic_controller
+                    .lock()
+                    .unwrap()
+                    .throttle_host(*subnet, b.try_into().unwrap())
+            });
+            self.throttled_hosts.insert(selector.clone(), subnets);
+            result
+        } else {
+            subnets.into_iter().try_for_each(|subnet| {
+                self.traffic_controller
+                    .lock()
+                    .unwrap()
+                    .unthrottle_host(subnet)
+            })
+        };
+        if let Err(err) = result {
+            self.report_throttling_error(&err);
+            return;
+        }
+
+        self.config_throttling
+            .set_rule(ThrottlingRuleTarget::Host(selector), bandwidth);
+        self.store_config_throttling();
+    }
+
+    /// Returns the subnets selected by a host selector: the subnet itself, or the resolved
+    /// addresses of a domain or Autonomous System
+    fn get_selected_subnets(&self, selector: &HostSelector) -> Vec<IpSubnet> {
+        if let HostSelector::Subnet(subnet) = selector {
+            return vec![*subnet];
+        }
+        self.info_traffic
+            .lock()
+            .unwrap()
+            .addresses_resolved
+            .iter()
+            .filter_map(|(address, (_, host))| {
+                let address = address.parse::<IpAddr>().ok()?;
+                selector
+                    .matches(address, host)
+                    .then(|| IpSubnet::from(address))
+            })
+            .collect()
+    }
+
+    /// Reports a step of the throttling configuration that failed, logging it
+    /// among the notifications
+    fn report_throttling_error(&mut self, error: &TrafficControlError) {
+        self.report_throttling_failure(error.get_step(), error.get_cause());
+    }
+
+    /// Reports a step related to throttling that failed (e.g., saving the rules) with its cause,
+    /// logging it among the notifications
+    pub fn report_throttling_failure(&mut self, step: &str, cause: String) {
+        log_throttling_failed(&mut self.runtime_data, step, cause);
+        self.runtime_data.tot_emitted_notifications += 1;
+        if self.running_page.ne(&RunningPage::Notifications) {
+            self.unread_notifications += 1;
+        }
+    }
+
+    /// Reports that the packets of an adapter couldn't be decoded or dumped,
+    /// logging it among the notifications
+    fn report_capture_failure(&mut self, device: String, cause: String) {
+        log_capture_failed(&mut self.runtime_data, device, cause);
+        self.runtime_data.tot_emitted_notifications += 1;
+        if self.running_page.ne(&RunningPage::Notifications) {
+            self.unread_notifications += 1;
+        }
+    }
+
+    /// Saves the throttling configuration, reporting the failure among the notifications
+    fn store_config_throttling(&mut self) {
+        if let Err(cause) = self.config_throttling.store() {
+            self.report_throttling_failure("save the throttling settings", cause);
+        }
+    }
+
+    /// Applies the saved throttling rules in force, as overridden by the active profile
+    /// and by the exceeded quotas
+    fn restore_throttling_rules(&mut self) {
+        let rules = self
+            .config_throttling
+            .get_rules_in_force(self.active_profile.as_deref());
+        for rule in rules {
+            if let Err(err) = self.apply_rule_limits(&rule.target, rule.kbps.try_into().unwrap()) {
+                self.report_throttling_error(&err);
+            }
+        }
+    }
+
+    /// Applies the limit of a rule to the ports, users and remote subnets it targets;
+    /// processes, domains and Autonomous Systems are throttled as they're observed
+    fn apply_rule_limits(
+        &mut self,
+        target: &ThrottlingRuleTarget,
+        kbps: usize,
+    ) -> Result<(), TrafficControlError> {
+        match *target {
+            ThrottlingRuleTarget::Port(port) => self
+                .traffic_controller
+                .lock()
+                .unwrap()
+                .throttle_port(port, kbps, None),
+            ThrottlingRuleTarget::Ports { ports, direction } => self
+                .traffic_controller
+                .lock()
+                .unwrap()
+                .throttle_ports(ports, direction, kbps, None),
+            ThrottlingRuleTarget::User(uid) => self
+                .traffic_controller
+                .lock()
+                .unwrap()
+                .throttle_uid(uid, kbps),
+            ThrottlingRuleTarget::Host(HostSelector::Subnet(subnet)) => {
+                self.throttled_hosts
+                    .entry(HostSelector::Subnet(subnet))
+                    .or_default()
+                    .insert(subnet);
+                self.traffic_controller
+                    .lock()
+                    .unwrap()
+                    .throttle_host(subnet, kbps)
+            }
+            ThrottlingRuleTarget::Host(_) | ThrottlingRuleTarget::Executable(_) => Ok(()),
+        }
+    }
+
+    /// Removes the limits applied for a rule; processes, domains and Autonomous Systems
+    /// are forgotten, so that the rule in force for them is applied when they're observed again
+    fn remove_rule_limits(
+        &mut self,
+        target: &ThrottlingRuleTarget,
+    ) -> Result<(), TrafficControlError> {
+        match target {
+            ThrottlingRuleTarget::Executable(executable) => {
+                let pids: Vec<u32> = self
+                    .info_traffic
+                    .lock()
+                    .unwrap()
+                    .processes
+                    .values()
+                    .filter(|process| process.exe.eq(executable))
+                    .map(|process| process.pid)
+                    .collect();
+                for pid in pids {
+                    self.restored_pids.remove(&pid);
+                    self.traffic_controller
+                        .lock()
+                        .unwrap()
+                        .unthrottle_pid(pid)?;
+                }
+                Ok(())
+            }
+            ThrottlingRuleTarget::Port(port) => self
+                .traffic_controller
+                .lock()
+                .unwrap()
+                .unthrottle_port(*port),
+            ThrottlingRuleTarget::Ports { ports, direction } => self
+                .traffic_controller
+                .lock()
+                .unwrap()
+                .unthrottle_ports(*ports, *direction),
+            ThrottlingRuleTarget::User(uid) => {
+                self.traffic_controller.lock().unwrap().unthrottle_uid(*uid)
+            }
+            ThrottlingRuleTarget::Host(selector) => self
+                .throttled_hosts
+                .remove(selector)
+                .unwrap_or_default()
+                .into_iter()
+                .try_for_each(|subnet| {
+                    self.traffic_controller
+                        .lock()
+                        .unwrap()
+                        .unthrottle_host(subnet)
+                }),
+        }
+    }
+
+    /// Activates the throttling profile scheduled at the given local time, if it isn't already:
+    /// the limits no longer in force are removed, and the new ones are applied
+    fn update_throttling_profile(&mut self, now: NaiveDateTime) {
+        let scheduled_profile = self
+            .config_throttling
+            .get_scheduled_profile(now)
+            .map(|profile| profile.name.clone());
+        if scheduled_profile.eq(&self.active_profile) {
+            return;
+        }
+
+        let previous_rules = self
+            .config_throttling
+            .get_rules_in_force(self.active_profile.as_deref());
+        let previous_interface_kbps = self.get_interface_kbps();
+        self.active_profile = scheduled_profile;
+        self.update_rules_in_force(&previous_rules);
+        self.update_interface_limit(previous_interface_kbps);
+    }
+
+    /// Applies the ingress bandwidth of the interface in force, if it differs from the previous one
+    fn update_interface_limit(&mut self, previous_interface_kbps: Option<usize>) {
+        let interface_kbps = self.get_interface_kbps();
+        if interface_kbps.eq(&previous_interface_kbps) {
+            return;
+        }
+        let result =
+            match interface_kbps {
+                Some(kbps) => self.traffic_controller.lock().unwrap().throttle_interface(
+                    &IngressThrottleConfig {
+                        kbps,
+                        burst_kb: 250,
+                    },
+                ),
+                None => self
+                    .traffic_controller
+                    .lock()
+                    .unwrap()
+                    .unthrottle_interface(),
+            };
+        if let Err(err) = result {
+            self.report_throttling_error(&err);
+        }
+    }
+
+    /// Saves the throttling profile of the draft, if valid, and updates the limits in force
+    /// in case the profile is scheduled now
+    fn save_profile(&mut self) {
+        let Some(profile) = self
+            .profile_draft
+            .to_profile(&self.config_throttling.profiles)
+        else {
+            return;
+        };
+        let index = self
+            .profile_draft
+            .index
+            .filter(|index| *index < self.config_throttling.profiles.len());
+        self.update_config_throttling(|config| match index {
+            Some(index) => config.profiles[index] = profile,
+            None => config.profiles.push(profile),
+        });
+        self.profile_draft = ThrottlingProfileDraft::default();
+    }
+
+    /// Removes the throttling profile at the given position, releasing its limits if it's active
+    fn remove_profile(&mut self, index: usize) {
+        if index >= self.config_throttling.profiles.len() {
+            return;
+        }
+        self.update_config_throttling(|config| {
+            config.profiles.remove(index);
+        });
+        // the profile being edited may have moved
+        self.profile_draft = ThrottlingProfileDraft::default();
+    }
+
+    /// Saves the data quota of the draft, if valid, and updates the limits in force
+    /// in case it's exceeded
+    fn save_quota(&mut self) {
+        let Some(quota) = self.quota_draft.to_quota(&self.config_throttling.quotas) else {
+            return;
+        };
+        let index = self
+            .quota_draft
+            .index
+            .filter(|index| *index < self.config_throttling.quotas.len());
+        self.update_config_throttling(|config| match index {
+            Some(index) => config.quotas[index] = quota,
+            None => config.quotas.push(quota),
+        });
+        self.quota_draft = DataQuotaDraft::default();
+    }
+
+    /// Removes the data quota at the given position, releasing its target if it's exceeded
+    fn remove_quota(&mut self, index: usize) {
+        if index >= self.config_throttling.quotas.len() {
+            return;
+        }
+        self.update_config_throttling(|config| {
+            config.quotas.remove(index);
+        });
+        // the quota being edited may have moved
+        self.quota_draft = DataQuotaDraft::default();
+    }
+
+    /// Updates the throttling configuration and saves it; the profile scheduled now is activated
+    /// again and the limits in force are updated, since they may have changed
+    fn update_config_throttling(&mut self, update: impl FnOnce(&mut ConfigThrottling)) {
+        let previous_rules = self
+            .config_throttling
+            .get_rules_in_force(self.active_profile.as_deref());
+        let previous_interface_kbps = self.get_interface_kbps();
+        update(&mut self.config_throttling);
+        self.store_config_throttling();
+        self.quotas_unsaved_since = None;
+        self.active_profile = self
+            .config_throttling
+            .get_scheduled_profile(Local::now().naive_local())
+            .map(|profile| profile.name.clone());
+        self.update_rules_in_force(&previous_rules);
+        self.update_interface_limit(previous_interface_kbps);
+    }
+
+    /// Accounts for the data exchanged by the targets of the quotas at the given local time:
+    /// the targets of the quotas exceeded are throttled, and released when a new period starts
+    fn update_quotas(&mut self, now: NaiveDateTime) {
+        if self.config_throttling.quotas.is_empty() {
+            return;
+        }
+
+        let previous_rules = self
+            .config_throttling
+            .get_rules_in_force(self.active_profile.as_deref());
+        let QuotasUpdate {
+            exceeded,
+            changed,
+            usage_changed,
+        } = update_quotas(
+            &mut self.config_throttling.quotas,
+            &self.info_traffic.lock().unwrap(),
+            now,
+        );
+
+        for quota in &exceeded {
+            log_quota_exceeded(&mut self.runtime_data, quota);
+            self.runtime_data.tot_emitted_notifications += 1;
+            if self.running_page.ne(&RunningPage::Notifications) {
+                self.unread_notifications += 1;
+            }
+        }
+        if changed {
+            self.update_rules_in_force(&previous_rules);
+        }
+        // the usage is saved whenever a quota changes state, and within a minute of any change
+        if usage_changed && self.quotas_unsaved_since.is_none() {
+            self.quotas_unsaved_since = Some(now);
+        }
+        let store_due = self
+            .quotas_unsaved_since
+            .is_some_and(|unsaved_since| now - unsaved_since >= chrono::Duration::minutes(1));
+        if changed || store_due {
+            self.store_config_throttling();
+            self.quotas_unsaved_since = None;
+        }
+    }
+
+    /// Removes the limits of the previous rules no longer in force, and applies the new ones
+    fn update_rules_in_force(&mut self, previous_rules: &[ThrottlingRule]) {
+        let rules = self
+            .config_throttling
+            .get_rules_in_force(self.active_profile.as_deref());
+        for rule in previous_rules.iter().filter(|rule| !rules.contains(rule)) {
+            if let Err(err) = self.remove_rule_limits(&rule.target) {
+                self.report_throttling_error(&err);
+            }
+        }
+        for rule in rules.iter().filter(|rule| !previous_rules.contains(rule)) {
+            if let Err(err) = self.apply_rule_limits(&rule.target, rule.kbps.try_into().unwrap()) {
+                self.report_throttling_error(&err);
+            }
+        }
+    }
+
+    /// Returns the ingress bandwidth of the interface in force: the one of the active profile,
+    /// if specified, or the one set by the user
+    fn get_interface_kbps(&self) -> Option<usize> {
+        self.config_throttling.get_interface_kbps(
+            self.active_profile.as_deref(),
+            str::parse::<usize>(&self.interface_bandwidth).ok(),
+        )
+    }
+
+    /// Applies the saved throttling rules to the processes running the corresponding
+    /// executables, the first time they're observed
+    fn restore_process_throttling_rules(&mut self) {
+        let rules = self
+            .config_throttling
+            .get_rules_in_force(self.active_profile.as_deref());
+        let processes_to_throttle: Vec<(u32, u32)> = self
+            .info_traffic
+            .lock()
+            .unwrap()
+            .processes
+            .values()
+            .filter(|process| !self.restored_pids.contains(&process.pid))
+            .filter_map(|process| {
+                get_executable_kbps(&rules, &process.exe).map(|kbps| (process.pid, kbps))
+            })
+            .collect();
+        for (pid, kbps) in processes_to_throttle {
+            // each process is only attempted once, to avoid retrying dead processes forever
+            self.restored_pids.insert(pid);
+            let mut traffic_controller = self.traffic_controller.lock().unwrap();
+            if traffic_controller.pid_is_throttled(pid) {
+                continue;
+            }
+            let result = traffic_controller.throttle_pid(pid, kbps.try_into().unwrap());
+            drop(traffic_controller);
+            if let Err(err) = result {
+                self.report_throttling_error(&err);
+            }
+        }
+    }
+
+    /// Applies the saved throttling rules for domains and Autonomous Systems to the addresses
+    /// resolved into them, the first time they're observed
+    fn restore_host_throttling_rules(&mut self) {
+        let mut hosts_to_throttle = Vec::new();
+        let rules = self
+            .config_throttling
+            .get_rules_in_force(self.active_profile.as_deref());
+        let info_traffic = self.info_traffic.lock().unwrap();
+        for rule in &rules {
+            let ThrottlingRuleTarget::Host(selector) = &rule.target else {
+                continue;
+            };
+            if let HostSelector::Subnet(_) = selector {
+                continue;
+            }
+            let throttled_subnets = self.throttled_hosts.get(selector);
+            for (address, (_, host)) in &info_traffic.addresses_resolved {
+                let Ok(address) = address.parse::<IpAddr>() else {
+                    continue;
+                };
+                let subnet = IpSubnet::from(address);
+                if selector.matches(address, host)
+                    && !throttled_subnets.is_some_and(|subnets| subnets.contains(&subnet))
+                {
+                    hosts_to_throttle.push((selector.clone(), subnet, rule.kbps));
+                }
+            }
+        }
+        drop(info_traffic);
+
+        for (selector, subnet, kbps) in hosts_to_throttle {
+            // each address is only attempted once, as done for processes
+            self.throttled_hosts
+                .entry(selector)
+                .or_default()
+                .insert(subnet);
+            let result = self
+                .traffic_controller
+                .lock()
+                .unwrap()
+                .throttle_host(subnet, kbps.try_into().unwrap());
+            if let Err(err) = result {
+                self.report_throttling_error(&err);
+            }
+        }
+    }
+
     fn update_waiting_dots(&mut self) {
         if self.waiting.len() > 2 {
             self.waiting = String::new();
@@ -616,16 +1429,20 @@ mod tests {
     use std::sync::{Arc, Mutex};
     use std::time::Duration;
 
+    use pcap::Device;
+
     use crate::countries::types::country::Country;
     use crate::gui::components::types::my_modal::MyModal;
     use crate::gui::pages::types::settings_page::SettingsPage;
     use crate::gui::types::message::Message;
     use crate::networking::types::host::Host;
+    use crate::networking::types::host_selector::HostSelector;
     use crate::notifications::types::logged_notification::{
         LoggedNotification, PacketsThresholdExceeded,
     };
     use crate::notifications::types::notifications::{
         BytesNotification, FavoriteNotification, Notification, PacketsNotification,
+        ProcessNotification,
     };
     use crate::notifications::types::sound::Sound;
     use crate::{
@@ -633,6 +1450,63 @@ mod tests {
         RunningPage, Sniffer, Status, StyleType, TransProtocol,
     };
 
+    #[test]
+    fn test_additional_adapters_selection() {
+        let mut sniffer = Sniffer::new(
+            Arc::new(Mutex::new(0)),
+            Arc::new(Mutex::new(InfoTraffic::new())),
+            Arc::new((Mutex::new(Status::Init), Default::default())),
+            &Default::default(),
+            &Default::default(),
+            &Default::default(),
+            Arc::new(Mutex::new(Err(String::new()))),
+        );
+        let main_device = sniffer.device.name.clone();
+
+        // the main adapter and unknown adapters can't be added
+        sniffer.update(Message::AdditionalAdapterSelection(
+            main_device.clone(),
+            true,
+        ));
+        sniffer.update(Message::AdditionalAdapterSelection(
+            "not-an-adapter".to_string(),
+            true,
+        ));
+        assert!(sniffer.additional_devices.is_empty());
+        assert_eq!(sniffer.get_sniffed_devices().len(), 1);
+
+        let Some(other_device) = Device::list()
+            .unwrap()
+            .into_iter()
+            .find(|device| device.name.ne(&main_device))
+        else {
+            return;
+        };
+        sniffer.update(Message::AdditionalAdapterSelection(
+            other_device.name.clone(),
+            true,
+        ));
+        let sniffed_devices: Vec<String> = sniffer
+            .get_sniffed_devices()
+            .into_iter()
+            .map(|device| device.name)
+            .collect();
+        assert_eq!(
+            sniffed_devices,
+            vec![main_device, other_device.name.clone()]
+        );
+        assert_eq!(
+            sniffer.get_config_device().additional_device_names,
+            vec![other_device.name.clone()]
+        );
+
+        sniffer.update(Message::AdditionalAdapterSelection(
+            other_device.name,
+            false,
+        ));
+        assert!(sniffer.additional_devices.is_empty());
+    }
+
     #[test]
     fn test_correctly_update_ip_version() {
         let mut sniffer = Sniffer::new(
@@ -641,6 +1515,7 @@ fn test_correctly_update_ip_version() {
             Arc::new((Mutex::new(Status::Init), Default::default())),
             &Default::default(),
             &Default::default(),
+            &Default::default(),
             Arc::new(Mutex::new(Err(String::new()))),
         );
 
@@ -655,6 +1530,34 @@ fn test_correctly_update_ip_version() {
         assert_eq!(sniffer.filters.ip, IpVersion::Other);
     }
 
+    #[test]
+    fn test_correctly_update_bpf_filter() {
+        let mut sniffer = Sniffer::new(
+            Arc::new(Mutex::new(0)),
+            Arc::new(Mutex::new(InfoTraffic::new())),
+            Arc::new((Mutex::new(Status::Init), Default::default())),
+            &Default::default(),
+            &Default::default(),
+            &Default::default(),
+            Arc::new(Mutex::new(Err(String::new()))),
+        );
+
+        assert_eq!(sniffer.filters.bpf, "");
+        assert_eq!(sniffer.bpf_error, None);
+        sniffer.update(Message::BpfFilter("net 10.0.0.0/8 and not".to_string()));
+        assert_eq!(sniffer.filters.bpf, "net 10.0.0.0/8 and not");
+        assert!(sniffer.bpf_error.is_some());
+        sniffer.update(Message::BpfFilter(
+            "net 10.0.0.0/8 and not port 22".to_string(),
+        ));
+        assert_eq!(sniffer.filters.bpf, "net 10.0.0.0/8 and not port 22");
+        assert_eq!(sniffer.bpf_error, None);
+        assert_eq!(
+            sniffer.get_config_device().bpf_filter,
+            "net 10.0.0.0/8 and not port 22"
+        );
+    }
+
     #[test]
     fn test_correctly_update_transport_protocol() {
         let mut sniffer = Sniffer::new(
@@ -663,6 +1566,7 @@ fn test_correctly_update_transport_protocol() {
             Arc::new((Mutex::new(Status::Init), Default::default())),
             &Default::default(),
             &Default::default(),
+            &Default::default(),
             Arc::new(Mutex::new(Err(String::new()))),
         );
 
@@ -685,6 +1589,7 @@ fn test_correctly_update_application_protocol() {
             Arc::new((Mutex::new(Status::Init), Default::default())),
             &Default::default(),
             &Default::default(),
+            &Default::default(),
             Arc::new(Mutex::new(Err(String::new()))),
         );
 
@@ -707,6 +1612,7 @@ fn test_correctly_update_chart_kind() {
             Arc::new((Mutex::new(Status::Init), Default::default())),
             &Default::default(),
             &Default::default(),
+            &Default::default(),
             Arc::new(Mutex::new(Err(String::new()))),
         );
 
@@ -727,6 +1633,7 @@ fn test_correctly_update_report_kind() {
             Arc::new((Mutex::new(Status::Init), Default::default())),
             &Default::default(),
             &Default::default(),
+            &Default::default(),
             Arc::new(Mutex::new(Err(String::new()))),
         );
 
@@ -749,6 +1656,7 @@ fn test_correctly_update_style() {
             Arc::new((Mutex::new(Status::Init), Default::default())),
             &Default::default(),
             &Default::default(),
+            &Default::default(),
             Arc::new(Mutex::new(Err(String::new()))),
         );
 
@@ -772,6 +1680,7 @@ fn test_waiting_dots_update() {
             Arc::new((Mutex::new(Status::Init), Default::default())),
             &Default::default(),
             &Default::default(),
+            &Default::default(),
             Arc::new(Mutex::new(Err(String::new()))),
         );
 
@@ -794,6 +1703,7 @@ fn test_modify_favorite_connections() {
             Arc::new((Mutex::new(Status::Init), Default::default())),
             &Default::default(),
             &Default::default(),
+            &Default::default(),
             Arc::new(Mutex::new(Err(String::new()))),
         );
         // remove 1
@@ -982,6 +1892,42 @@ fn test_modify_favorite_connections() {
         );
     }
 
+    #[test]
+    fn test_show_host_throttling_modal() {
+        let mut sniffer = Sniffer::new(
+            Arc::new(Mutex::new(0)),
+            Arc::new(Mutex::new(InfoTraffic::new())),
+            Arc::new((Mutex::new(Status::Init), Default::default())),
+            &Default::default(),
+            &Default::default(),
+            &Default::default(),
+            Arc::new(Mutex::new(Err(String::new()))),
+        );
+        let selectors = vec![
+            HostSelector::Domain("example.com".to_string()),
+            HostSelector::Asn(15169),
+        ];
+
+        // the modal replaces the details of a connection
+        sniffer.update(Message::ShowModal(MyModal::ConnectionDetails(0)));
+        sniffer.update(Message::ShowHostThrottlingModal(selectors.clone()));
+        assert_eq!(sniffer.modal, Some(MyModal::HostThrottlingModal));
+        assert_eq!(sniffer.host_throttling_selectors, selectors);
+        sniffer.update(Message::HideModal);
+
+        // but not the other modals
+        sniffer.update(Message::ShowModal(MyModal::Quit));
+        sniffer.update(Message::ShowHostThrottlingModal(vec![]));
+        assert_eq!(sniffer.modal, Some(MyModal::Quit));
+        assert_eq!(sniffer.host_throttling_selectors, selectors);
+        sniffer.update(Message::HideModal);
+
+        // nor the settings
+        sniffer.update(Message::OpenLastSettings);
+        sniffer.update(Message::ShowHostThrottlingModal(vec![]));
+        assert_eq!(sniffer.modal, None);
+    }
+
     #[test]
     fn test_show_and_hide_modal_and_settings() {
         let mut sniffer = Sniffer::new(
@@ -990,6 +1936,7 @@ fn test_show_and_hide_modal_and_settings() {
             Arc::new((Mutex::new(Status::Init), Default::default())),
             &Default::default(),
             &Default::default(),
+            &Default::default(),
             Arc::new(Mutex::new(Err(String::new()))),
         );
 
@@ -1084,6 +2031,7 @@ fn test_correctly_update_language() {
             Arc::new((Mutex::new(Status::Init), Default::default())),
             &Default::default(),
             &Default::default(),
+            &Default::default(),
             Arc::new(Mutex::new(Err(String::new()))),
         );
 
@@ -1108,6 +2056,7 @@ fn test_correctly_update_notification_settings() {
             Arc::new((Mutex::new(Status::Init), Default::default())),
             &Default::default(),
             &Default::default(),
+            &Default::default(),
             Arc::new(Mutex::new(Err(String::new()))),
         );
 
@@ -1141,6 +2090,7 @@ fn test_correctly_update_notification_settings() {
             sniffer.notifications.process_notification,
             ProcessNotification {
                 threshold: None,
+                threshold_type: ChartType::Packets,
                 sound: Sound::Gulp,
                 previous_threshold: 750
             }
@@ -1176,6 +2126,7 @@ fn test_correctly_update_notification_settings() {
             sniffer.notifications.process_notification,
             ProcessNotification {
                 threshold: None,
+                threshold_type: ChartType::Packets,
                 sound: Sound::Gulp,
                 previous_threshold: 750
             }
@@ -1218,6 +2169,7 @@ fn test_correctly_update_notification_settings() {
             sniffer.notifications.process_notification,
             ProcessNotification {
                 threshold: None,
+                threshold_type: ChartType::Packets,
                 sound: Sound::Gulp,
                 previous_threshold: 750
             }
@@ -1261,6 +2213,7 @@ fn test_correctly_update_notification_settings() {
             sniffer.notifications.process_notification,
             ProcessNotification {
                 threshold: Some(1122),
+                threshold_type: ChartType::Packets,
                 sound: Sound::None,
                 previous_threshold: 1122
             }
@@ -1302,6 +2255,7 @@ fn test_correctly_update_notification_settings() {
             sniffer.notifications.process_notification,
             ProcessNotification {
                 threshold: Some(1122),
+                threshold_type: ChartType::Packets,
                 sound: Sound::None,
                 previous_threshold: 1122
             }
@@ -1310,6 +2264,7 @@ fn test_correctly_update_notification_settings() {
         sniffer.update(Message::UpdateNotificationSettings(
             Notification::Process(ProcessNotification {
                 threshold: Some(1122),
+                threshold_type: ChartType::Packets,
                 sound: Sound::None,
                 previous_threshold: 1122,
             }),
@@ -1344,6 +2299,7 @@ fn test_correctly_update_notification_settings() {
             sniffer.notifications.process_notification,
             ProcessNotification {
                 threshold: Some(1122),
+                threshold_type: ChartType::Packets,
                 sound: Sound::None,
                 previous_threshold: 1122
             }
@@ -1358,6 +2314,7 @@ fn test_clear_all_notifications() {
             Arc::new((Mutex::new(Status::Init), Default::default())),
             &Default::default(),
             &Default::default(),
+            &Default::default(),
             Arc::new(Mutex::new(Err(String::new()))),
         );
         sniffer.runtime_data.logged_notifications =
@@ -1387,6 +2344,7 @@ fn test_correctly_switch_running_and_notification_pages() {
             Arc::new((Mutex::new(Status::Init), Default::default())),
             &Default::default(),
             &Default::default(),
+            &Default::default(),
             Arc::new(Mutex::new(Err(String::new()))),
         );
         sniffer.last_focus_time = std::time::Instant::now().sub(Duration::from_millis(400));
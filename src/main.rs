@@ -3,7 +3,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 #![allow(clippy::module_name_repetitions, clippy::too_many_lines)]
 
+use std::sync::atomic::Ordering;
 use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
 use std::{panic, process, thread};
 
 use iced::window::Position;
@@ -11,9 +13,11 @@
 
 use chart::types::chart_type::ChartType;
 use chart::types::traffic_chart::TrafficChart;
+use cli::headless::run_headless;
 use cli::parse_cli_args;
 use configs::types::config_device::ConfigDevice;
 use configs::types::config_settings::ConfigSettings;
+use configs::types::config_throttling::ConfigThrottling;
 use gui::pages::types::running_page::RunningPage;
 use gui::styles::style_constants::FONT_SIZE_BODY;
 use gui::styles::types::palette::get_colors;
@@ -25,9 +29,8 @@
 use networking::types::byte_multiple::ByteMultiple;
 use networking::types::info_traffic::InfoTraffic;
 use networking::types::ip_version::IpVersion;
-use networking::types::traffic_control::TrafficControl;
+use networking::types::traffic_control::{TrafficControl, QUIT_TIMEOUT_SECS};
 use networking::types::trans_protocol::TransProtocol;
-use pcap::Device;
 use report::types::report_sort_type::ReportSortType;
 use secondary_threads::write_report_file::sleep_and_write_report_loop;
 use translations::types::language::Language;
@@ -51,7 +54,7 @@
 ///
 /// It initializes shared variables and loads configuration parameters
 pub fn main() -> iced::Result {
-    parse_cli_args();
+    let cli_options = parse_cli_args();
 
     let current_capture_id1 = Arc::new(Mutex::new(0));
     let current_capture_id2 = current_capture_id1.clone();
@@ -68,9 +71,6 @@ pub fn main() -> iced::Result {
     // to kill the main thread as soon as a secondary thread panics
     let orig_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
-        for device in Device::list().expect("Couldn't get network devices") {
-            TrafficControl::clean_traffic_control_settings(device.name);
-        }
         // invoke the default handler and exit the process
         orig_hook(panic_info);
         process::exit(1);
@@ -92,22 +92,88 @@ pub fn main() -> iced::Result {
     };
 
     thread::Builder::new()
-        .name("thread_check_updates".to_string())
+        .name("thread_write_report".to_string())
         .spawn(move || {
-            set_newer_release_status(&newer_release_available2);
+            sleep_and_write_report_loop(&current_capture_id2, &mutex_map2, &status_pair2);
         })
         .unwrap();
 
+    if cli_options.headless {
+        // to stop the capture when the process is interrupted or terminated,
+        // still printing the final summary and exporting the connections table
+        let current_capture_id3 = current_capture_id1.clone();
+        ctrlc::set_handler(move || {
+            *current_capture_id3.lock().unwrap() += 1;
+        })
+        .unwrap_or(());
+
+        if let Err(err) = run_headless(
+            &cli_options,
+            &config_device,
+            &current_capture_id1,
+            &mutex_map1,
+            &status_pair1,
+        ) {
+            eprintln!("sniffnet: {err}");
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
     thread::Builder::new()
-        .name("thread_write_report".to_string())
+        .name("thread_check_updates".to_string())
         .spawn(move || {
-            sleep_and_write_report_loop(&current_capture_id2, &mutex_map2, &status_pair2);
+            set_newer_release_status(&newer_release_available2);
         })
         .unwrap();
 
     print_cli_welcome_message();
 
-    Sniffer::run(Settings {
+    // a configuration that can't be loaded is reported once the app starts
+    let (config_throttling, config_throttling_error) = match ConfigThrottling::load() {
+        Ok(config_throttling) => (config_throttling, None),
+        Err(cause) => (ConfigThrottling::default(), Some(cause)),
+    };
+
+    let mut sniffer = Sniffer::new(
+        current_capture_id1,
+        mutex_map1,
+        status_pair1,
+        &config_settings,
+        &config_device,
+        &config_throttling,
+        newer_release_available1,
+    );
+    if let Some(cause) = config_throttling_error {
+        sniffer.report_throttling_failure("load the throttling settings", cause);
+    }
+    // apply the settings specified from command line, as if they were inserted in the GUI
+    for message in cli_options.get_gui_messages() {
+        let _ = sniffer.update(message);
+    }
+
+    // to remove the traffic control settings of this instance (leaving the ones of other
+    // instances untouched) when the app panics, is interrupted or is terminated
+    let exit_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |panic_info| {
+        // the settings are found by the tag of this instance, since the lock of the controller
+        // is still held (or poisoned) if the panic occurred while updating them
+        TrafficControl::clean_up_instance();
+        exit_hook(panic_info);
+    }));
+    let quit_requested1 = sniffer.quit_requested.clone();
+    let quit_requested2 = sniffer.quit_requested.clone();
+    ctrlc::set_handler(move || {
+        // the app quits at the next tick, cleaning up and saving the usage of the quotas;
+        // the settings are removed anyway if it doesn't manage to in time
+        quit_requested1.store(true, Ordering::Relaxed);
+        thread::sleep(Duration::from_secs(QUIT_TIMEOUT_SECS));
+        TrafficControl::clean_up_instance();
+        process::exit(130);
+    })
+    .unwrap_or(());
+
+    let result = Sniffer::run(Settings {
         // id needed for Linux Wayland; should match StartupWMClass in .desktop file; see issue #292
         id: Some("sniffnet".to_string()),
         window: window::Settings {
@@ -122,17 +188,15 @@ pub fn main() -> iced::Result {
             icon: None,
             ..Default::default()
         },
-        flags: Sniffer::new(
-            current_capture_id1,
-            mutex_map1,
-            status_pair1,
-            &config_settings,
-            &config_device,
-            newer_release_available1,
-        ),
+        flags: sniffer,
         default_font: Font::with_name("Sarasa Mono SC"),
         default_text_size: FONT_SIZE_BODY,
         antialiasing: false,
-        exit_on_close_request: true,
-    })
+        // the window is closed by the app, after removing the traffic control settings
+        exit_on_close_request: false,
+    });
+    if quit_requested2.load(Ordering::Relaxed) {
+        process::exit(130);
+    }
+    result
 }
@@ -1,28 +1,33 @@
+use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::sync::{Arc, Mutex};
 
-use chrono::Local;
+use chrono::{DateTime, Local, TimeZone};
 use dns_lookup::lookup_addr;
 use etherparse::{Ethernet2Header, IpHeader, TransportHeader};
 use maxminddb::Reader;
-use pcap::{Active, Address, Capture, Device};
+use pcap::{Activated, Active, Address, Capture, Device, Linktype, Offline};
 
 use crate::countries::country_utils::get_country;
 use crate::gui::types::sniffer;
 use crate::networking::types::address_port_pair::AddressPortPair;
 use crate::networking::types::app_protocol::from_port_to_application_protocol;
+use crate::networking::types::capture_source::CaptureSource;
 use crate::networking::types::data_info::DataInfo;
 use crate::networking::types::data_info_host::DataInfoHost;
 use crate::networking::types::host::Host;
 use crate::networking::types::info_address_port_pair::InfoAddressPortPair;
+use crate::networking::types::link_type::LinkType;
 use crate::networking::types::my_device::MyDevice;
+use crate::networking::types::rotating_savefile::RotatingSavefile;
+use crate::networking::types::savefile_options::SavefileOptions;
+use crate::networking::types::socket_cache::{SocketCache, SocketOwner};
 use crate::networking::types::traffic_direction::TrafficDirection;
 use crate::networking::types::traffic_type::TrafficType;
 use crate::utils::asn::asn;
 use crate::utils::formatted_strings::get_domain_from_r_dns;
 use crate::IpVersion::{IPv4, IPv6};
 use crate::{AppProtocol, InfoTraffic, IpVersion, TransProtocol};
-use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo};
 use crate::Sniffer;
 use crate::process::Command;
 
@@ -31,20 +36,30 @@
 
 /// This function analyzes the data link layer header passed as parameter and updates variables
 /// passed by reference on the basis of the packet header content.
+///
+/// Only Ethernet frames carry both the MAC addresses: Linux cooked captures carry the source one,
+/// while the other link types carry none.
 pub fn analyze_link_header(
+    link_type: LinkType,
+    packet: &[u8],
     link_header: Option<Ethernet2Header>,
     mac_address1: &mut String,
     mac_address2: &mut String,
     skip_packet: &mut bool,
 ) {
-    match link_header {
-        Some(header) => {
+    match (link_type, link_header) {
+        (LinkType::Ethernet, Some(header)) => {
             *mac_address1 = mac_from_dec_to_hex(header.source);
             *mac_address2 = mac_from_dec_to_hex(header.destination);
         }
-        _ => {
+        (LinkType::Ethernet, None) => {
             *skip_packet = true;
         }
+        _ => {
+            if let Some(source) = link_type.get_cooked_source_mac(packet) {
+                *mac_address1 = mac_from_dec_to_hex(source);
+            }
+        }
     }
 }
 
@@ -114,12 +129,39 @@ pub fn analyze_transport_header(
                 *application_protocol = from_port_to_application_protocol(*port2);
             }
         }
-        _ => {
+        Some(TransportHeader::Icmpv4(icmp_header)) => {
+            (*port1, *port2) = get_icmp_key(&icmp_header.to_bytes(), ICMP_ECHO_TYPES);
+            *transport_protocol = TransProtocol::ICMP;
+            *application_protocol = AppProtocol::Other;
+        }
+        Some(TransportHeader::Icmpv6(icmp_header)) => {
+            (*port1, *port2) = get_icmp_key(&icmp_header.to_bytes(), ICMPV6_ECHO_TYPES);
+            *transport_protocol = TransProtocol::ICMPv6;
+            *application_protocol = AppProtocol::Other;
+        }
+        None => {
             *skip_packet = true;
         }
     }
 }
 
+/// Types of the ICMP echo request and reply messages
+const ICMP_ECHO_TYPES: [u8; 2] = [8, 0];
+/// Types of the ICMPv6 echo request and reply messages
+const ICMPV6_ECHO_TYPES: [u8; 2] = [128, 129];
+
+/// Returns the values identifying an ICMP or ICMPv6 message in place of the ports:
+/// its type and code, and the identifier of echo messages (zero for the other messages)
+fn get_icmp_key(header: &[u8], echo_types: [u8; 2]) -> (u16, u16) {
+    let (icmp_type, code) = (header[0], header[1]);
+    let identifier = if echo_types.contains(&icmp_type) {
+        u16::from_be_bytes([header[4], header[5]])
+    } else {
+        0
+    };
+    (u16::from_be_bytes([icmp_type, code]), identifier)
+}
+
 /// Function to insert the source and destination of a packet into the shared map containing the analyzed traffic.
 pub fn modify_or_insert_in_map(
     info_traffic_mutex: &Arc<Mutex<InfoTraffic>>,
@@ -128,13 +170,13 @@ pub fn modify_or_insert_in_map(
     mac_addresses: (String, String),
     exchanged_bytes: u128,
     application_protocol: AppProtocol,
+    timestamp: DateTime<Local>,
+    socket_cache: Option<&Arc<Mutex<SocketCache>>>,
     pid_filter: String,
     uid_filter: String,
     port_filter: String,
     filter_packet: &mut bool,
 ) -> InfoAddressPortPair {
-    let now = Local::now();
-    let mut traffic_direction = TrafficDirection::default();
     let source_ip = &key.address1;
     let destination_ip = &key.address2;
     let very_long_address = source_ip.len() > 25 || destination_ip.len() > 25;
@@ -147,15 +189,23 @@ pub fn modify_or_insert_in_map(
         .get_index_of(key)
         .unwrap_or(len);
 
-    let mut uid = None;
-    let mut pids = None;
-    if index == len {
+    let (traffic_direction, known_owner, socket_owner) = if index == len {
         // first occurrence of key
 
-        // update device addresses
+        // update device addresses; the local addresses of an imported file are set
+        // once for all when the import starts
         let mut my_interface_addresses = Vec::new();
-        for dev in Device::list().expect("Error retrieving device list\r\n") {
-            if dev.name.eq(&my_device.name) {
+        let adapters = if socket_cache.is_some() {
+            Device::list().expect("Error retrieving device list\r\n")
+        } else {
+            my_interface_addresses = my_device.addresses.lock().unwrap().clone();
+            Vec::new()
+        };
+        for dev in adapters {
+            // the Linux "any" pseudo-device captures the traffic of all the adapters
+            if my_device.is_any() {
+                my_interface_addresses.extend(dev.addresses);
+            } else if dev.name.eq(&my_device.name) {
                 let mut my_interface_addresses_mutex = my_device.addresses.lock().unwrap();
                 *my_interface_addresses_mutex = dev.addresses.clone();
                 drop(my_interface_addresses_mutex);
@@ -163,20 +213,39 @@ pub fn modify_or_insert_in_map(
                 break;
             }
         }
+        if my_device.is_any() && socket_cache.is_some() {
+            *my_device.addresses.lock().unwrap() = my_interface_addresses.clone();
+        }
         // determine traffic direction
-        traffic_direction =
+        let traffic_direction =
             get_traffic_direction(source_ip, destination_ip, &my_interface_addresses);
-        
 
-        let port = if traffic_direction == TrafficDirection::Outgoing {
-            key.port1
+        (
+            traffic_direction,
+            None,
+            get_socket_owner(socket_cache, key, traffic_direction),
+        )
+    } else {
+        let info_traffic = info_traffic_mutex.lock().unwrap();
+        let info = &info_traffic.map[index];
+        let traffic_direction = info.traffic_direction;
+        let known_owner = (info.uid, info.pids.clone(), info.process_name.clone());
+        drop(info_traffic);
+        // the socket may have been opened after the last cache refresh: try again
+        let socket_owner = if known_owner.1.is_none() {
+            get_socket_owner(socket_cache, key, traffic_direction)
         } else {
-            key.port2
+            None
         };
-
-        uid = get_uid(port, key.trans_protocol);
-        pids = get_pid(port, key.trans_protocol);
-        
+        (traffic_direction, Some(known_owner), socket_owner)
+    };
+    let (uid, pids, process_name) = match &socket_owner {
+        Some(owner) => (
+            Some(owner.uid),
+            Some(owner.pids.clone()),
+            owner.get_process_name(),
+        ),
+        None => known_owner.unwrap_or_default(),
     };
     // pid filter only accepts one pid for now and it is of type String
     if let Some(okay_pid) = pid_filter.trim().parse::<u32>().ok() {
@@ -204,9 +273,13 @@ pub fn modify_or_insert_in_map(
             return InfoAddressPortPair::default();
         }
     }
-    // port filter only accepts one port for now and it is of type String
+    // port filter only accepts one port for now and it is of type String;
+    // ICMP messages have no ports, so they never match it
     if let Some(okay_port) = port_filter.trim().parse::<u16>().ok() {
-        if okay_port != key.port1 && okay_port != key.port2 {
+        if !key
+            .get_ports()
+            .is_some_and(|(port1, port2)| okay_port == port1 || okay_port == port2)
+        {
             *filter_packet = true;
             return InfoAddressPortPair::default();
         }
@@ -222,25 +295,39 @@ pub fn modify_or_insert_in_map(
         .and_modify(|info| {
             info.transmitted_bytes += exchanged_bytes;
             info.transmitted_packets += 1;
-            info.final_timestamp = now;
+            info.final_timestamp = timestamp;
+            if info.pids.is_none() {
+                info.uid = uid;
+                info.pids = pids.clone();
+                info.process_name = process_name.clone();
+            }
         })
         .or_insert(InfoAddressPortPair {
             mac_address1: mac_addresses.0,
             mac_address2: mac_addresses.1,
             transmitted_bytes: exchanged_bytes,
             transmitted_packets: 1,
-            initial_timestamp: now,
-            final_timestamp: now,
+            initial_timestamp: timestamp,
+            final_timestamp: timestamp,
             app_protocol: application_protocol,
             very_long_address,
             traffic_direction,
             index,
             uid,
             pids,
+            process_name,
         })
         .clone();
 
     info_traffic.addresses_last_interval.insert(index);
+    if let Some(owner) = socket_owner {
+        if let Some(username) = owner.username {
+            info_traffic.usernames.insert(owner.uid, username);
+        }
+        for process in owner.processes {
+            info_traffic.processes.insert(process.pid, process);
+        }
+    }
 
     if let Some(host_info) = info_traffic
         .addresses_resolved
@@ -503,11 +590,14 @@ pub fn is_my_address(address_to_lookup: &String, my_interface_addresses: &Vec<Ad
 }
 
 /// Determines if the capture opening resolves into an Error
-pub fn get_capture_result(device: &MyDevice) -> (Option<String>, Option<Capture<Active>>) {
+pub fn get_capture_result(
+    device: &MyDevice,
+    snaplen: i32,
+) -> (Option<String>, Option<Capture<Active>>) {
     let cap_result = Capture::from_device(&*device.name)
         .expect("Capture initialization error\n\r")
         .promisc(false)
-        .snaplen(256) //limit stored packets slice dimension (to keep more in the buffer)
+        .snaplen(snaplen) //limit stored packets slice dimension (to keep more in the buffer)
         .immediate_mode(true) //parse packets ASAP!
         .open();
     if cap_result.is_err() {
@@ -518,41 +608,206 @@ pub fn get_capture_result(device: &MyDevice) -> (Option<String>, Option<Capture<
     }
 }
 
-// using netstat to get the uid of the process that is using a specific port
-pub fn get_uid(port: u16, trans_protocol: TransProtocol) -> Option<u32> {
-    let af_falgs = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
-    let protocol_flags = if trans_protocol == TransProtocol::TCP {
-        ProtocolFlags::TCP
-    } else {
-        ProtocolFlags::UDP
-    };
-    let socket_info = get_sockets_info(af_falgs, protocol_flags).unwrap(); 
+/// Determines if opening the pcap/pcapng file at the given path resolves into an Error
+pub fn get_offline_capture_result(path: &str) -> (Option<String>, Option<Capture<Offline>>) {
+    match Capture::from_file(path) {
+        Ok(cap) => (None, Some(cap)),
+        Err(err) => (Some(format!("{path}: {err}")), None),
+    }
+}
+
+/// Link types commonly used by network adapters, against which BPF expressions are validated
+/// when the adapters to be captured are not known yet
+pub const COMMON_DATALINKS: [Linktype; 5] = [
+    Linktype::ETHERNET,
+    Linktype::LINUX_SLL,
+    Linktype::IEEE802_11_RADIOTAP,
+    Linktype::RAW,
+    Linktype::NULL,
+];
+
+/// Checks that the given BPF expression compiles for each of the given link types;
+/// an empty expression is always valid
+pub fn check_bpf_filter(expression: &str, datalinks: &[Linktype]) -> Result<(), String> {
+    if expression.trim().is_empty() {
+        return Ok(());
+    }
+    if expression.contains('\0') {
+        return Err("the expression can't contain NUL characters".to_string());
+    }
+    for datalink in datalinks {
+        Capture::dead(*datalink)
+            .and_then(|cap| cap.compile(expression, true))
+            .map_err(|err| match datalinks {
+                [_] => err.to_string(),
+                _ => format!("{err} ({} link type)", get_datalink_name(*datalink)),
+            })?;
+    }
+    Ok(())
+}
 
-    for socket in socket_info {
-        if socket.local_port() == port {
-            return Some(socket.uid);
+/// Checks that the given BPF expression compiles for at least one of the common link types,
+/// to catch syntax errors before the link types of the adapters are known
+pub fn check_bpf_syntax(expression: &str) -> Result<(), String> {
+    let mut result = Ok(());
+    for datalink in COMMON_DATALINKS {
+        result = check_bpf_filter(expression, &[datalink]);
+        if result.is_ok() {
+            break;
         }
     }
-    None
+    result
 }
 
-// using netstat to get the pids of the process that is using a specific port
-pub fn get_pid (port: u16, trans_protocol: TransProtocol) -> Option<Vec<u32>> {
-    let af_falgs = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
-    let protocol_flags = if trans_protocol == TransProtocol::TCP {
-        ProtocolFlags::TCP
-    } else {
-        ProtocolFlags::UDP
+/// Returns the link type of the given adapter (or of the file imported in its place);
+/// adapters that can't be opened, e.g. because of missing privileges, are assumed to be Ethernet
+pub fn get_datalink(device: &MyDevice, capture_source: &CaptureSource) -> Linktype {
+    let datalink = match capture_source {
+        CaptureSource::Live => Capture::from_device(device.name.as_str())
+            .and_then(|cap| cap.open())
+            .map(|cap| cap.get_datalink()),
+        CaptureSource::File(path) => Capture::from_file(path).map(|cap| cap.get_datalink()),
     };
+    datalink.unwrap_or(Linktype::ETHERNET)
+}
 
-    let socket_info = get_sockets_info(af_falgs, protocol_flags).unwrap();
+/// Parses the local addresses entered by the user for an imported file,
+/// separated by commas or spaces
+pub fn parse_local_addresses(input: &str) -> Result<Vec<IpAddr>, String> {
+    input
+        .split(|ch: char| ch == ',' || ch.is_whitespace())
+        .filter(|address| !address.is_empty())
+        .map(|address| {
+            address
+                .parse()
+                .map_err(|_| format!("'{address}' is not a valid IP address"))
+        })
+        .collect()
+}
+
+/// Returns the local addresses of the imported file: the ones entered by the user if any,
+/// otherwise the ones inferred from the file itself
+pub fn get_file_local_addresses(path: &str, input: &str) -> Vec<IpAddr> {
+    match parse_local_addresses(input) {
+        Ok(addresses) if !addresses.is_empty() => addresses,
+        _ => infer_local_addresses(path),
+    }
+}
 
-    for socket in socket_info {
-        if socket.local_port() == port {
-            return Some(socket.associated_pids);
+/// Infers the local addresses of the host a capture file was recorded on:
+/// for each IP version, the unicast address involved in most of the packets is chosen
+pub fn infer_local_addresses(path: &str) -> Vec<IpAddr> {
+    let Ok(mut cap) = Capture::from_file(path) else {
+        return Vec::new();
+    };
+    let link_type = LinkType::from_datalink(cap.get_datalink());
+    let mut occurrences: HashMap<IpAddr, usize> = HashMap::new();
+    while let Ok(packet) = cap.next_packet() {
+        let addresses: [IpAddr; 2] = match link_type
+            .get_packet_headers(&packet)
+            .and_then(|headers| headers.ip)
+        {
+            Some(IpHeader::Version4(header, _)) => {
+                [header.source.into(), header.destination.into()]
+            }
+            Some(IpHeader::Version6(header, _)) => {
+                [header.source.into(), header.destination.into()]
+            }
+            None => continue,
+        };
+        for address in addresses {
+            let is_unicast = match address {
+                IpAddr::V4(ipv4) => {
+                    !ipv4.is_multicast() && !ipv4.is_broadcast() && !ipv4.is_unspecified()
+                }
+                IpAddr::V6(ipv6) => !ipv6.is_multicast() && !ipv6.is_unspecified(),
+            };
+            if is_unicast {
+                *occurrences.entry(address).or_default() += 1;
+            }
         }
     }
-    None
+    let most_frequent = |is_ipv4: bool| {
+        occurrences
+            .iter()
+            .filter(|(address, _)| address.is_ipv4() == is_ipv4)
+            .max_by_key(|(address, count)| (**count, std::cmp::Reverse(**address)))
+            .map(|(address, _)| *address)
+    };
+    [most_frequent(true), most_frequent(false)]
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+fn get_datalink_name(datalink: Linktype) -> String {
+    datalink
+        .get_name()
+        .unwrap_or_else(|_| datalink.0.to_string())
+}
+
+/// Opens the capture of the given adapter (or of the file imported in its place),
+/// together with the savefile where its packets are dumped, if requested;
+/// the given BPF expression (if any) is compiled into the capture
+pub fn open_capture(
+    device: &MyDevice,
+    capture_source: &CaptureSource,
+    bpf_filter: &str,
+    savefile_options: &SavefileOptions,
+) -> Result<(Capture<dyn Activated>, Option<RotatingSavefile>), String> {
+    let (pcap_error, cap): (Option<String>, Option<Capture<dyn Activated>>) = match capture_source
+    {
+        CaptureSource::Live => {
+            let (pcap_error, cap) = get_capture_result(device, savefile_options.get_snaplen());
+            (pcap_error, cap.map(Into::into))
+        }
+        CaptureSource::File(path) => {
+            let (pcap_error, cap) = get_offline_capture_result(path);
+            (pcap_error, cap.map(Into::into))
+        }
+    };
+    let mut cap = match (pcap_error, cap) {
+        (None, Some(cap)) => cap,
+        (Some(err), _) => return Err(err),
+        (None, None) => return Err(format!("{}: couldn't open the capture", device.name)),
+    };
+    if !bpf_filter.trim().is_empty() {
+        check_bpf_filter(bpf_filter, &[cap.get_datalink()])
+            .and_then(|()| cap.filter(bpf_filter, true).map_err(|err| err.to_string()))
+            .map_err(|err| format!("Invalid BPF filter '{bpf_filter}': {err}"))?;
+    }
+    let savefile = if savefile_options.is_enabled() {
+        let savefile = RotatingSavefile::new(savefile_options, cap.get_datalink())
+            .map_err(|err| format!("Couldn't create the pcap savefile: {err}"))?;
+        Some(savefile)
+    } else {
+        None
+    };
+    Ok((cap, savefile))
+}
+
+/// Converts the timestamp of a captured packet (seconds and microseconds since the epoch)
+/// into a local date time; it falls back to the current time if the timestamp is not valid
+pub fn get_packet_timestamp(seconds: i64, microseconds: i64) -> DateTime<Local> {
+    u32::try_from(microseconds * 1000)
+        .ok()
+        .and_then(|nanoseconds| Local.timestamp_opt(seconds, nanoseconds).single())
+        .unwrap_or_else(Local::now)
+}
+
+/// Returns the user and the processes owning the local socket involved in the given connection;
+/// nothing is returned if the packets don't come from a live capture
+/// (i.e., the socket cache is not available)
+fn get_socket_owner(
+    socket_cache: Option<&Arc<Mutex<SocketCache>>>,
+    key: &AddressPortPair,
+    traffic_direction: TrafficDirection,
+) -> Option<SocketOwner> {
+    socket_cache?
+        .lock()
+        .unwrap()
+        .get_owner(key, traffic_direction)
+        .cloned()
 }
 
 /// Converts a MAC address in its hexadecimal form
@@ -669,14 +924,73 @@ pub fn get_address_to_lookup(key: &AddressPortPair, traffic_direction: TrafficDi
 mod tests {
     use std::net::IpAddr;
 
-    use pcap::Address;
+    use pcap::{Address, Linktype};
+
+    use chrono::{Local, TimeZone};
+
+    use etherparse::{
+        IcmpEchoHeader, Icmpv4Header, Icmpv4Type, Icmpv6Header, Icmpv6Type, TransportHeader,
+    };
 
     use crate::networking::manage_packets::{
+        analyze_transport_header, check_bpf_filter, check_bpf_syntax, get_packet_timestamp,
         get_traffic_direction, get_traffic_type, ipv6_from_long_dec_to_short_hex,
-        is_local_connection, mac_from_dec_to_hex,
+        is_local_connection, mac_from_dec_to_hex, parse_local_addresses,
     };
     use crate::networking::types::traffic_direction::TrafficDirection;
     use crate::networking::types::traffic_type::TrafficType;
+    use crate::{AppProtocol, TransProtocol};
+
+    fn analyze_icmp_header(header: TransportHeader) -> (u16, u16, TransProtocol, bool) {
+        let mut port1 = 0;
+        let mut port2 = 0;
+        let mut application_protocol = AppProtocol::Other;
+        let mut transport_protocol = TransProtocol::Other;
+        let mut skip_packet = false;
+        analyze_transport_header(
+            Some(header),
+            &mut port1,
+            &mut port2,
+            &mut application_protocol,
+            &mut transport_protocol,
+            &mut skip_packet,
+        );
+        (port1, port2, transport_protocol, skip_packet)
+    }
+
+    #[test]
+    fn icmp_messages_keyed_by_type_and_code() {
+        let echo = IcmpEchoHeader { id: 4660, seq: 7 };
+        assert_eq!(
+            analyze_icmp_header(TransportHeader::Icmpv4(Icmpv4Header::new(
+                Icmpv4Type::EchoRequest(echo)
+            ))),
+            (0x0800, 4660, TransProtocol::ICMP, false)
+        );
+        assert_eq!(
+            analyze_icmp_header(TransportHeader::Icmpv4(Icmpv4Header::new(
+                Icmpv4Type::TimeExceeded(etherparse::icmpv4::TimeExceededCode::TtlExceededInTransit)
+            ))),
+            (0x0b00, 0, TransProtocol::ICMP, false)
+        );
+        assert_eq!(
+            analyze_icmp_header(TransportHeader::Icmpv6(Icmpv6Header::new(
+                Icmpv6Type::EchoReply(echo)
+            ))),
+            (0x8100, 4660, TransProtocol::ICMPv6, false)
+        );
+        // neighbor solicitation
+        assert_eq!(
+            analyze_icmp_header(TransportHeader::Icmpv6(Icmpv6Header::new(
+                Icmpv6Type::Unknown {
+                    type_u8: 135,
+                    code_u8: 0,
+                    bytes5to8: [0; 4],
+                }
+            ))),
+            (0x8700, 0, TransProtocol::ICMPv6, false)
+        );
+    }
 
     #[test]
     fn mac_simple_test() {
@@ -690,6 +1004,24 @@ fn mac_all_zero_test() {
         assert_eq!(result, "00:00:00:00:00:00".to_string());
     }
 
+    #[test]
+    fn packet_timestamp_test() {
+        let result = get_packet_timestamp(1_690_000_000, 250_000);
+        assert_eq!(
+            result,
+            Local.timestamp_opt(1_690_000_000, 250_000_000).unwrap()
+        );
+        assert_eq!(result.timestamp(), 1_690_000_000);
+        assert_eq!(result.timestamp_subsec_micros(), 250_000);
+    }
+
+    #[test]
+    fn packet_timestamp_invalid_microseconds_test() {
+        let before = Local::now();
+        let result = get_packet_timestamp(1_690_000_000, -1);
+        assert!(result >= before);
+    }
+
     #[test]
     fn ipv6_simple_test() {
         let result = ipv6_from_long_dec_to_short_hex([
@@ -1138,4 +1470,50 @@ fn is_local_connection_ipv6_link_local_test() {
         let result3 = is_local_connection("fe70::8b1:1234:5678:d065", &address_vec);
         assert_eq!(result3, false);
     }
+
+    #[test]
+    fn bpf_filters_validation() {
+        let ethernet = [Linktype::ETHERNET];
+        assert!(check_bpf_filter("", &ethernet).is_ok());
+        assert!(check_bpf_filter("   ", &ethernet).is_ok());
+        assert!(check_bpf_filter("net 10.0.0.0/8 and not port 22", &ethernet).is_ok());
+        assert!(check_bpf_filter("icmp or (tcp and dst port 443)", &ethernet).is_ok());
+
+        assert!(check_bpf_filter("port 99999", &ethernet).is_err());
+        assert!(check_bpf_filter("net 10.0.0.0/8 and", &ethernet).is_err());
+        assert!(check_bpf_filter("hots 10.0.0.1", &ethernet).is_err());
+        assert!(check_bpf_filter("port 22\0", &ethernet).is_err());
+
+        // the expression is validated against the link type of each adapter
+        let ether_host = "ether host 00:11:22:33:44:55";
+        assert!(check_bpf_filter(ether_host, &ethernet).is_ok());
+        assert!(
+            check_bpf_filter(ether_host, &[Linktype::ETHERNET, Linktype::RAW])
+                .is_err_and(|err| err.ends_with("(RAW link type)"))
+        );
+        let wlan_addr = "wlan addr1 00:11:22:33:44:55";
+        assert!(check_bpf_filter(wlan_addr, &ethernet).is_err());
+        assert!(check_bpf_filter(wlan_addr, &[Linktype::IEEE802_11_RADIOTAP]).is_ok());
+
+        // before the adapters are known, only syntax errors are reported
+        assert!(check_bpf_syntax(wlan_addr).is_ok());
+        assert!(check_bpf_syntax("net 10.0.0.0/8 and").is_err());
+    }
+    #[test]
+    fn test_parse_local_addresses() {
+        assert_eq!(parse_local_addresses(""), Ok(vec![]));
+        assert_eq!(parse_local_addresses("  "), Ok(vec![]));
+        assert_eq!(
+            parse_local_addresses("192.168.1.10, fe80::1 10.0.0.2"),
+            Ok(vec![
+                "192.168.1.10".parse::<IpAddr>().unwrap(),
+                "fe80::1".parse::<IpAddr>().unwrap(),
+                "10.0.0.2".parse::<IpAddr>().unwrap()
+            ])
+        );
+        assert_eq!(
+            parse_local_addresses("192.168.1.10,192.168.1"),
+            Err("'192.168.1' is not a valid IP address".to_string())
+        );
+    }
 }
\ No newline at end of file
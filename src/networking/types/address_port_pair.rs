@@ -10,13 +10,19 @@ pub struct AddressPortPair {
     /// Network layer IPv4 or IPv6 source address.
     pub address1: String,
     /// Transport layer source port number (in the range 0..=65535).
+    ///
+    /// For ICMP and ICMPv6, the type (high byte) and code (low byte) of the messages.
     pub port1: u16,
     /// Network layer IPv4 or IPv6 destination address.
     pub address2: String,
     /// Transport layer destination port number (in the range 0..=65535).
+    ///
+    /// For ICMP and ICMPv6, the identifier of echo messages (zero for the other messages).
     pub port2: u16,
-    ///  Transport layer protocol carried through the associate address:port pair (TCP or UPD).
+    ///  Transport layer protocol carried through the associate address:port pair (TCP, UDP, ICMP or ICMPv6).
     pub trans_protocol: TransProtocol,
+    /// Name of the network adapter on which the packets were observed.
+    pub interface: String,
 }
 
 impl AddressPortPair {
@@ -27,12 +33,15 @@ impl AddressPortPair {
     /// * `address` - A string representing the network layer IPv4 or IPv6 address.
     ///
     /// * `port` - An integer representing the transport layer port number (in the range 0..=65535).
+    ///
+    /// * `interface` - The name of the network adapter on which the packets were observed.
     pub fn new(
         address1: String,
         port1: u16,
         address2: String,
         port2: u16,
         trans_protocol: TransProtocol,
+        interface: String,
     ) -> Self {
         AddressPortPair {
             address1,
@@ -40,6 +49,32 @@ pub fn new(
             address2,
             port2,
             trans_protocol,
+            interface,
+        }
+    }
+
+    /// Returns the source and destination ports, if the transport protocol uses them
+    pub fn get_ports(&self) -> Option<(u16, u16)> {
+        (!self.trans_protocol.is_icmp()).then_some((self.port1, self.port2))
+    }
+
+    /// Returns the type and code of ICMP and ICMPv6 messages
+    pub fn get_icmp_type_code(&self) -> Option<(u8, u8)> {
+        let [icmp_type, code] = self.port1.to_be_bytes();
+        self.trans_protocol.is_icmp().then_some((icmp_type, code))
+    }
+
+    /// Returns the identifier of ICMP and ICMPv6 echo messages (zero for the other messages)
+    pub fn get_icmp_identifier(&self) -> Option<u16> {
+        self.trans_protocol.is_icmp().then_some(self.port2)
+    }
+
+    /// Returns the labels of the source and destination ports; for ICMP and ICMPv6,
+    /// the type/code and the echo identifier of the messages
+    pub fn get_port_labels(&self) -> (String, String) {
+        match self.get_icmp_type_code() {
+            Some((icmp_type, code)) => (format!("{icmp_type}/{code}"), format!("#{}", self.port2)),
+            None => (self.port1.to_string(), self.port2.to_string()),
         }
     }
 
@@ -50,18 +85,60 @@ pub fn print_gui(&self) -> String {
 
 impl fmt::Display for AddressPortPair {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (port1, port2) = self.get_port_labels();
         if self.address1.len() > 25 || self.address2.len() > 25 {
             write!(
                 f,
                 "|{:^45}|{:>8}  |{:^45}|{:>8}  |   {}   |",
-                self.address1, self.port1, self.address2, self.port2, self.trans_protocol
+                self.address1, port1, self.address2, port2, self.trans_protocol
             )
         } else {
             write!(
                 f,
                 "|{:^25}|{:>8}  |{:^25}|{:>8}  |   {}   |",
-                self.address1, self.port1, self.address2, self.port2, self.trans_protocol
+                self.address1, port1, self.address2, port2, self.trans_protocol
             )
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::networking::types::address_port_pair::AddressPortPair;
+    use crate::TransProtocol;
+
+    #[test]
+    fn test_icmp_pair_has_no_ports() {
+        let ping = AddressPortPair::new(
+            "192.168.1.2".to_string(),
+            0x0800,
+            "8.8.8.8".to_string(),
+            4660,
+            TransProtocol::ICMP,
+            "eth0".to_string(),
+        );
+        assert_eq!(ping.get_ports(), None);
+        assert_eq!(ping.get_icmp_type_code(), Some((8, 0)));
+        assert_eq!(ping.get_icmp_identifier(), Some(4660));
+        assert_eq!(
+            ping.get_port_labels(),
+            ("8/0".to_string(), "#4660".to_string())
+        );
+
+        let https = AddressPortPair::new(
+            "192.168.1.2".to_string(),
+            50000,
+            "8.8.8.8".to_string(),
+            443,
+            TransProtocol::TCP,
+            "eth0".to_string(),
+        );
+        assert_eq!(https.get_ports(), Some((50000, 443)));
+        assert_eq!(https.get_icmp_type_code(), None);
+        assert_eq!(https.get_icmp_identifier(), None);
+        assert_eq!(
+            https.get_port_labels(),
+            ("50000".to_string(), "443".to_string())
+        );
+    }
+}
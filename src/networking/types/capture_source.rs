@@ -0,0 +1,43 @@
+//! Module defining the `CaptureSource` enum, which represents where the parsed packets come from.
+
+/// Origin of the packets parsed by the application
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CaptureSource {
+    /// Live capture on the selected network adapter
+    Live,
+    /// Offline capture replayed from the pcap/pcapng file at the given path
+    File(String),
+}
+
+impl CaptureSource {
+    /// Builds the capture source from the path inserted by the user: an empty path means live capture
+    pub fn from_import_path(path: &str) -> Self {
+        if path.trim().is_empty() {
+            CaptureSource::Live
+        } else {
+            CaptureSource::File(path.trim().to_string())
+        }
+    }
+
+    /// Returns true if packets are read from a file instead of a network adapter
+    pub fn is_offline(&self) -> bool {
+        matches!(self, CaptureSource::File(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::networking::types::capture_source::CaptureSource;
+
+    #[test]
+    fn test_capture_source_from_import_path() {
+        assert_eq!(CaptureSource::from_import_path(""), CaptureSource::Live);
+        assert_eq!(CaptureSource::from_import_path("   "), CaptureSource::Live);
+        assert_eq!(
+            CaptureSource::from_import_path(" /tmp/server.pcapng "),
+            CaptureSource::File("/tmp/server.pcapng".to_string())
+        );
+        assert!(!CaptureSource::Live.is_offline());
+        assert!(CaptureSource::File("dump.pcap".to_string()).is_offline());
+    }
+}
@@ -0,0 +1,178 @@
+//! Module defining the `CgroupHierarchy` enum, which represents the cgroup hierarchy used
+//! to classify the traffic of the throttled processes.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::networking::types::traffic_control::get_instance_tag;
+
+/// Mount point of the cgroup filesystems
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Cgroup hierarchy used to classify the traffic of the throttled processes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CgroupHierarchy {
+    /// Unified hierarchy (cgroup v2), mounted at the given path:
+    /// packets are matched by the path of the cgroup of their socket
+    Unified(PathBuf),
+    /// Legacy `net_cls` controller (cgroup v1), mounted at the given path:
+    /// packets are matched by the class ID assigned to the cgroup of their socket
+    NetCls(PathBuf),
+}
+
+impl CgroupHierarchy {
+    /// Detects the hierarchy to use, preferring the unified one (which is also looked for
+    /// in the `unified` directory of hybrid setups) and falling back to `net_cls`
+    pub fn detect(cgroup_root: &Path) -> Self {
+        [cgroup_root.to_path_buf(), cgroup_root.join("unified")]
+            .into_iter()
+            .find(|root| root.join("cgroup.controllers").exists())
+            .map_or_else(|| Self::NetCls(cgroup_root.join("net_cls")), Self::Unified)
+    }
+
+    /// Returns the path where the hierarchy is mounted, which is its root cgroup
+    fn get_root(&self) -> &Path {
+        match self {
+            CgroupHierarchy::Unified(root) | CgroupHierarchy::NetCls(root) => root,
+        }
+    }
+
+    /// Returns the path of the cgroup created for the given process
+    pub fn get_process_cgroup(&self, pid: u32) -> PathBuf {
+        self.get_root().join(get_process_cgroup_name(pid))
+    }
+
+    /// Moves the processes of the cgroup created for the given process back to the root
+    /// cgroup, and removes it
+    pub fn release_process_cgroup(&self, pid: u32) -> io::Result<()> {
+        release_cgroup(self.get_root(), &self.get_process_cgroup(pid))
+    }
+
+    /// Releases the cgroups created for the throttled processes by the instances of the app
+    /// whose tag satisfies the predicate, including the ones left by a previous run;
+    /// failures are ignored
+    pub fn release_process_cgroups(&self, is_released: impl Fn(&str) -> bool) {
+        let Ok(entries) = fs::read_dir(self.get_root()) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            if get_cgroup_instance_tag(&entry.file_name().to_string_lossy())
+                .is_some_and(&is_released)
+            {
+                let _ = release_cgroup(self.get_root(), &entry.path());
+            }
+        }
+    }
+
+    /// Returns the arguments of the `iptables` match selecting the packets sent by the cgroup
+    /// of the given process, whose class ID is only needed by the `net_cls` hierarchy
+    pub fn get_iptables_match(&self, pid: u32, classid: u16) -> [String; 4] {
+        let (option, value) = match self {
+            CgroupHierarchy::Unified(_) => ("--path", get_process_cgroup_name(pid)),
+            CgroupHierarchy::NetCls(_) => ("--cgroup", get_net_cls_classid(classid)),
+        };
+        [
+            "-m".to_string(),
+            "cgroup".to_string(),
+            option.to_string(),
+            value,
+        ]
+    }
+}
+
+/// Returns the value of `net_cls.classid` corresponding to the tc class `1:<classid>`
+pub fn get_net_cls_classid(classid: u16) -> String {
+    format!("0x1{classid:04x}")
+}
+
+/// Returns the name of the cgroup created for the given process, made of the tag of this
+/// instance of the app and of the process ID
+fn get_process_cgroup_name(pid: u32) -> String {
+    format!("{}_{pid}", get_instance_tag())
+}
+
+/// Returns the tag of the instance of the app that created the cgroup with the given name
+/// (the part preceding the process ID), if any
+fn get_cgroup_instance_tag(name: &str) -> Option<&str> {
+    name.rsplit_once('_').map(|(tag, _)| tag)
+}
+
+/// Moves the processes of a cgroup to the root cgroup, and removes the (then empty) cgroup
+fn release_cgroup(root: &Path, cgroup: &Path) -> io::Result<()> {
+    let pids = fs::read_to_string(cgroup.join("cgroup.procs"))?;
+    for pid in pids.split_whitespace() {
+        // processes can only be written one at a time, and may have exited in the meantime
+        let _ = fs::write(root.join("cgroup.procs"), pid);
+    }
+    fs::remove_dir(cgroup)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use crate::networking::types::cgroup_hierarchy::{get_cgroup_instance_tag, CgroupHierarchy};
+
+    #[test]
+    fn test_detect_cgroup_hierarchy() {
+        let root = std::env::temp_dir().join(format!("sniffnet_cgroups_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("unified")).unwrap();
+
+        assert_eq!(
+            CgroupHierarchy::detect(&root),
+            CgroupHierarchy::NetCls(root.join("net_cls"))
+        );
+
+        fs::write(root.join("unified/cgroup.controllers"), "").unwrap();
+        assert_eq!(
+            CgroupHierarchy::detect(&root),
+            CgroupHierarchy::Unified(root.join("unified"))
+        );
+
+        fs::write(root.join("cgroup.controllers"), "cpu io memory pids").unwrap();
+        assert_eq!(
+            CgroupHierarchy::detect(&root),
+            CgroupHierarchy::Unified(root.clone())
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_cgroup_iptables_match() {
+        let name = format!("sniffnet-{}_1234", std::process::id());
+        let unified = CgroupHierarchy::Unified(Path::new("/sys/fs/cgroup").to_path_buf());
+        assert_eq!(
+            unified.get_process_cgroup(1234),
+            Path::new("/sys/fs/cgroup").join(&name)
+        );
+        assert_eq!(
+            unified.get_iptables_match(1234, 18),
+            ["-m", "cgroup", "--path", &name]
+        );
+
+        let net_cls = CgroupHierarchy::NetCls(Path::new("/sys/fs/cgroup/net_cls").to_path_buf());
+        assert_eq!(
+            net_cls.get_process_cgroup(1234),
+            Path::new("/sys/fs/cgroup/net_cls").join(&name)
+        );
+        assert_eq!(
+            net_cls.get_iptables_match(1234, 18),
+            ["-m", "cgroup", "--cgroup", "0x10012"]
+        );
+    }
+
+    #[test]
+    fn test_cgroup_instance_tag() {
+        assert_eq!(
+            get_cgroup_instance_tag("sniffnet-42_1234"),
+            Some("sniffnet-42")
+        );
+        // cgroups created by older versions weren't tagged with the process ID
+        assert_eq!(get_cgroup_instance_tag("sniffnet_1234"), Some("sniffnet"));
+        assert_eq!(get_cgroup_instance_tag("system.slice"), None);
+    }
+}
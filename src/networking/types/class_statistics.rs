@@ -0,0 +1,78 @@
+//! Module defining the `ClassStatistics` struct, which keeps track of the throughput
+//! of the HTB classes used to throttle the traffic.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Throughput of the traffic control classes, computed from the byte counters reported
+/// by the kernel
+#[derive(Debug, Default)]
+pub struct ClassStatistics {
+    /// Bytes sent by each class (identified by interface and minor ID) at the last update
+    bytes: HashMap<(String, u16), u64>,
+    /// Bytes per second sent by each class between the last two updates
+    throughput: HashMap<(String, u16), u64>,
+    /// Instant of the last update
+    last_update: Option<Instant>,
+}
+
+impl ClassStatistics {
+    /// Updates the throughput of the classes given their current byte counters
+    pub fn update(&mut self, bytes: HashMap<(String, u16), u64>, now: Instant) {
+        if let Some(last_update) = self.last_update {
+            let elapsed_millis = u64::try_from(now.duration_since(last_update).as_millis())
+                .unwrap_or(u64::MAX)
+                .max(1);
+            self.throughput = bytes
+                .iter()
+                .filter_map(|(class, current)| {
+                    // counters restart when a class is recreated to change its rate
+                    let delta = current.checked_sub(*self.bytes.get(class)?)?;
+                    Some((class.clone(), delta.saturating_mul(1000) / elapsed_millis))
+                })
+                .collect();
+        }
+        self.bytes = bytes;
+        self.last_update = Some(now);
+    }
+
+    /// Returns the bytes per second sent by the class `1:<classid>` of the given interface,
+    /// if measured
+    pub fn get_throughput(&self, interface: &str, classid: u16) -> Option<u64> {
+        self.throughput
+            .get(&(interface.to_string(), classid))
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    use crate::networking::types::class_statistics::ClassStatistics;
+
+    #[test]
+    fn test_class_throughput() {
+        let mut statistics = ClassStatistics::default();
+        let start = Instant::now();
+        let class = ("eth0".to_string(), 2);
+
+        statistics.update(HashMap::from([(class.clone(), 1000)]), start);
+        assert_eq!(statistics.get_throughput("eth0", 2), None);
+
+        statistics.update(
+            HashMap::from([(class.clone(), 5000)]),
+            start + Duration::from_secs(2),
+        );
+        assert_eq!(statistics.get_throughput("eth0", 2), Some(2000));
+        assert_eq!(statistics.get_throughput("ifb_eth0", 2), None);
+
+        // the class was recreated, so its counter restarted
+        statistics.update(
+            HashMap::from([(class, 100)]),
+            start + Duration::from_secs(3),
+        );
+        assert_eq!(statistics.get_throughput("eth0", 2), None);
+    }
+}
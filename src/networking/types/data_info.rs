@@ -36,6 +36,16 @@ pub fn add_packet(&mut self, bytes: u128, traffic_direction: TrafficDirection) {
         }
     }
 
+    pub fn add_packets(&mut self, packets: u128, bytes: u128, traffic_direction: TrafficDirection) {
+        if traffic_direction.eq(&TrafficDirection::Outgoing) {
+            self.outgoing_packets += packets;
+            self.outgoing_bytes += bytes;
+        } else {
+            self.incoming_packets += packets;
+            self.incoming_bytes += bytes;
+        }
+    }
+
     pub fn new_with_first_packet(bytes: u128, traffic_direction: TrafficDirection) -> Self {
         if traffic_direction.eq(&TrafficDirection::Outgoing) {
             Self {
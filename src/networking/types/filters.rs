@@ -17,6 +17,8 @@ pub struct Filters {
     pub uid: String,
     // port
     pub port: String,
+    /// Raw BPF expression compiled into the capture, discarding packets before they're parsed
+    pub bpf: String,
 }
 
 impl Default for Filters {
@@ -28,6 +30,7 @@ fn default() -> Self {
             pid: String::new(),
             uid: String::new(),
             port: String::new(),
+            bpf: String::new(),
         }
     }
 }
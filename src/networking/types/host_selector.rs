@@ -0,0 +1,138 @@
+//! Module defining the `HostSelector` enum, which selects the remote hosts whose traffic
+//! has to be throttled.
+
+use std::fmt;
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::networking::types::host::Host;
+use crate::networking::types::ip_subnet::IpSubnet;
+
+/// Remote hosts selected by address, by domain or by Autonomous System
+///
+/// Domains and Autonomous Systems are matched against the hosts resolved while sniffing.
+/// Selectors are stored as a `kind` and a `value`, as done for the targets of the throttling rules.
+#[derive(Hash, Eq, PartialEq, PartialOrd, Ord, Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value")]
+pub enum HostSelector {
+    /// Remote address or subnet
+    Subnet(IpSubnet),
+    /// Domain of the remote hosts, as obtained from the reverse DNS
+    Domain(String),
+    /// Number of the Autonomous System operating the remote hosts
+    Asn(u32),
+}
+
+impl HostSelector {
+    /// Returns true if the remote address, resolved into the given host, is selected
+    pub fn matches(&self, address: IpAddr, host: &Host) -> bool {
+        match self {
+            HostSelector::Subnet(subnet) => subnet.contains(address),
+            HostSelector::Domain(domain) => host.domain.eq(domain),
+            HostSelector::Asn(number) => host.asn.number.eq(number),
+        }
+    }
+
+    /// Returns the selectors of a resolved host, from the most to the least specific:
+    /// its address, its /24 (IPv4) or /64 (IPv6) subnet, its domain and its Autonomous System
+    pub fn get_address_selectors(address: IpAddr, host: &Host) -> Vec<Self> {
+        let subnet_prefix_len = if address.is_ipv4() { 24 } else { 64 };
+        let mut selectors = vec![HostSelector::Subnet(IpSubnet::from(address))];
+        if let Some(subnet) = IpSubnet::new(address, subnet_prefix_len) {
+            selectors.push(HostSelector::Subnet(subnet));
+        }
+        for selector in Self::get_host_selectors(host) {
+            if !selectors.contains(&selector) {
+                selectors.push(selector);
+            }
+        }
+        selectors
+    }
+
+    /// Returns the selectors of a host: its domain (or its address, if it couldn't be resolved)
+    /// and its Autonomous System, if known
+    pub fn get_host_selectors(host: &Host) -> Vec<Self> {
+        let mut selectors = Vec::new();
+        match host.domain.parse::<IpAddr>() {
+            Ok(address) => selectors.push(HostSelector::Subnet(IpSubnet::from(address))),
+            Err(_) if !host.domain.is_empty() => {
+                selectors.push(HostSelector::Domain(host.domain.clone()));
+            }
+            Err(_) => {}
+        }
+        if host.asn.number > 0 {
+            selectors.push(HostSelector::Asn(host.asn.number));
+        }
+        selectors
+    }
+}
+
+impl fmt::Display for HostSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HostSelector::Subnet(subnet) => write!(f, "{subnet}"),
+            HostSelector::Domain(domain) => write!(f, "{domain}"),
+            HostSelector::Asn(number) => write!(f, "ASN {number}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use crate::networking::types::asn::Asn;
+    use crate::networking::types::host::Host;
+    use crate::networking::types::host_selector::HostSelector;
+    use crate::networking::types::ip_subnet::IpSubnet;
+
+    #[test]
+    fn test_host_selectors() {
+        let address: IpAddr = "142.250.180.14".parse().unwrap();
+        let host = Host {
+            domain: "1e100.net".to_string(),
+            asn: Asn {
+                number: 15169,
+                name: "GOOGLE".to_string(),
+            },
+            country: Default::default(),
+        };
+        let selectors = HostSelector::get_address_selectors(address, &host);
+        assert_eq!(
+            selectors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>(),
+            vec![
+                "142.250.180.14/32",
+                "142.250.180.0/24",
+                "1e100.net",
+                "ASN 15169"
+            ]
+        );
+        assert!(selectors
+            .iter()
+            .all(|selector| selector.matches(address, &host)));
+
+        let other_address: IpAddr = "142.250.181.14".parse().unwrap();
+        let other_host = Host {
+            domain: "142.250.181.14".to_string(),
+            asn: host.asn.clone(),
+            country: Default::default(),
+        };
+        assert!(!selectors[1].matches(other_address, &other_host));
+        assert!(!selectors[2].matches(other_address, &other_host));
+        assert!(selectors[3].matches(other_address, &other_host));
+        // hosts without a domain are identified by their address
+        assert_eq!(
+            HostSelector::get_address_selectors(other_address, &other_host),
+            vec![
+                HostSelector::Subnet(IpSubnet::from(other_address)),
+                HostSelector::Subnet(IpSubnet::new(other_address, 24).unwrap()),
+                HostSelector::Asn(15169)
+            ]
+        );
+        assert_eq!(HostSelector::get_host_selectors(&Host::default()), vec![]);
+    }
+}
@@ -38,6 +38,8 @@ pub struct InfoAddressPortPair {
     pub uid: Option<u32>,
     /// pid[s] of the process running the connection on the local machine
     pub pids: Option<Vec<u32>>,
+    /// Name[s] of the process running the connection on the local machine
+    pub process_name: Option<String>,
 }
 
 impl Default for InfoAddressPortPair {
@@ -55,6 +57,7 @@ fn default() -> Self {
             index: 0,
             uid: None,
             pids: None,
+            process_name: None,
         }
     }
 }
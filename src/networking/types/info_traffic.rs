@@ -1,7 +1,7 @@
 //! Module defining the `ReportInfo` struct, useful to format the output report file and
 //! to keep track of statistics about the sniffed traffic.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use indexmap::IndexMap;
 
@@ -10,6 +10,8 @@
 use crate::networking::types::data_info_host::DataInfoHost;
 use crate::networking::types::host::Host;
 use crate::networking::types::info_address_port_pair::InfoAddressPortPair;
+use crate::networking::types::process_info::ProcessInfo;
+use crate::networking::types::replay_interval::ReplayInterval;
 use crate::AppProtocol;
 
 /// Struct to be shared between the threads in charge of parsing packets and update reports.
@@ -44,6 +46,15 @@ pub struct InfoTraffic {
     pub addresses_resolved: HashMap<String, (String, Host)>,
     /// Map of the hosts with their data info
     pub hosts: HashMap<Host, DataInfoHost>,
+    /// Map of the local processes owning the sniffed connections, indexed by PID
+    pub processes: HashMap<u32, ProcessInfo>,
+    /// Names of the local users owning the sniffed connections, indexed by UID
+    pub usernames: HashMap<u32, String>,
+    /// Seconds of an imported file parsed since the last refresh of the application
+    pub replay_intervals: VecDeque<ReplayInterval>,
+    /// Failures occurred while capturing (as name of the adapter and cause),
+    /// until they're reported to the user
+    pub capture_failures: Vec<(String, String)>,
 }
 
 impl InfoTraffic {
@@ -65,6 +76,10 @@ pub fn new() -> Self {
             addresses_waiting_resolution: HashMap::new(),
             addresses_resolved: HashMap::new(),
             hosts: HashMap::new(),
+            processes: HashMap::new(),
+            usernames: HashMap::new(),
+            replay_intervals: VecDeque::new(),
+            capture_failures: Vec::new(),
         }
     }
 }
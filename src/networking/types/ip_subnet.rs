@@ -0,0 +1,124 @@
+//! Module defining the `IpSubnet` struct, which represents a block of remote addresses
+//! in CIDR notation.
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use serde::{Deserialize, Serialize};
+
+/// IPv4 or IPv6 subnet, made of the addresses sharing the first `prefix_len` bits
+#[derive(Hash, Eq, PartialEq, PartialOrd, Ord, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct IpSubnet {
+    /// Network address, with all the host bits set to zero
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpSubnet {
+    /// Returns the subnet of the given length containing the address, if the length is valid
+    /// for its IP version
+    pub fn new(address: IpAddr, prefix_len: u8) -> Option<Self> {
+        let max_prefix_len = match address {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        (prefix_len <= max_prefix_len).then(|| Self {
+            address: mask_address(address, prefix_len),
+            prefix_len,
+        })
+    }
+
+    /// Returns true if the address belongs to the subnet, false otherwise
+    pub fn contains(&self, address: IpAddr) -> bool {
+        address.is_ipv4() == self.address.is_ipv4()
+            && mask_address(address, self.prefix_len) == self.address
+    }
+
+    /// Returns the network address of the subnet
+    pub fn get_address(&self) -> IpAddr {
+        self.address
+    }
+
+    /// Returns the number of bits shared by the addresses of the subnet
+    pub fn get_prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+}
+
+impl From<IpAddr> for IpSubnet {
+    /// Returns the subnet made of a single address
+    fn from(address: IpAddr) -> Self {
+        let prefix_len = match address {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        Self {
+            address,
+            prefix_len,
+        }
+    }
+}
+
+impl fmt::Display for IpSubnet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_len)
+    }
+}
+
+/// Sets to zero the bits of the address following the first `prefix_len`
+fn mask_address(address: IpAddr, prefix_len: u8) -> IpAddr {
+    match address {
+        IpAddr::V4(address) => {
+            let mask = u32::MAX
+                .checked_shl(32 - u32::from(prefix_len))
+                .unwrap_or(0);
+            IpAddr::V4(Ipv4Addr::from(u32::from(address) & mask))
+        }
+        IpAddr::V6(address) => {
+            let mask = u128::MAX
+                .checked_shl(128 - u32::from(prefix_len))
+                .unwrap_or(0);
+            IpAddr::V6(Ipv6Addr::from(u128::from(address) & mask))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use crate::networking::types::ip_subnet::IpSubnet;
+
+    #[test]
+    fn test_ipv4_subnet() {
+        let address: IpAddr = "192.168.1.77".parse().unwrap();
+        let subnet = IpSubnet::new(address, 24).unwrap();
+        assert_eq!(subnet.to_string(), "192.168.1.0/24");
+        assert_eq!(subnet.get_address().to_string(), "192.168.1.0");
+        assert_eq!(subnet.get_prefix_len(), 24);
+        assert!(subnet.contains("192.168.1.200".parse().unwrap()));
+        assert!(!subnet.contains("192.168.2.1".parse().unwrap()));
+        assert!(!subnet.contains("::ffff:c0a8:101".parse().unwrap()));
+        assert!(IpSubnet::new(address, 0)
+            .unwrap()
+            .contains("8.8.8.8".parse().unwrap()));
+        assert_eq!(IpSubnet::new(address, 33), None);
+        assert_eq!(IpSubnet::from(address).to_string(), "192.168.1.77/32");
+    }
+
+    #[test]
+    fn test_ipv6_subnet() {
+        let address: IpAddr = "2001:db8:85a3::8a2e:370:7334".parse().unwrap();
+        let subnet = IpSubnet::new(address, 64).unwrap();
+        assert_eq!(subnet.to_string(), "2001:db8:85a3::/64");
+        assert_eq!(subnet.get_address().to_string(), "2001:db8:85a3::");
+        assert_eq!(subnet.get_prefix_len(), 64);
+        assert!(subnet.contains("2001:db8:85a3::1".parse().unwrap()));
+        assert!(!subnet.contains("2001:db8:85a4::1".parse().unwrap()));
+        assert_eq!(IpSubnet::new(address, 129), None);
+        assert_eq!(
+            IpSubnet::from(address),
+            IpSubnet::new(address, 128).unwrap()
+        );
+    }
+}
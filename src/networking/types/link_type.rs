@@ -0,0 +1,192 @@
+//! Module defining the `LinkType` enum, which represents the data link layer of the captured packets.
+
+use etherparse::PacketHeaders;
+use pcap::Linktype;
+
+/// Data link layer of the packets of a capture, determining how their headers are decoded
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LinkType {
+    /// Ethernet frames, optionally VLAN tagged
+    Ethernet,
+    /// IP packets without any link layer header (e.g., `tun` and WireGuard devices)
+    RawIp,
+    /// BSD loopback encapsulation: the IP packet is preceded by its 4 bytes protocol family
+    Null,
+    /// Linux cooked capture, used by the `any` pseudo-device
+    LinuxSll,
+    /// Linux cooked capture v2, also including the index of the interface
+    LinuxSll2,
+    /// Link type whose packets can't be decoded
+    Unsupported(i32),
+}
+
+/// Length of the Linux cooked capture header
+const SLL_HEADER_LEN: usize = 16;
+/// Length of the Linux cooked capture v2 header
+const SLL2_HEADER_LEN: usize = 20;
+/// Length of the BSD loopback header
+const NULL_HEADER_LEN: usize = 4;
+
+impl LinkType {
+    /// Returns the link type corresponding to the datalink of a capture
+    pub fn from_datalink(datalink: Linktype) -> Self {
+        match datalink {
+            Linktype::ETHERNET => LinkType::Ethernet,
+            // DLT_RAW is 12 on most platforms, but 14 on OpenBSD
+            Linktype(12 | 14) | Linktype::RAW | Linktype::IPV4 | Linktype::IPV6 => LinkType::RawIp,
+            Linktype::NULL | Linktype::LOOP => LinkType::Null,
+            Linktype::LINUX_SLL => LinkType::LinuxSll,
+            Linktype::LINUX_SLL2 => LinkType::LinuxSll2,
+            Linktype(datalink) => LinkType::Unsupported(datalink),
+        }
+    }
+
+    /// Decodes the headers of a packet, if they are valid
+    pub fn get_packet_headers(self, packet: &[u8]) -> Option<PacketHeaders> {
+        match self {
+            LinkType::Ethernet => PacketHeaders::from_ethernet_slice(packet).ok(),
+            LinkType::RawIp => PacketHeaders::from_ip_slice(packet).ok(),
+            LinkType::Null => PacketHeaders::from_ip_slice(packet.get(NULL_HEADER_LEN..)?).ok(),
+            LinkType::LinuxSll => PacketHeaders::from_ether_type(
+                u16::from_be_bytes([*packet.get(14)?, *packet.get(15)?]),
+                packet.get(SLL_HEADER_LEN..)?,
+            )
+            .ok(),
+            LinkType::LinuxSll2 => PacketHeaders::from_ether_type(
+                u16::from_be_bytes([*packet.first()?, *packet.get(1)?]),
+                packet.get(SLL2_HEADER_LEN..)?,
+            )
+            .ok(),
+            LinkType::Unsupported(_) => None,
+        }
+    }
+
+    /// Returns the MAC address of the sender of a packet in a Linux cooked capture;
+    /// cooked captures don't report the MAC address of the receiver
+    pub fn get_cooked_source_mac(self, packet: &[u8]) -> Option<[u8; 6]> {
+        let (address_len, address) = match self {
+            LinkType::LinuxSll => (
+                u16::from_be_bytes([*packet.get(4)?, *packet.get(5)?]),
+                packet.get(6..12)?,
+            ),
+            LinkType::LinuxSll2 => (u16::from(*packet.get(11)?), packet.get(12..18)?),
+            _ => return None,
+        };
+        if address_len == 6 {
+            address.try_into().ok()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use etherparse::{IpHeader, PacketBuilder, TransportHeader};
+    use pcap::Linktype;
+
+    use crate::networking::types::link_type::LinkType;
+
+    const MAC: [u8; 6] = [0x02, 0x42, 0xac, 0x11, 0x00, 0x02];
+
+    fn get_udp_packet() -> Vec<u8> {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([10, 8, 0, 2], [1, 1, 1, 1], 64)
+            .udp(50000, 53)
+            .write(&mut packet, &[0; 8])
+            .unwrap();
+        packet
+    }
+
+    fn assert_udp_headers(link_type: LinkType, packet: &[u8]) {
+        let headers = link_type.get_packet_headers(packet).unwrap();
+        assert!(
+            matches!(&headers.ip, Some(IpHeader::Version4(ipv4, _)) if ipv4.source == [10, 8, 0, 2])
+        );
+        assert!(
+            matches!(&headers.transport, Some(TransportHeader::Udp(udp)) if udp.destination_port == 53)
+        );
+    }
+
+    #[test]
+    fn test_link_type_from_datalink() {
+        assert_eq!(
+            LinkType::from_datalink(Linktype::ETHERNET),
+            LinkType::Ethernet
+        );
+        assert_eq!(LinkType::from_datalink(Linktype(12)), LinkType::RawIp);
+        assert_eq!(LinkType::from_datalink(Linktype::RAW), LinkType::RawIp);
+        assert_eq!(LinkType::from_datalink(Linktype::LOOP), LinkType::Null);
+        assert_eq!(
+            LinkType::from_datalink(Linktype::LINUX_SLL),
+            LinkType::LinuxSll
+        );
+        assert_eq!(
+            LinkType::from_datalink(Linktype::LINUX_SLL2),
+            LinkType::LinuxSll2
+        );
+        assert_eq!(
+            LinkType::from_datalink(Linktype::IEEE802_11),
+            LinkType::Unsupported(105)
+        );
+    }
+
+    #[test]
+    fn test_ethernet_and_vlan_packets() {
+        let mut packet = Vec::new();
+        PacketBuilder::ethernet2(MAC, [0xff; 6])
+            .single_vlan(100)
+            .ipv4([10, 8, 0, 2], [1, 1, 1, 1], 64)
+            .udp(50000, 53)
+            .write(&mut packet, &[0; 8])
+            .unwrap();
+        assert_udp_headers(LinkType::Ethernet, &packet);
+        assert_eq!(LinkType::Ethernet.get_cooked_source_mac(&packet), None);
+    }
+
+    #[test]
+    fn test_raw_ip_and_loopback_packets() {
+        let packet = get_udp_packet();
+        assert_udp_headers(LinkType::RawIp, &packet);
+
+        let mut null_packet = 2_u32.to_ne_bytes().to_vec();
+        null_packet.extend(&packet);
+        assert_udp_headers(LinkType::Null, &null_packet);
+
+        assert!(LinkType::Unsupported(105)
+            .get_packet_headers(&packet)
+            .is_none());
+    }
+
+    #[test]
+    fn test_linux_cooked_packets() {
+        let mut sll_packet = vec![0, 4, 0, 1, 0, 6];
+        sll_packet.extend(MAC);
+        sll_packet.extend([0, 0, 0x08, 0x00]);
+        sll_packet.extend(get_udp_packet());
+        assert_udp_headers(LinkType::LinuxSll, &sll_packet);
+        assert_eq!(
+            LinkType::LinuxSll.get_cooked_source_mac(&sll_packet),
+            Some(MAC)
+        );
+
+        let mut sll2_packet = vec![0x08, 0x00, 0, 0, 0, 0, 0, 3, 0, 1, 4, 6];
+        sll2_packet.extend(MAC);
+        sll2_packet.extend([0, 0]);
+        sll2_packet.extend(get_udp_packet());
+        assert_udp_headers(LinkType::LinuxSll2, &sll2_packet);
+        assert_eq!(
+            LinkType::LinuxSll2.get_cooked_source_mac(&sll2_packet),
+            Some(MAC)
+        );
+
+        // truncated header
+        assert!(LinkType::LinuxSll
+            .get_packet_headers(&sll_packet[..10])
+            .is_none());
+        assert_eq!(
+            LinkType::LinuxSll2.get_cooked_source_mac(&sll2_packet[..10]),
+            None
+        );
+    }
+}
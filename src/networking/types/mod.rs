@@ -2,16 +2,30 @@
 pub mod app_protocol;
 pub mod asn;
 pub mod byte_multiple;
+pub mod capture_source;
+pub mod cgroup_hierarchy;
+pub mod class_statistics;
 pub mod data_info;
 pub mod data_info_host;
 pub mod filters;
 pub mod host;
+pub mod host_selector;
 pub mod info_address_port_pair;
 pub mod info_traffic;
+pub mod ip_subnet;
 pub mod ip_version;
+pub mod link_type;
 pub mod my_device;
+pub mod port_selector;
+pub mod process_info;
+pub mod replay_interval;
+pub mod rotating_savefile;
+pub mod rtnetlink;
+pub mod savefile_options;
 pub mod search_parameters;
+pub mod socket_cache;
 pub mod traffic_control;
+pub mod traffic_control_error;
 pub mod traffic_direction;
 pub mod traffic_type;
 pub mod trans_protocol;
@@ -1,3 +1,4 @@
+use std::net::IpAddr;
 use std::sync::{Arc, Mutex};
 
 use pcap::Address;
@@ -11,3 +12,23 @@ pub struct MyDevice {
     pub desc: Option<String>,
     pub addresses: Arc<Mutex<Vec<Address>>>,
 }
+
+impl MyDevice {
+    /// Returns true if this is the Linux pseudo-device capturing the traffic of all the adapters
+    pub fn is_any(&self) -> bool {
+        self.name.eq("any")
+    }
+
+    /// Replaces the device addresses with the given ones, e.g. the local addresses of an imported file
+    pub fn set_addresses(&self, addresses: &[IpAddr]) {
+        *self.addresses.lock().unwrap() = addresses
+            .iter()
+            .map(|addr| Address {
+                addr: *addr,
+                netmask: None,
+                broadcast_addr: None,
+                dst_addr: None,
+            })
+            .collect();
+    }
+}
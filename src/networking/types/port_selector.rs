@@ -0,0 +1,97 @@
+//! Module defining the `PortSelector` struct, which selects the traffic of a range of local
+//! ports to be throttled.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::TransProtocol;
+
+/// Range of local ports (inclusive), optionally restricted to a transport protocol
+#[derive(Hash, Eq, PartialEq, PartialOrd, Ord, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PortSelector {
+    pub first: u16,
+    pub last: u16,
+    /// Transport protocol of the traffic; `TransProtocol::Other` selects both TCP and UDP
+    pub protocol: TransProtocol,
+}
+
+impl PortSelector {
+    /// Returns a selector for a range of ports, if the range isn't empty
+    /// and the transport protocol uses ports
+    pub fn new(first: u16, last: u16, protocol: TransProtocol) -> Option<Self> {
+        (first <= last && !protocol.is_icmp()).then_some(Self {
+            first,
+            last,
+            protocol,
+        })
+    }
+
+    /// Returns a selector for both the TCP and UDP traffic of a single port
+    pub fn single(port: u16) -> Self {
+        Self {
+            first: port,
+            last: port,
+            protocol: TransProtocol::Other,
+        }
+    }
+
+    /// Returns the port if the selector is made of a single port of any transport protocol
+    pub fn get_single_port(&self) -> Option<u16> {
+        (self.first == self.last && self.protocol == TransProtocol::Other).then_some(self.first)
+    }
+
+    /// Returns the range of ports, as a single port or as `first-last`
+    pub fn get_range(&self) -> String {
+        if self.first == self.last {
+            self.first.to_string()
+        } else {
+            format!("{}-{}", self.first, self.last)
+        }
+    }
+
+    /// Returns the transport protocols of the selected traffic
+    pub fn get_transports(&self) -> &'static [TransProtocol] {
+        match self.protocol {
+            TransProtocol::TCP => &[TransProtocol::TCP],
+            TransProtocol::UDP => &[TransProtocol::UDP],
+            TransProtocol::Other => &[TransProtocol::TCP, TransProtocol::UDP],
+            TransProtocol::ICMP | TransProtocol::ICMPv6 => &[],
+        }
+    }
+}
+
+impl fmt::Display for PortSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.protocol {
+            TransProtocol::Other => write!(f, "{}", self.get_range()),
+            protocol => write!(f, "{}/{protocol}", self.get_range()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::networking::types::port_selector::PortSelector;
+    use crate::TransProtocol;
+
+    #[test]
+    fn test_port_selector() {
+        let range = PortSelector::new(27000, 27100, TransProtocol::UDP).unwrap();
+        assert_eq!(range.get_range(), "27000-27100");
+        assert_eq!(range.get_transports(), [TransProtocol::UDP]);
+        assert_eq!(range.get_single_port(), None);
+        assert_eq!(range.to_string(), "27000-27100/UDP");
+        assert_eq!(PortSelector::new(27100, 27000, TransProtocol::UDP), None);
+        assert_eq!(PortSelector::new(0, 0, TransProtocol::ICMP), None);
+
+        let single = PortSelector::single(8080);
+        assert_eq!(single.get_range(), "8080");
+        assert_eq!(
+            single.get_transports(),
+            [TransProtocol::TCP, TransProtocol::UDP]
+        );
+        assert_eq!(single.get_single_port(), Some(8080));
+        assert_eq!(single.to_string(), "8080");
+    }
+}
@@ -0,0 +1,224 @@
+//! Module defining the `ProcessInfo` struct, which represents a local process owning network sockets.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Local, TimeZone};
+
+/// Number of clock ticks per second used by the kernel to express times in `/proc` (`USER_HZ`)
+const CLOCK_TICKS_PER_SECOND: u64 = 100;
+
+/// Maximum number of ancestors retrieved for each process
+const MAX_ANCESTORS: usize = 64;
+
+/// Information about a local process, as read from `/proc/<pid>`
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct ProcessInfo {
+    /// Process ID
+    pub pid: u32,
+    /// Parent process ID
+    pub ppid: u32,
+    /// Executable name (from `/proc/<pid>/comm`)
+    pub name: String,
+    /// Path of the executable; empty if not accessible
+    pub exe: String,
+    /// Full command line, with arguments separated by spaces
+    pub cmdline: String,
+    /// Real user ID of the process
+    pub uid: u32,
+    /// Name of the user owning the process, if known
+    pub username: Option<String>,
+    /// Instant at which the process started
+    pub start_time: Option<DateTime<Local>>,
+    /// Chain of the parent processes, from the nearest to the farthest
+    pub ancestors: Vec<u32>,
+}
+
+impl ProcessInfo {
+    /// Reads the information about the process with the given PID; returns `None` if the
+    /// process doesn't exist anymore
+    pub fn from_proc(pid: u32, usernames: &HashMap<u32, String>) -> Option<Self> {
+        let name = std::fs::read_to_string(format!("/proc/{pid}/comm")).ok()?;
+        let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+        let (ppid, start_ticks) = parse_stat(&stat)?;
+        let status = std::fs::read_to_string(format!("/proc/{pid}/status")).unwrap_or_default();
+        let uid = parse_status_uid(&status).unwrap_or(u32::MAX);
+        let cmdline = std::fs::read(format!("/proc/{pid}/cmdline")).unwrap_or_default();
+        let exe = std::fs::read_link(format!("/proc/{pid}/exe"))
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let start_time = get_boot_time().and_then(|boot_time| {
+            let seconds = boot_time + start_ticks / CLOCK_TICKS_PER_SECOND;
+            Local
+                .timestamp_opt(i64::try_from(seconds).ok()?, 0)
+                .single()
+        });
+        Some(Self {
+            pid,
+            ppid,
+            name: name.trim_end().to_string(),
+            exe,
+            cmdline: parse_cmdline(&cmdline),
+            uid,
+            username: usernames.get(&uid).cloned(),
+            start_time,
+            ancestors: get_ancestors(ppid),
+        })
+    }
+}
+
+/// Arranges the given processes (already sorted by relevance) as a tree, in which each process
+/// follows its nearest ancestor among the given ones; the returned PIDs are paired with
+/// their depth in the tree, and siblings keep their original order
+pub fn get_process_tree(pids: &[u32], processes: &HashMap<u32, ProcessInfo>) -> Vec<(u32, usize)> {
+    let listed: HashSet<u32> = pids.iter().copied().collect();
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut roots = Vec::new();
+    for pid in pids {
+        let parent = processes.get(pid).and_then(|process| {
+            process
+                .ancestors
+                .iter()
+                .find(|ancestor| **ancestor != *pid && listed.contains(*ancestor))
+        });
+        match parent {
+            Some(parent) => children.entry(*parent).or_default().push(*pid),
+            None => roots.push(*pid),
+        }
+    }
+
+    let mut tree = Vec::new();
+    let mut stack: Vec<(u32, usize)> = roots.iter().rev().map(|pid| (*pid, 0)).collect();
+    let mut visited = HashSet::new();
+    while let Some((pid, depth)) = stack.pop() {
+        if !visited.insert(pid) {
+            continue;
+        }
+        tree.push((pid, depth));
+        if let Some(pid_children) = children.get(&pid) {
+            stack.extend(pid_children.iter().rev().map(|child| (*child, depth + 1)));
+        }
+    }
+    tree
+}
+
+/// Returns the PIDs of the parent processes, starting from the given one
+fn get_ancestors(ppid: u32) -> Vec<u32> {
+    let mut ancestors = Vec::new();
+    let mut current = ppid;
+    while current != 0 && ancestors.len() < MAX_ANCESTORS && !ancestors.contains(&current) {
+        ancestors.push(current);
+        current = std::fs::read_to_string(format!("/proc/{current}/stat"))
+            .ok()
+            .and_then(|stat| parse_stat(&stat))
+            .map_or(0, |(ppid, _)| ppid);
+    }
+    ancestors
+}
+
+/// Returns the boot time of the machine, in seconds since the epoch
+fn get_boot_time() -> Option<u64> {
+    std::fs::read_to_string("/proc/stat")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Extracts the parent PID and the start time (in clock ticks since boot) from the content of
+/// `/proc/<pid>/stat`, formatted as `pid (comm) state ppid ...`
+fn parse_stat(stat: &str) -> Option<(u32, u64)> {
+    // the executable name may contain spaces and parentheses
+    let (_, fields) = stat.rsplit_once(')')?;
+    let fields: Vec<&str> = fields.split_whitespace().collect();
+    let ppid = fields.get(1)?.parse().ok()?;
+    // start time is the 22nd field, and fields are counted from the 3rd here
+    let start_ticks = fields.get(19)?.parse().ok()?;
+    Some((ppid, start_ticks))
+}
+
+/// Extracts the real user ID from the content of `/proc/<pid>/status`
+fn parse_status_uid(status: &str) -> Option<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Converts the content of `/proc/<pid>/cmdline`, whose arguments are NUL-terminated,
+/// into a human readable string
+fn parse_cmdline(cmdline: &[u8]) -> String {
+    cmdline
+        .split(|byte| *byte == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(String::from_utf8_lossy)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::networking::types::process_info::{
+        get_process_tree, parse_cmdline, parse_stat, parse_status_uid, ProcessInfo,
+    };
+
+    #[test]
+    fn test_parse_cmdline() {
+        assert_eq!(
+            parse_cmdline(b"/usr/bin/curl\0-s\0https://example.com\0"),
+            "/usr/bin/curl -s https://example.com"
+        );
+        assert_eq!(parse_cmdline(b""), "");
+    }
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = "4242 (Web Content (1)) S 4200 4242 4200 0 -1 4194560 62836 0 3 0 \
+            1218 361 0 0 20 0 30 0 123456 3004649472 66355 18446744073709551615";
+        assert_eq!(parse_stat(stat), Some((4200, 123_456)));
+        assert_eq!(parse_stat("4242 (curl) S"), None);
+    }
+
+    #[test]
+    fn test_parse_status_uid() {
+        let status = "Name:\tcurl\nState:\tS (sleeping)\nUid:\t1000\t1001\t1001\t1001\nGid:\t1000";
+        assert_eq!(parse_status_uid(status), Some(1000));
+        assert_eq!(parse_status_uid("Name:\tcurl"), None);
+    }
+
+    #[test]
+    fn test_process_tree() {
+        let processes: HashMap<u32, ProcessInfo> = [
+            (10, vec![1]),
+            (20, vec![15, 10, 1]),
+            (30, vec![1]),
+            (40, vec![20, 15, 10, 1]),
+            (50, vec![10, 1]),
+        ]
+        .into_iter()
+        .map(|(pid, ancestors)| {
+            (
+                pid,
+                ProcessInfo {
+                    pid,
+                    ancestors,
+                    ..ProcessInfo::default()
+                },
+            )
+        })
+        .collect();
+
+        // 40 is listed before its ancestors, and 99 is unknown
+        let tree = get_process_tree(&[40, 30, 50, 10, 99, 20], &processes);
+        assert_eq!(
+            tree,
+            vec![(30, 0), (10, 0), (50, 1), (20, 1), (40, 2), (99, 0)]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Module defining the `ReplayInterval` struct, which represents a second of an imported file.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Local, SubsecRound};
+
+use crate::networking::types::host::Host;
+use crate::InfoTraffic;
+
+/// Maximum number of empty seconds recorded for a gap between two packets of an imported file:
+/// longer gaps are collapsed, since the charts only display the last 30 seconds
+const MAX_EMPTY_SECONDS: i64 = 30;
+
+/// Traffic totals at the end of a second of an imported file.
+///
+/// Files are parsed at full speed, so charts and notifications are computed from these intervals
+/// (bucketed by packet timestamp) rather than from the totals at each tick of the application.
+pub struct ReplayInterval {
+    /// Second of the file this interval refers to
+    pub timestamp: DateTime<Local>,
+    /// Total amount of filtered bytes received until the end of this second
+    pub tot_received_bytes: u128,
+    /// Total amount of filtered bytes sent until the end of this second
+    pub tot_sent_bytes: u128,
+    /// Total amount of filtered packets received until the end of this second
+    pub tot_received_packets: u128,
+    /// Total amount of filtered packets sent until the end of this second
+    pub tot_sent_packets: u128,
+    /// Total packets including those not filtered until the end of this second
+    pub all_packets: u128,
+    /// Total bytes including those not filtered until the end of this second
+    pub all_bytes: u128,
+    /// Favorite hosts that exchanged data during this second
+    pub favorites: HashSet<Host>,
+}
+
+impl ReplayInterval {
+    /// Records the current totals as the ones at the end of the given second
+    fn new(timestamp: DateTime<Local>, info_traffic: &mut InfoTraffic) -> Self {
+        ReplayInterval {
+            timestamp,
+            tot_received_bytes: info_traffic.tot_received_bytes,
+            tot_sent_bytes: info_traffic.tot_sent_bytes,
+            tot_received_packets: info_traffic.tot_received_packets,
+            tot_sent_packets: info_traffic.tot_sent_packets,
+            all_packets: info_traffic.all_packets,
+            all_bytes: info_traffic.all_bytes,
+            favorites: std::mem::take(&mut info_traffic.favorites_last_interval),
+        }
+    }
+}
+
+/// Closes the seconds of an imported file elapsed before the given packet timestamp
+/// (or the current second, at the end of the file), recording their traffic totals.
+///
+/// `current_second` is the second of the file being parsed, `None` before the first packet.
+pub fn close_replay_seconds(
+    info_traffic: &mut InfoTraffic,
+    current_second: &mut Option<DateTime<Local>>,
+    timestamp: Option<DateTime<Local>>,
+) {
+    let Some(second) = *current_second else {
+        *current_second = timestamp.map(|timestamp| timestamp.trunc_subsecs(0));
+        return;
+    };
+    let elapsed_seconds = timestamp.map_or(1, |timestamp| (timestamp - second).num_seconds());
+    // packets out of order are accounted in the current second
+    if elapsed_seconds < 1 {
+        return;
+    }
+    for i in 0..elapsed_seconds.min(MAX_EMPTY_SECONDS) {
+        let interval = ReplayInterval::new(second + Duration::seconds(i), info_traffic);
+        info_traffic.replay_intervals.push_back(interval);
+    }
+    *current_second = Some(second + Duration::seconds(elapsed_seconds));
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Local, TimeZone};
+
+    use crate::networking::types::replay_interval::close_replay_seconds;
+    use crate::InfoTraffic;
+
+    #[test]
+    fn test_close_replay_seconds() {
+        let mut info_traffic = InfoTraffic::new();
+        let start = Local.timestamp_opt(1_700_000_000, 0).unwrap();
+        let mut current_second = None;
+
+        close_replay_seconds(
+            &mut info_traffic,
+            &mut current_second,
+            Some(start + Duration::milliseconds(300)),
+        );
+        assert_eq!(current_second, Some(start));
+        assert!(info_traffic.replay_intervals.is_empty());
+        info_traffic.tot_sent_packets = 1;
+
+        // packets of the same second don't close it
+        close_replay_seconds(
+            &mut info_traffic,
+            &mut current_second,
+            Some(start + Duration::milliseconds(900)),
+        );
+        assert!(info_traffic.replay_intervals.is_empty());
+        info_traffic.tot_sent_packets = 2;
+
+        // a gap of 3 seconds closes the current second and two empty ones
+        close_replay_seconds(
+            &mut info_traffic,
+            &mut current_second,
+            Some(start + Duration::milliseconds(3100)),
+        );
+        assert_eq!(current_second, Some(start + Duration::seconds(3)));
+        let intervals: Vec<_> = info_traffic
+            .replay_intervals
+            .iter()
+            .map(|interval| (interval.timestamp, interval.tot_sent_packets))
+            .collect();
+        assert_eq!(
+            intervals,
+            vec![
+                (start, 2),
+                (start + Duration::seconds(1), 2),
+                (start + Duration::seconds(2), 2)
+            ]
+        );
+        info_traffic.tot_sent_packets = 3;
+
+        // longer gaps are collapsed
+        info_traffic.replay_intervals.clear();
+        close_replay_seconds(
+            &mut info_traffic,
+            &mut current_second,
+            Some(start + Duration::seconds(3600)),
+        );
+        assert_eq!(info_traffic.replay_intervals.len(), 30);
+        assert_eq!(current_second, Some(start + Duration::seconds(3600)));
+
+        // the end of the file closes the current second
+        info_traffic.replay_intervals.clear();
+        close_replay_seconds(&mut info_traffic, &mut current_second, None);
+        assert_eq!(info_traffic.replay_intervals.len(), 1);
+    }
+}
@@ -0,0 +1,161 @@
+//! Module defining the `RotatingSavefile` struct, which dumps the sniffed packets into pcap files
+//! rotated by size or time.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use pcap::{Capture, Linktype, Packet, Savefile};
+
+use crate::networking::types::savefile_options::SavefileOptions;
+
+/// Size of the global header of a pcap file
+const PCAP_FILE_HEADER_LEN: u64 = 24;
+
+/// Size of the header preceding each packet in a pcap file
+const PCAP_PACKET_HEADER_LEN: u64 = 16;
+
+/// Pcap savefile which is periodically closed and replaced by a new one
+pub struct RotatingSavefile {
+    /// Path specified by the user
+    base_path: PathBuf,
+    /// Link type of the dumped packets
+    linktype: Linktype,
+    /// Size after which a new file is started
+    rotation_bytes: Option<u64>,
+    /// Time after which a new file is started
+    rotation_interval: Option<Duration>,
+    /// Maximum number of files retained on disk
+    max_files: Option<usize>,
+    /// Currently open savefile
+    savefile: Savefile,
+    /// Bytes written in the current file
+    current_size: u64,
+    /// Instant at which the current file was opened
+    opened_at: Instant,
+    /// Instant of the last flush of the current file
+    last_flush: Instant,
+    /// Progressive number of the current file
+    index: usize,
+    /// Paths of the files written so far, oldest first
+    written_files: VecDeque<PathBuf>,
+}
+
+impl RotatingSavefile {
+    /// Creates the first savefile according to the given options
+    pub fn new(options: &SavefileOptions, linktype: Linktype) -> Result<Self, pcap::Error> {
+        let base_path = PathBuf::from(options.path.trim());
+        let index = 0;
+        let first_path = if options.is_rotating() {
+            get_rotated_path(&base_path, index)
+        } else {
+            base_path.clone()
+        };
+        let savefile = open_savefile(&first_path, linktype)?;
+        let now = Instant::now();
+        Ok(Self {
+            base_path,
+            linktype,
+            rotation_bytes: options.get_rotation_bytes(),
+            rotation_interval: options.get_rotation_interval(),
+            max_files: options.get_max_files(),
+            savefile,
+            current_size: PCAP_FILE_HEADER_LEN,
+            opened_at: now,
+            last_flush: now,
+            index,
+            written_files: VecDeque::from([first_path]),
+        })
+    }
+
+    /// Writes a packet, starting a new file first if the current one must be rotated
+    pub fn write(&mut self, packet: &Packet) -> Result<(), pcap::Error> {
+        let packet_len = PCAP_PACKET_HEADER_LEN + u64::from(packet.header.caplen);
+        if self.must_rotate(packet_len) {
+            self.rotate()?;
+        }
+        self.savefile.write(packet);
+        self.current_size += packet_len;
+        // flush from time to time so that the file can be inspected while sniffing
+        if self.last_flush.elapsed() >= Duration::from_secs(1) {
+            self.savefile.flush()?;
+            self.last_flush = Instant::now();
+        }
+        Ok(())
+    }
+
+    fn must_rotate(&self, packet_len: u64) -> bool {
+        let size_exceeded = self.rotation_bytes.is_some_and(|max_size| {
+            self.current_size > PCAP_FILE_HEADER_LEN && self.current_size + packet_len > max_size
+        });
+        let time_exceeded = self
+            .rotation_interval
+            .is_some_and(|interval| self.opened_at.elapsed() >= interval);
+        size_exceeded || time_exceeded
+    }
+
+    fn rotate(&mut self) -> Result<(), pcap::Error> {
+        self.savefile.flush()?;
+        self.index += 1;
+        let new_path = get_rotated_path(&self.base_path, self.index);
+        // the previous savefile is closed when replaced
+        self.savefile = open_savefile(&new_path, self.linktype)?;
+        self.current_size = PCAP_FILE_HEADER_LEN;
+        self.opened_at = Instant::now();
+        self.written_files.push_back(new_path);
+        if let Some(max_files) = self.max_files {
+            while self.written_files.len() > max_files {
+                if let Some(oldest) = self.written_files.pop_front() {
+                    std::fs::remove_file(oldest).unwrap_or(());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn open_savefile(path: &Path, linktype: Linktype) -> Result<Savefile, pcap::Error> {
+    Capture::dead(linktype)?.savefile(path)
+}
+
+/// Returns the path of the savefile with the given progressive number,
+/// obtained appending the number to the file name specified by the user
+fn get_rotated_path(base_path: &Path, index: usize) -> PathBuf {
+    let stem = base_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let file_name = match base_path.extension() {
+        Some(extension) => format!("{stem}_{index:05}.{}", extension.to_string_lossy()),
+        None => format!("{stem}_{index:05}"),
+    };
+    base_path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::networking::types::rotating_savefile::get_rotated_path;
+
+    #[test]
+    fn test_rotated_path_keeps_extension() {
+        assert_eq!(
+            get_rotated_path(&PathBuf::from("/tmp/capture.pcap"), 0),
+            PathBuf::from("/tmp/capture_00000.pcap")
+        );
+        assert_eq!(
+            get_rotated_path(&PathBuf::from("/tmp/capture.pcap"), 42),
+            PathBuf::from("/tmp/capture_00042.pcap")
+        );
+    }
+
+    #[test]
+    fn test_rotated_path_without_extension() {
+        assert_eq!(
+            get_rotated_path(&PathBuf::from("dumps/capture"), 7),
+            PathBuf::from("dumps/capture_00007")
+        );
+    }
+}
@@ -0,0 +1,1136 @@
+//! Module defining the `RtNetlink` struct, which configures the qdiscs, classes and filters
+//! used to throttle the traffic (and the IFB devices they need) by sending rtnetlink requests
+//! to the kernel, exactly like `tc` and `ip` do, so that these programs aren't needed.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::TransProtocol;
+
+/// Protocol family of the routing subsystem of the kernel
+const NETLINK_ROUTE: i32 = 0;
+/// Socket option asking the kernel to explain why a request failed
+const NETLINK_EXT_ACK: i32 = 11;
+/// Socket option asking the kernel not to echo the requests in its acknowledgements
+const NETLINK_CAP_ACK: i32 = 10;
+
+const NLM_F_REQUEST: u16 = 0x1;
+const NLM_F_ACK: u16 = 0x4;
+const NLM_F_DUMP: u16 = 0x300;
+const NLM_F_EXCL: u16 = 0x200;
+const NLM_F_CREATE: u16 = 0x400;
+/// Flag of the error messages followed by attributes describing the error
+const NLM_F_ACK_TLVS: u16 = 0x200;
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+const NLMSGERR_ATTR_MSG: u16 = 1;
+const NLMSG_HEADER_LEN: usize = 16;
+const NLA_F_NESTED: u16 = 0x8000;
+
+const RTM_NEWLINK: u16 = 16;
+const RTM_DELLINK: u16 = 17;
+const RTM_NEWQDISC: u16 = 36;
+const RTM_DELQDISC: u16 = 37;
+const RTM_NEWTCLASS: u16 = 40;
+const RTM_DELTCLASS: u16 = 41;
+const RTM_GETTCLASS: u16 = 42;
+const RTM_NEWTFILTER: u16 = 44;
+const RTM_DELTFILTER: u16 = 45;
+
+const IFLA_IFNAME: u16 = 3;
+const IFLA_LINKINFO: u16 = 18;
+const IFLA_IFALIAS: u16 = 20;
+const IFLA_INFO_KIND: u16 = 1;
+const IFF_UP: u32 = 0x1;
+
+const TCA_KIND: u16 = 1;
+const TCA_OPTIONS: u16 = 2;
+const TCA_STATS: u16 = 3;
+const TCA_STATS2: u16 = 7;
+const TCA_STATS_BASIC: u16 = 1;
+const TCMSG_LEN: usize = 20;
+
+const TC_H_ROOT: u32 = 0xffff_ffff;
+const TC_H_INGRESS: u32 = 0xffff_fff1;
+/// Parent of the filters attached to the ingress qdisc
+const TC_H_INGRESS_FILTERS: u32 = 0xffff_fff2;
+const INGRESS_HANDLE: u32 = 0xffff_0000;
+/// Major number of the root HTB qdisc (`1:`)
+const HTB_MAJOR: u32 = 1;
+
+const ETH_P_ALL: u16 = 0x0003;
+const ETH_P_IP: u16 = 0x0800;
+const ETH_P_IPV6: u16 = 0x86dd;
+const IPPROTO_TCP: u8 = 6;
+const IPPROTO_UDP: u8 = 17;
+
+const TCA_HTB_PARMS: u16 = 1;
+const TCA_HTB_INIT: u16 = 2;
+const TCA_HTB_CTAB: u16 = 3;
+const TCA_HTB_RTAB: u16 = 4;
+const TCA_HTB_RATE64: u16 = 6;
+const TCA_HTB_CEIL64: u16 = 7;
+const TC_HTB_PROTOVER: u32 = 3;
+const HTB_RATE2QUANTUM: u32 = 10;
+/// Largest packet considered by `tc` when computing the buffers of the HTB classes
+const HTB_MTU: u32 = 1600;
+
+const TCA_ACT_KIND: u16 = 1;
+const TCA_ACT_OPTIONS: u16 = 2;
+const TC_ACT_UNSPEC: i32 = -1;
+const TC_ACT_SHOT: i32 = 2;
+const TC_ACT_STOLEN: i32 = 4;
+const TCA_POLICE_TBF: u16 = 1;
+const TCA_POLICE_RATE: u16 = 2;
+const TCA_POLICE_RESULT: u16 = 5;
+const TCA_POLICE_RATE64: u16 = 8;
+const TCA_CONNMARK_PARMS: u16 = 1;
+const TCA_MIRRED_PARMS: u16 = 2;
+const TCA_EGRESS_REDIR: i32 = 1;
+
+const TCA_MATCHALL_ACT: u16 = 2;
+const TCA_FW_CLASSID: u16 = 1;
+const TCA_FW_ACT: u16 = 4;
+const TCA_FLOWER_ACT: u16 = 3;
+const TCA_FLOWER_KEY_ETH_TYPE: u16 = 8;
+const TCA_FLOWER_KEY_IP_PROTO: u16 = 9;
+const TCA_FLOWER_KEY_IPV4_SRC: u16 = 10;
+const TCA_FLOWER_KEY_IPV4_DST: u16 = 12;
+const TCA_FLOWER_KEY_IPV6_SRC: u16 = 14;
+const TCA_FLOWER_KEY_IPV6_DST: u16 = 16;
+const TCA_FLOWER_KEY_TCP_SRC: u16 = 18;
+const TCA_FLOWER_KEY_TCP_DST: u16 = 19;
+const TCA_FLOWER_KEY_UDP_SRC: u16 = 20;
+const TCA_FLOWER_KEY_UDP_DST: u16 = 21;
+const TCA_FLOWER_FLAGS: u16 = 22;
+const TCA_FLOWER_KEY_PORT_SRC_MIN: u16 = 87;
+const TCA_FLOWER_KEY_PORT_DST_MIN: u16 = 89;
+
+/// Units of the kernel packet scheduler clock in a microsecond, as reported by
+/// `/proc/net/psched` (the clock has a resolution of 64 nanoseconds)
+const TICKS_PER_MICROSECOND: f64 = 15.625;
+
+/// Directory listing the network interfaces of the system
+const NET_CLASS_DIR: &str = "/sys/class/net";
+
+/// Qdisc of an interface used to throttle its traffic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Qdisc {
+    /// Root HTB qdisc (with handle `1:`), shaping the egress traffic with its classes
+    Htb,
+    /// Ingress qdisc, whose filters police the incoming traffic
+    Ingress,
+}
+
+/// Ethernet protocol of the packets matched by a filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterProtocol {
+    All,
+    Ipv4,
+    Ipv6,
+}
+
+impl FilterProtocol {
+    /// Returns the protocol of the given IP address
+    pub fn of_address(address: IpAddr) -> Self {
+        match address {
+            IpAddr::V4(_) => FilterProtocol::Ipv4,
+            IpAddr::V6(_) => FilterProtocol::Ipv6,
+        }
+    }
+
+    fn get_ethertype(self) -> u16 {
+        match self {
+            FilterProtocol::All => ETH_P_ALL,
+            FilterProtocol::Ipv4 => ETH_P_IP,
+            FilterProtocol::Ipv6 => ETH_P_IPV6,
+        }
+    }
+}
+
+/// Filter of a qdisc, classifying its packets or applying an action to them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub qdisc: Qdisc,
+    pub protocol: FilterProtocol,
+    /// Priority of the filter, which identifies it when it has to be removed;
+    /// the kernel assigns one if it's zero
+    pub prio: u16,
+    pub classifier: Classifier,
+}
+
+/// Packets matched by a filter, and what happens to them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Classifier {
+    /// Puts the packets in the class of the HTB qdisc having the `net_cls` class ID
+    /// of the cgroup of their socket
+    Cgroup,
+    /// Applies the action to all the packets
+    Matchall(Action),
+    /// Puts the packets with the given firewall mark in the class of the HTB qdisc
+    /// with the given minor ID
+    FwClass { mark: u16, classid: u16 },
+    /// Applies the action to the packets with the given firewall mark
+    FwAction { mark: u16, action: Action },
+    /// Applies the action to the packets matching the key
+    Flower { key: FlowerKey, action: Action },
+}
+
+/// Packet fields matched by a flower filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowerKey {
+    /// Range of ports (inclusive) of a transport protocol, which must be TCP or UDP
+    Ports {
+        endpoint: Endpoint,
+        protocol: TransProtocol,
+        first: u16,
+        last: u16,
+    },
+    /// Addresses sharing the first `prefix_len` bits with the given one
+    Subnet {
+        endpoint: Endpoint,
+        address: IpAddr,
+        prefix_len: u8,
+    },
+}
+
+/// Endpoint of the packets whose fields are matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endpoint {
+    Source,
+    Destination,
+}
+
+/// Action applied to the packets matched by a filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Drops the packets exceeding the rate, letting the conforming ones continue
+    /// to be classified by the following filters
+    Police {
+        bytes_per_second: u64,
+        burst_bytes: u32,
+    },
+    /// Sets the firewall mark of the packets to the one saved in their connection
+    RestoreConnmark,
+    /// Redirects the packets to the egress of the interface with the given index
+    Redirect { ifindex: u32 },
+}
+
+/// Connection to the routing subsystem of the kernel
+pub struct RtNetlink {
+    socket: socket::NetlinkSocket,
+    sequence: u32,
+}
+
+impl RtNetlink {
+    /// Opens a connection to the routing subsystem of the kernel
+    pub fn open() -> io::Result<Self> {
+        Ok(Self {
+            socket: socket::NetlinkSocket::open()?,
+            sequence: 0,
+        })
+    }
+
+    /// Creates an Intermediate Functional Block device with the given name
+    pub fn add_ifb_device(&mut self, name: &str) -> io::Result<()> {
+        let mut message = Message::new(
+            RTM_NEWLINK,
+            NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE,
+            &get_ifinfomsg(0, 0, 0),
+        );
+        message.push_string(IFLA_IFNAME, name);
+        message.push_nested(IFLA_LINKINFO, |linkinfo| {
+            linkinfo.push_attribute(IFLA_INFO_KIND, b"ifb");
+        });
+        self.request(message)
+    }
+
+    /// Brings up the given interface
+    pub fn set_link_up(&mut self, interface: &str) -> io::Result<()> {
+        let message = Message::new(
+            RTM_NEWLINK,
+            NLM_F_REQUEST | NLM_F_ACK,
+            &get_ifinfomsg(get_ifindex(interface)?, IFF_UP, IFF_UP),
+        );
+        self.request(message)
+    }
+
+    /// Sets the alias of the given interface
+    pub fn set_link_alias(&mut self, interface: &str, alias: &str) -> io::Result<()> {
+        let mut message = Message::new(
+            RTM_NEWLINK,
+            NLM_F_REQUEST | NLM_F_ACK,
+            &get_ifinfomsg(get_ifindex(interface)?, 0, 0),
+        );
+        // not terminated by a null character, like `ip` does
+        message.push_attribute(IFLA_IFALIAS, alias.as_bytes());
+        self.request(message)
+    }
+
+    /// Removes the given interface
+    pub fn delete_link(&mut self, interface: &str) -> io::Result<()> {
+        let message = Message::new(
+            RTM_DELLINK,
+            NLM_F_REQUEST | NLM_F_ACK,
+            &get_ifinfomsg(get_ifindex(interface)?, 0, 0),
+        );
+        self.request(message)
+    }
+
+    /// Creates the given qdisc on the interface
+    pub fn add_qdisc(&mut self, interface: &str, qdisc: Qdisc) -> io::Result<()> {
+        let message = get_qdisc_message(get_ifindex(interface)?, qdisc, true);
+        self.request(message)
+    }
+
+    /// Removes the given qdisc from the interface, together with its classes and filters
+    pub fn delete_qdisc(&mut self, interface: &str, qdisc: Qdisc) -> io::Result<()> {
+        let message = get_qdisc_message(get_ifindex(interface)?, qdisc, false);
+        self.request(message)
+    }
+
+    /// Creates the class `1:<classid>` of the HTB qdisc of the interface, limiting
+    /// its traffic to the given rate
+    pub fn add_htb_class(
+        &mut self,
+        interface: &str,
+        classid: u16,
+        bytes_per_second: u64,
+    ) -> io::Result<()> {
+        let message = get_htb_class_message(get_ifindex(interface)?, classid, bytes_per_second);
+        self.request(message)
+    }
+
+    /// Removes the class `1:<classid>` of the HTB qdisc of the interface
+    pub fn delete_class(&mut self, interface: &str, classid: u16) -> io::Result<()> {
+        let message = Message::new(
+            RTM_DELTCLASS,
+            NLM_F_REQUEST | NLM_F_ACK,
+            &get_tcmsg(get_ifindex(interface)?, get_htb_handle(classid), 0, 0),
+        );
+        self.request(message)
+    }
+
+    /// Returns the bytes sent by each class of the HTB qdisc of the interface,
+    /// identified by its minor ID
+    pub fn get_class_bytes(&mut self, interface: &str) -> io::Result<HashMap<u16, u64>> {
+        let message = Message::new(
+            RTM_GETTCLASS,
+            NLM_F_REQUEST | NLM_F_DUMP,
+            &get_tcmsg(get_ifindex(interface)?, 0, 0, 0),
+        );
+        let replies = self.dump(message)?;
+        Ok(replies
+            .iter()
+            .filter_map(|reply| parse_class_bytes(reply))
+            .collect())
+    }
+
+    /// Attaches the filter to a qdisc of the interface
+    pub fn add_filter(&mut self, interface: &str, filter: &Filter) -> io::Result<()> {
+        let message = get_filter_message(get_ifindex(interface)?, filter)?;
+        self.request(message)
+    }
+
+    /// Removes the filters with the given priority from a qdisc of the interface
+    pub fn delete_filters(&mut self, interface: &str, qdisc: Qdisc, prio: u16) -> io::Result<()> {
+        let message = Message::new(
+            RTM_DELTFILTER,
+            NLM_F_REQUEST | NLM_F_ACK,
+            &get_tcmsg(
+                get_ifindex(interface)?,
+                0,
+                get_filter_parent(qdisc),
+                u32::from(prio) << 16,
+            ),
+        );
+        self.request(message)
+    }
+
+    /// Sends a request, waiting for the kernel to acknowledge it
+    fn request(&mut self, message: Message) -> io::Result<()> {
+        self.exchange(message, |_| {}).map(|_| ())
+    }
+
+    /// Sends a dump request, returning the payloads of the replies
+    fn dump(&mut self, message: Message) -> io::Result<Vec<Vec<u8>>> {
+        let mut replies = Vec::new();
+        self.exchange(message, |reply| replies.push(reply.to_vec()))?;
+        Ok(replies)
+    }
+
+    /// Sends a message and handles the replies to it until the kernel acknowledges it,
+    /// returns an error or completes the dump
+    fn exchange(
+        &mut self,
+        message: Message,
+        mut handle_reply: impl FnMut(&[u8]),
+    ) -> io::Result<()> {
+        self.sequence = self.sequence.wrapping_add(1);
+        self.socket.send(&message.finish(self.sequence))?;
+        let mut buffer = vec![0; 65536];
+        loop {
+            let received = self.socket.receive(&mut buffer)?;
+            let mut offset = 0;
+            while let Some((message_type, flags, sequence, payload)) =
+                parse_message(&buffer[offset..received])
+            {
+                offset += align(NLMSG_HEADER_LEN + payload.len());
+                if sequence != self.sequence {
+                    continue;
+                }
+                match message_type {
+                    NLMSG_DONE => return Ok(()),
+                    NLMSG_ERROR => return parse_error(flags, payload),
+                    _ => handle_reply(payload),
+                }
+            }
+        }
+    }
+}
+
+/// Returns the index of the given interface
+pub fn get_ifindex(interface: &str) -> io::Result<u32> {
+    let ifindex = fs::read_to_string(Path::new(NET_CLASS_DIR).join(interface).join("ifindex"))
+        .map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("interface {interface} not found"),
+            )
+        })?;
+    ifindex
+        .trim()
+        .parse()
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Returns the handle of the class `1:<classid>` of the HTB qdisc
+fn get_htb_handle(classid: u16) -> u32 {
+    HTB_MAJOR << 16 | u32::from(classid)
+}
+
+/// Returns the parent of the filters attached to the given qdisc
+fn get_filter_parent(qdisc: Qdisc) -> u32 {
+    match qdisc {
+        Qdisc::Htb => HTB_MAJOR << 16,
+        Qdisc::Ingress => TC_H_INGRESS_FILTERS,
+    }
+}
+
+/// Returns the message creating (or removing) the given qdisc
+fn get_qdisc_message(ifindex: u32, qdisc: Qdisc, add: bool) -> Message {
+    let (handle, parent) = match (qdisc, add) {
+        (Qdisc::Htb, true) => (HTB_MAJOR << 16, TC_H_ROOT),
+        (Qdisc::Htb, false) => (0, TC_H_ROOT),
+        (Qdisc::Ingress, _) => (INGRESS_HANDLE, TC_H_INGRESS),
+    };
+    let mut message = if add {
+        Message::new(
+            RTM_NEWQDISC,
+            NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE,
+            &get_tcmsg(ifindex, handle, parent, 0),
+        )
+    } else {
+        Message::new(
+            RTM_DELQDISC,
+            NLM_F_REQUEST | NLM_F_ACK,
+            &get_tcmsg(ifindex, handle, parent, 0),
+        )
+    };
+    match (qdisc, add) {
+        (Qdisc::Htb, true) => {
+            message.push_string(TCA_KIND, "htb");
+            message.push_nested(TCA_OPTIONS, |options| {
+                // version, rate to quantum ratio, default class, debug and direct packets
+                let init = [TC_HTB_PROTOVER, HTB_RATE2QUANTUM, 0, 0, 0];
+                options.push_attribute(TCA_HTB_INIT, &get_u32s_bytes(&init));
+            });
+        }
+        (Qdisc::Htb, false) => {}
+        (Qdisc::Ingress, _) => message.push_string(TCA_KIND, "ingress"),
+    }
+    message
+}
+
+/// Returns the message creating an HTB class, whose ceiling is equal to its rate
+fn get_htb_class_message(ifindex: u32, classid: u16, bytes_per_second: u64) -> Message {
+    let mut message = Message::new(
+        RTM_NEWTCLASS,
+        NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE,
+        &get_tcmsg(ifindex, get_htb_handle(classid), HTB_MAJOR << 16, 0),
+    );
+    message.push_string(TCA_KIND, "htb");
+    let (rate, table) = get_rate(bytes_per_second, HTB_MTU);
+    // the buffer can hold the packets sent in a clock tick, plus a full-sized one
+    let buffer_bytes = u32::try_from(bytes_per_second / 1_000_000_000)
+        .unwrap_or(u32::MAX)
+        .saturating_add(HTB_MTU);
+    let buffer = get_transmission_time(bytes_per_second, buffer_bytes);
+    message.push_nested(TCA_OPTIONS, |options| {
+        if bytes_per_second > u64::from(u32::MAX) {
+            options.push_attribute(TCA_HTB_RATE64, &bytes_per_second.to_ne_bytes());
+            options.push_attribute(TCA_HTB_CEIL64, &bytes_per_second.to_ne_bytes());
+        }
+        // rate, ceil, buffer, cbuffer, quantum (chosen by the kernel), level and priority
+        let mut parameters = [&rate[..], &rate[..]].concat();
+        parameters.extend(get_u32s_bytes(&[buffer, buffer, 0, 0, 0]));
+        options.push_attribute(TCA_HTB_PARMS, &parameters);
+        options.push_attribute(TCA_HTB_RTAB, &table);
+        options.push_attribute(TCA_HTB_CTAB, &table);
+    });
+    message
+}
+
+/// Returns the message attaching the filter to a qdisc
+fn get_filter_message(ifindex: u32, filter: &Filter) -> io::Result<Message> {
+    let handle = match filter.classifier {
+        // the cgroup classifier only accepts a single filter, with handle 1
+        Classifier::Cgroup => 1,
+        Classifier::FwClass { mark, .. } | Classifier::FwAction { mark, .. } => u32::from(mark),
+        Classifier::Matchall(_) | Classifier::Flower { .. } => 0,
+    };
+    let info = u32::from(filter.prio) << 16 | u32::from(filter.protocol.get_ethertype().to_be());
+    let mut message = Message::new(
+        RTM_NEWTFILTER,
+        NLM_F_REQUEST | NLM_F_ACK | NLM_F_EXCL | NLM_F_CREATE,
+        &get_tcmsg(ifindex, handle, get_filter_parent(filter.qdisc), info),
+    );
+    match &filter.classifier {
+        Classifier::Cgroup => {
+            message.push_string(TCA_KIND, "cgroup");
+            message.push_nested(TCA_OPTIONS, |_| {});
+        }
+        Classifier::Matchall(action) => {
+            message.push_string(TCA_KIND, "matchall");
+            message.push_nested(TCA_OPTIONS, |options| {
+                push_action(options, TCA_MATCHALL_ACT, action);
+            });
+        }
+        Classifier::FwClass { classid, .. } => {
+            message.push_string(TCA_KIND, "fw");
+            message.push_nested(TCA_OPTIONS, |options| {
+                options.push_attribute(TCA_FW_CLASSID, &get_htb_handle(*classid).to_ne_bytes());
+            });
+        }
+        Classifier::FwAction { action, .. } => {
+            message.push_string(TCA_KIND, "fw");
+            message.push_nested(TCA_OPTIONS, |options| {
+                push_action(options, TCA_FW_ACT, action);
+            });
+        }
+        Classifier::Flower { key, action } => {
+            let valid = match *key {
+                FlowerKey::Ports { protocol, .. } => {
+                    matches!(protocol, TransProtocol::TCP | TransProtocol::UDP)
+                }
+                FlowerKey::Subnet { address, .. } => {
+                    FilterProtocol::of_address(address) == filter.protocol
+                }
+            };
+            if !valid {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "invalid flower key {key:?} for {:?} packets",
+                        filter.protocol
+                    ),
+                ));
+            }
+            message.push_string(TCA_KIND, "flower");
+            message.push_nested(TCA_OPTIONS, |options| {
+                push_flower_key(options, key);
+                push_action(options, TCA_FLOWER_ACT, action);
+                options.push_attribute(TCA_FLOWER_FLAGS, &0_u32.to_ne_bytes());
+                options.push_attribute(
+                    TCA_FLOWER_KEY_ETH_TYPE,
+                    &filter.protocol.get_ethertype().to_be_bytes(),
+                );
+            });
+        }
+    }
+    Ok(message)
+}
+
+/// Appends the attributes of a flower filter matching the given key
+fn push_flower_key(options: &mut Message, key: &FlowerKey) {
+    match *key {
+        FlowerKey::Ports {
+            endpoint,
+            protocol,
+            first,
+            last,
+        } => {
+            let ip_proto = match protocol {
+                TransProtocol::TCP => IPPROTO_TCP,
+                _ => IPPROTO_UDP,
+            };
+            options.push_attribute(TCA_FLOWER_KEY_IP_PROTO, &[ip_proto]);
+            if first == last {
+                // a single port isn't a valid range for the kernel
+                let kind = match (ip_proto, endpoint) {
+                    (IPPROTO_TCP, Endpoint::Source) => TCA_FLOWER_KEY_TCP_SRC,
+                    (IPPROTO_TCP, Endpoint::Destination) => TCA_FLOWER_KEY_TCP_DST,
+                    (_, Endpoint::Source) => TCA_FLOWER_KEY_UDP_SRC,
+                    (_, Endpoint::Destination) => TCA_FLOWER_KEY_UDP_DST,
+                };
+                options.push_attribute(kind, &first.to_be_bytes());
+            } else {
+                // the maximum follows the minimum
+                let min = match endpoint {
+                    Endpoint::Source => TCA_FLOWER_KEY_PORT_SRC_MIN,
+                    Endpoint::Destination => TCA_FLOWER_KEY_PORT_DST_MIN,
+                };
+                options.push_attribute(min, &first.to_be_bytes());
+                options.push_attribute(min + 1, &last.to_be_bytes());
+            }
+        }
+        FlowerKey::Subnet {
+            endpoint,
+            address,
+            prefix_len,
+        } => {
+            let (kind, address) = match (address, endpoint) {
+                (IpAddr::V4(address), Endpoint::Source) => {
+                    (TCA_FLOWER_KEY_IPV4_SRC, address.octets().to_vec())
+                }
+                (IpAddr::V4(address), Endpoint::Destination) => {
+                    (TCA_FLOWER_KEY_IPV4_DST, address.octets().to_vec())
+                }
+                (IpAddr::V6(address), Endpoint::Source) => {
+                    (TCA_FLOWER_KEY_IPV6_SRC, address.octets().to_vec())
+                }
+                (IpAddr::V6(address), Endpoint::Destination) => {
+                    (TCA_FLOWER_KEY_IPV6_DST, address.octets().to_vec())
+                }
+            };
+            // the mask follows the address
+            let mask = get_prefix_mask(prefix_len, address.len());
+            options.push_attribute(kind, &address);
+            options.push_attribute(kind + 1, &mask);
+        }
+    }
+}
+
+/// Returns the mask made of the first `prefix_len` bits set, long `len` bytes
+fn get_prefix_mask(prefix_len: u8, len: usize) -> Vec<u8> {
+    (0..len)
+        .map(|byte| {
+            let bits = usize::from(prefix_len).saturating_sub(byte * 8).min(8);
+            // the shift is performed on 16 bits, since shifting 8 bits by 8 would overflow
+            (0xff00_u16 >> bits).to_be_bytes()[1]
+        })
+        .collect()
+}
+
+/// Appends the list of actions of a filter, made of the given action
+fn push_action(options: &mut Message, kind: u16, action: &Action) {
+    options.push_nested(kind, |actions| {
+        // actions are listed by their order of execution, starting from one
+        actions.push_nested(1, |entry| match *action {
+            Action::Police {
+                bytes_per_second,
+                burst_bytes,
+            } => {
+                entry.push_string(TCA_ACT_KIND, "police");
+                entry.push_nested(TCA_ACT_OPTIONS | NLA_F_NESTED, |police| {
+                    let (rate, table) = get_rate(bytes_per_second, 0);
+                    let burst = get_transmission_time(bytes_per_second, burst_bytes);
+                    // index, action for the exceeding packets, limit, burst and MTU
+                    let mut tbf = get_u32s_bytes(&[0, TC_ACT_SHOT as u32, 0, burst, 0]);
+                    tbf.extend(rate);
+                    // peak rate, reference count, bind count and capabilities
+                    tbf.extend([0; 12 + 12]);
+                    police.push_attribute(TCA_POLICE_TBF, &tbf);
+                    police.push_attribute(TCA_POLICE_RATE, &table);
+                    police.push_attribute(TCA_POLICE_RESULT, &TC_ACT_UNSPEC.to_ne_bytes());
+                    if bytes_per_second > u64::from(u32::MAX) {
+                        police.push_attribute(TCA_POLICE_RATE64, &bytes_per_second.to_ne_bytes());
+                    }
+                });
+            }
+            Action::RestoreConnmark => {
+                entry.push_string(TCA_ACT_KIND, "connmark");
+                entry.push_nested(TCA_ACT_OPTIONS | NLA_F_NESTED, |connmark| {
+                    // index, capabilities, action (continue), reference and bind count,
+                    // followed by the conntrack zone
+                    let mut parameters = get_u32s_bytes(&[0, 0, TC_ACT_UNSPEC as u32, 0, 0]);
+                    parameters.extend([0; 4]);
+                    connmark.push_attribute(TCA_CONNMARK_PARMS, &parameters);
+                });
+            }
+            Action::Redirect { ifindex } => {
+                entry.push_string(TCA_ACT_KIND, "mirred");
+                entry.push_nested(TCA_ACT_OPTIONS | NLA_F_NESTED, |mirred| {
+                    // index, capabilities, action (stolen), reference and bind count,
+                    // followed by the kind of mirroring and the target interface
+                    let parameters = get_u32s_bytes(&[
+                        0,
+                        0,
+                        TC_ACT_STOLEN as u32,
+                        0,
+                        0,
+                        TCA_EGRESS_REDIR as u32,
+                        ifindex,
+                    ]);
+                    mirred.push_attribute(TCA_MIRRED_PARMS, &parameters);
+                });
+            }
+        });
+    });
+}
+
+/// Returns the rate specification (`struct tc_ratespec`) of the given rate, together with
+/// the table of the transmission times of the packets up to the given MTU
+fn get_rate(bytes_per_second: u64, mtu: u32) -> (Vec<u8>, Vec<u8>) {
+    let mtu = if mtu == 0 { 2047 } else { mtu };
+    // the table has 256 cells, each one covering 2^cell_log bytes
+    let mut cell_log = 0;
+    while (mtu >> cell_log) > 255 {
+        cell_log += 1;
+    }
+    let rate = u32::try_from(bytes_per_second).unwrap_or(u32::MAX);
+    let table: Vec<u32> = (1..=256)
+        .map(|cell| get_transmission_time(u64::from(rate), cell << cell_log))
+        .collect();
+
+    // cell log, link layer (Ethernet), overhead, cell alignment, minimum packet unit and rate
+    let mut spec = vec![cell_log, 1];
+    spec.extend(0_u16.to_ne_bytes());
+    spec.extend((-1_i16).to_ne_bytes());
+    spec.extend(0_u16.to_ne_bytes());
+    spec.extend(rate.to_ne_bytes());
+    (spec, get_u32s_bytes(&table))
+}
+
+/// Returns the time needed to transmit the given bytes at the given rate,
+/// in units of the packet scheduler clock
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+fn get_transmission_time(bytes_per_second: u64, bytes: u32) -> u32 {
+    // computed like `tc`, truncating the microseconds before converting them
+    let microseconds = (1_000_000.0 * (f64::from(bytes) / bytes_per_second.max(1) as f64)) as u32;
+    (f64::from(microseconds) * TICKS_PER_MICROSECOND) as u32
+}
+
+/// Returns the bytes sent by a class, identified by its minor ID, given the payload
+/// of its description; classes not belonging to the HTB qdisc are ignored
+fn parse_class_bytes(payload: &[u8]) -> Option<(u16, u64)> {
+    let handle = u32::from_ne_bytes(payload.get(8..12)?.try_into().ok()?);
+    if handle >> 16 != HTB_MAJOR {
+        return None;
+    }
+    let mut attributes = get_attributes(payload.get(TCMSG_LEN..)?);
+    // the basic statistics start with the 64-bit byte counter
+    let stats = match attributes.remove(&TCA_STATS2) {
+        Some(stats) => get_attributes(&stats).remove(&TCA_STATS_BASIC)?,
+        None => attributes.remove(&TCA_STATS)?,
+    };
+    let bytes = u64::from_ne_bytes(stats.get(..8)?.try_into().ok()?);
+    #[allow(clippy::cast_possible_truncation)]
+    Some((handle as u16, bytes))
+}
+
+/// Returns the outcome of a request given the payload of the error message replying to it:
+/// the error reported by the kernel, with its explanation if available
+fn parse_error(flags: u16, payload: &[u8]) -> io::Result<()> {
+    let code = payload
+        .get(..4)
+        .map(|code| i32::from_ne_bytes(code.try_into().unwrap_or_default()))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "truncated netlink error"))?;
+    if code == 0 {
+        return Ok(());
+    }
+    let error = io::Error::from_raw_os_error(code.saturating_neg());
+    // the explanation follows the header of the request (which isn't echoed)
+    let explanation = (flags & NLM_F_ACK_TLVS != 0)
+        .then(|| payload.get(4 + NLMSG_HEADER_LEN..))
+        .flatten()
+        .and_then(|attributes| {
+            let message = get_attributes(attributes).remove(&NLMSGERR_ATTR_MSG)?;
+            let message = String::from_utf8_lossy(&message);
+            Some(message.trim_end_matches('\0').to_string())
+        });
+    match explanation {
+        Some(explanation) => Err(io::Error::new(
+            error.kind(),
+            format!("{explanation} ({error})"),
+        )),
+        None => Err(error),
+    }
+}
+
+/// Returns the type, the flags, the sequence number and the payload of the first netlink
+/// message of the buffer, if complete
+fn parse_message(buffer: &[u8]) -> Option<(u16, u16, u32, &[u8])> {
+    let len = usize::try_from(u32::from_ne_bytes(buffer.get(..4)?.try_into().ok()?)).ok()?;
+    let message_type = u16::from_ne_bytes(buffer.get(4..6)?.try_into().ok()?);
+    let flags = u16::from_ne_bytes(buffer.get(6..8)?.try_into().ok()?);
+    let sequence = u32::from_ne_bytes(buffer.get(8..12)?.try_into().ok()?);
+    let payload = buffer.get(NLMSG_HEADER_LEN..len)?;
+    Some((message_type, flags, sequence, payload))
+}
+
+/// Returns the attributes of a netlink message, by type (without the nested flag)
+fn get_attributes(mut buffer: &[u8]) -> HashMap<u16, Vec<u8>> {
+    let mut attributes = HashMap::new();
+    while buffer.len() >= 4 {
+        let len = usize::from(u16::from_ne_bytes([buffer[0], buffer[1]]));
+        let kind = u16::from_ne_bytes([buffer[2], buffer[3]]) & !NLA_F_NESTED;
+        let Some(value) = buffer.get(4..len) else {
+            break;
+        };
+        attributes.insert(kind, value.to_vec());
+        buffer = buffer.get(align(len)..).unwrap_or_default();
+    }
+    attributes
+}
+
+/// Returns the header of the messages about the interfaces (`struct ifinfomsg`)
+fn get_ifinfomsg(ifindex: u32, flags: u32, change: u32) -> Vec<u8> {
+    // family, padding and device type
+    let mut header = vec![0; 4];
+    header.extend(get_u32s_bytes(&[ifindex, flags, change]));
+    header
+}
+
+/// Returns the header of the messages about the qdiscs, classes and filters (`struct tcmsg`)
+fn get_tcmsg(ifindex: u32, handle: u32, parent: u32, info: u32) -> Vec<u8> {
+    // family and padding
+    let mut header = vec![0; 4];
+    header.extend(get_u32s_bytes(&[ifindex, handle, parent, info]));
+    header
+}
+
+/// Returns the bytes of the given integers, in native byte order
+fn get_u32s_bytes(values: &[u32]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_ne_bytes())
+        .collect()
+}
+
+/// Returns the length rounded up to the alignment of the netlink messages and attributes
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+/// Netlink message being built
+#[derive(Debug)]
+struct Message {
+    buffer: Vec<u8>,
+}
+
+impl Message {
+    /// Starts a message of the given type, with its fixed header
+    fn new(message_type: u16, flags: u16, header: &[u8]) -> Self {
+        let mut buffer = vec![0; NLMSG_HEADER_LEN];
+        buffer[4..6].copy_from_slice(&message_type.to_ne_bytes());
+        buffer[6..8].copy_from_slice(&flags.to_ne_bytes());
+        buffer.extend(header);
+        Self { buffer }
+    }
+
+    /// Appends an attribute with the given value
+    fn push_attribute(&mut self, kind: u16, value: &[u8]) {
+        let len = u16::try_from(4 + value.len()).unwrap_or(u16::MAX);
+        self.buffer.extend(len.to_ne_bytes());
+        self.buffer.extend(kind.to_ne_bytes());
+        self.buffer.extend(value);
+        self.buffer.resize(align(self.buffer.len()), 0);
+    }
+
+    /// Appends an attribute with the given string value, terminated by a null character
+    fn push_string(&mut self, kind: u16, value: &str) {
+        let mut value = value.as_bytes().to_vec();
+        value.push(0);
+        self.push_attribute(kind, &value);
+    }
+
+    /// Appends an attribute containing the attributes added by the given function
+    fn push_nested(&mut self, kind: u16, push_attributes: impl FnOnce(&mut Self)) {
+        let start = self.buffer.len();
+        self.push_attribute(kind, &[]);
+        push_attributes(self);
+        let len = u16::try_from(self.buffer.len() - start).unwrap_or(u16::MAX);
+        self.buffer[start..start + 2].copy_from_slice(&len.to_ne_bytes());
+    }
+
+    /// Returns the bytes of the message, with the given sequence number
+    fn finish(mut self, sequence: u32) -> Vec<u8> {
+        let len = u32::try_from(self.buffer.len()).unwrap_or(u32::MAX);
+        self.buffer[..4].copy_from_slice(&len.to_ne_bytes());
+        self.buffer[8..12].copy_from_slice(&sequence.to_ne_bytes());
+        self.buffer
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod socket {
+    use std::io;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    use crate::networking::types::rtnetlink::{NETLINK_CAP_ACK, NETLINK_EXT_ACK, NETLINK_ROUTE};
+
+    /// Netlink socket connected to the routing subsystem of the kernel
+    pub struct NetlinkSocket(OwnedFd);
+
+    impl NetlinkSocket {
+        pub fn open() -> io::Result<Self> {
+            // SAFETY: the returned descriptor is checked before being used
+            let fd = unsafe {
+                libc::socket(
+                    libc::AF_NETLINK,
+                    libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                    NETLINK_ROUTE,
+                )
+            };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // SAFETY: the descriptor was just opened, and nothing else owns it
+            let socket = Self(unsafe { OwnedFd::from_raw_fd(fd) });
+            // failures are ignored, since older kernels don't support these options:
+            // errors are just less detailed, and the acknowledgements longer
+            for option in [NETLINK_EXT_ACK, NETLINK_CAP_ACK] {
+                let enable: libc::c_int = 1;
+                // SAFETY: the option value is a valid integer, with the given size
+                unsafe {
+                    libc::setsockopt(
+                        fd,
+                        libc::SOL_NETLINK,
+                        option,
+                        std::ptr::addr_of!(enable).cast(),
+                        std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+                    );
+                }
+            }
+            Ok(socket)
+        }
+
+        /// Sends the message to the kernel
+        pub fn send(&self, message: &[u8]) -> io::Result<()> {
+            // SAFETY: the buffer is valid for its whole length
+            let sent = unsafe {
+                libc::send(
+                    self.0.as_raw_fd(),
+                    message.as_ptr().cast(),
+                    message.len(),
+                    0,
+                )
+            };
+            if sent < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        /// Receives the next messages from the kernel, returning their length
+        pub fn receive(&self, buffer: &mut [u8]) -> io::Result<usize> {
+            // SAFETY: the buffer is valid for its whole length
+            let received = unsafe {
+                libc::recv(
+                    self.0.as_raw_fd(),
+                    buffer.as_mut_ptr().cast(),
+                    buffer.len(),
+                    0,
+                )
+            };
+            usize::try_from(received).map_err(|_| io::Error::last_os_error())
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod socket {
+    use std::io;
+
+    /// Netlink socket connected to the routing subsystem of the kernel,
+    /// which is only available on Linux
+    pub struct NetlinkSocket;
+
+    impl NetlinkSocket {
+        pub fn open() -> io::Result<Self> {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "rtnetlink is only available on Linux",
+            ))
+        }
+
+        pub fn send(&self, _message: &[u8]) -> io::Result<()> {
+            Err(io::ErrorKind::Unsupported.into())
+        }
+
+        pub fn receive(&self, _buffer: &mut [u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::Unsupported.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::networking::types::rtnetlink::{
+        get_filter_message, get_prefix_mask, get_tcmsg, get_transmission_time, parse_class_bytes,
+        parse_error, Action, Classifier, Endpoint, Filter, FilterProtocol, FlowerKey, Message,
+        Qdisc, NLM_F_ACK_TLVS, RTM_NEWTCLASS, TCA_STATS2, TCA_STATS_BASIC,
+    };
+    use crate::TransProtocol;
+
+    /// Returns the bytes written in hexadecimal notation
+    fn get_bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_messages_match_the_ones_of_tc() {
+        // tc filter add dev <2> parent 1: protocol all prio 5 handle 5 fw classid 1:5
+        let filter = Filter {
+            qdisc: Qdisc::Htb,
+            protocol: FilterProtocol::All,
+            prio: 5,
+            classifier: Classifier::FwClass {
+                mark: 5,
+                classid: 5,
+            },
+        };
+        assert_eq!(
+            get_filter_message(2, &filter).unwrap().finish(0),
+            get_bytes(
+                "380000002c0005060000000000000000000000000200000005000000000001000003050007000100\
+                667700000c0002000800010005000100"
+            )
+        );
+
+        // tc filter add dev <2> ingress protocol all prio 1 matchall action connmark continue
+        let filter = Filter {
+            qdisc: Qdisc::Ingress,
+            protocol: FilterProtocol::All,
+            prio: 1,
+            classifier: Classifier::Matchall(Action::RestoreConnmark),
+        };
+        assert_eq!(
+            get_filter_message(2, &filter).unwrap().finish(0),
+            get_bytes(
+                "700000002c0005060000000000000000000000000200000000000000f2ffffff000301000d000100\
+                6d61746368616c6c000000003c00020038000200340001000d000100636f6e6e6d61726b00000000\
+                200002801c0001000000000000000000ffffffff000000000000000000000000"
+            )
+        );
+    }
+
+    #[test]
+    fn test_invalid_flower_keys_are_rejected() {
+        let police = Action::Police {
+            bytes_per_second: 1000,
+            burst_bytes: 1024,
+        };
+        let icmp = Filter {
+            qdisc: Qdisc::Htb,
+            protocol: FilterProtocol::Ipv4,
+            prio: 2,
+            classifier: Classifier::Flower {
+                key: FlowerKey::Ports {
+                    endpoint: Endpoint::Source,
+                    protocol: TransProtocol::ICMP,
+                    first: 1,
+                    last: 1,
+                },
+                action: police.clone(),
+            },
+        };
+        assert!(get_filter_message(2, &icmp).is_err());
+        let mismatched_address = Filter {
+            qdisc: Qdisc::Htb,
+            protocol: FilterProtocol::Ipv4,
+            prio: 2,
+            classifier: Classifier::Flower {
+                key: FlowerKey::Subnet {
+                    endpoint: Endpoint::Source,
+                    address: "2001:db8::1".parse().unwrap(),
+                    prefix_len: 64,
+                },
+                action: police,
+            },
+        };
+        assert!(get_filter_message(2, &mismatched_address).is_err());
+    }
+
+    #[test]
+    fn test_transmission_time() {
+        // 80 microseconds, with 64 nanoseconds per tick
+        assert_eq!(get_transmission_time(100_000, 8), 1250);
+        // burst of 256 KiB at 10 kB/s
+        assert_eq!(get_transmission_time(10_000, 256 * 1024), 0x186a_0000);
+        // the microseconds are truncated like `tc` does: 125599.99... becomes 125599
+        assert_eq!(get_transmission_time(10_000, 1256), 1_962_484);
+        assert_eq!(get_transmission_time(0, 0), 0);
+    }
+
+    #[test]
+    fn test_prefix_mask() {
+        assert_eq!(get_prefix_mask(8, 4), [0xff, 0, 0, 0]);
+        assert_eq!(get_prefix_mask(12, 4), [0xff, 0xf0, 0, 0]);
+        assert_eq!(get_prefix_mask(0, 4), [0, 0, 0, 0]);
+        assert_eq!(get_prefix_mask(32, 4), [0xff; 4]);
+        assert_eq!(get_prefix_mask(128, 16), [0xff; 16]);
+        assert_eq!(get_prefix_mask(65, 16)[7..10], [0xff, 0x80, 0]);
+    }
+
+    #[test]
+    fn test_parse_class_bytes() {
+        let get_payload = |handle: u32, bytes: u64| {
+            let mut message = Message::new(RTM_NEWTCLASS, 0, &get_tcmsg(2, handle, 0, 0));
+            message.push_nested(TCA_STATS2, |stats| {
+                let mut basic = bytes.to_ne_bytes().to_vec();
+                // packets
+                basic.extend(4_u32.to_ne_bytes());
+                stats.push_attribute(TCA_STATS_BASIC, &basic);
+            });
+            message.finish(0)[16..].to_vec()
+        };
+        let classes: HashMap<u16, u64> = [
+            get_payload(0x0001_000a, 4096),
+            get_payload(0x0001_0002, 512),
+            get_payload(0x0002_0003, 1),
+        ]
+        .iter()
+        .filter_map(|payload| parse_class_bytes(payload))
+        .collect();
+        assert_eq!(classes, HashMap::from([(10, 4096), (2, 512)]));
+        assert_eq!(parse_class_bytes(&[0; 4]), None);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(parse_error(0, &0_i32.to_ne_bytes()).is_ok());
+        assert!(parse_error(0, &[]).is_err());
+
+        let mut payload = (-2_i32).to_ne_bytes().to_vec();
+        assert_eq!(
+            parse_error(0, &payload).unwrap_err().kind(),
+            std::io::ErrorKind::NotFound
+        );
+
+        // header of the request, followed by the explanation
+        payload.extend([0; 16]);
+        let mut explanation = Message::new(0, 0, &[]);
+        explanation.push_string(1, "TC classifier not found");
+        payload.extend(&explanation.finish(0)[16..]);
+        let error = parse_error(NLM_F_ACK_TLVS, &payload).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+        assert_eq!(
+            error.to_string(),
+            "TC classifier not found (No such file or directory (os error 2))"
+        );
+    }
+}
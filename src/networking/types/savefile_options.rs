@@ -0,0 +1,171 @@
+//! Module defining the `SavefileOptions` struct, which represents the user settings used to
+//! dump the sniffed packets into pcap files.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Snapshot length used when packets are only parsed (to keep more of them in the buffer)
+pub const DEFAULT_SNAPLEN: i32 = 256;
+
+/// Snapshot length used when packets are dumped into a savefile and no other value is specified
+pub const SAVEFILE_SNAPLEN: i32 = 65535;
+
+/// Options to dump the filtered packets into rotating pcap savefiles, as inserted by the user
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct SavefileOptions {
+    /// Path of the savefile; packets are not dumped if empty
+    pub path: String,
+    /// Maximum number of bytes captured for each packet
+    pub snaplen: String,
+    /// Size (in megabytes) after which a new savefile is started
+    pub rotate_megabytes: String,
+    /// Time (in seconds) after which a new savefile is started
+    pub rotate_seconds: String,
+    /// Maximum number of savefiles retained on disk
+    pub max_files: String,
+}
+
+impl SavefileOptions {
+    /// Returns true if the user asked to dump the sniffed packets
+    pub fn is_enabled(&self) -> bool {
+        !self.path.trim().is_empty()
+    }
+
+    /// Returns the snapshot length to be used when opening the capture
+    pub fn get_snaplen(&self) -> i32 {
+        if !self.is_enabled() {
+            return DEFAULT_SNAPLEN;
+        }
+        match self.snaplen.trim().parse::<i32>() {
+            Ok(snaplen) if snaplen > 0 => snaplen,
+            _ => SAVEFILE_SNAPLEN,
+        }
+    }
+
+    /// Returns the size in bytes after which the savefile has to be rotated, if any
+    pub fn get_rotation_bytes(&self) -> Option<u64> {
+        self.rotate_megabytes
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .filter(|megabytes| *megabytes > 0)
+            .map(|megabytes| megabytes * 1_000_000)
+    }
+
+    /// Returns the time interval after which the savefile has to be rotated, if any
+    pub fn get_rotation_interval(&self) -> Option<Duration> {
+        self.rotate_seconds
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .filter(|seconds| *seconds > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Returns the maximum number of savefiles to be retained on disk, if any
+    pub fn get_max_files(&self) -> Option<usize> {
+        self.max_files
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|max_files| *max_files > 0)
+    }
+
+    /// Returns true if a size or time based rotation has been specified
+    pub fn is_rotating(&self) -> bool {
+        self.get_rotation_bytes().is_some() || self.get_rotation_interval().is_some()
+    }
+
+    /// Returns the options to dump the packets of an additional adapter into savefiles
+    /// of their own, whose name is suffixed with the adapter name
+    pub fn for_device(&self, device_name: &str) -> Self {
+        if !self.is_enabled() {
+            return self.clone();
+        }
+        let path = PathBuf::from(self.path.trim());
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        // adapter names may contain path separators (e.g., on Windows)
+        let suffix = device_name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+        let file_name = match path.extension() {
+            Some(extension) => format!("{stem}_{suffix}.{}", extension.to_string_lossy()),
+            None => format!("{stem}_{suffix}"),
+        };
+        Self {
+            path: path.with_file_name(file_name).to_string_lossy().to_string(),
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::networking::types::savefile_options::{
+        SavefileOptions, DEFAULT_SNAPLEN, SAVEFILE_SNAPLEN,
+    };
+
+    #[test]
+    fn test_snaplen_depends_on_savefile_path() {
+        let mut options = SavefileOptions {
+            snaplen: "1500".to_string(),
+            ..SavefileOptions::default()
+        };
+        assert!(!options.is_enabled());
+        assert_eq!(options.get_snaplen(), DEFAULT_SNAPLEN);
+
+        options.path = "/tmp/sniffnet.pcap".to_string();
+        assert!(options.is_enabled());
+        assert_eq!(options.get_snaplen(), 1500);
+
+        options.snaplen = "not a number".to_string();
+        assert_eq!(options.get_snaplen(), SAVEFILE_SNAPLEN);
+        options.snaplen = "-5".to_string();
+        assert_eq!(options.get_snaplen(), SAVEFILE_SNAPLEN);
+    }
+
+    #[test]
+    fn test_rotation_options() {
+        let mut options = SavefileOptions {
+            path: "/tmp/sniffnet.pcap".to_string(),
+            ..SavefileOptions::default()
+        };
+        assert!(!options.is_rotating());
+        assert_eq!(options.get_max_files(), None);
+
+        options.rotate_megabytes = " 10 ".to_string();
+        assert_eq!(options.get_rotation_bytes(), Some(10_000_000));
+        assert!(options.is_rotating());
+
+        options.rotate_megabytes = "0".to_string();
+        options.rotate_seconds = "3600".to_string();
+        assert_eq!(options.get_rotation_bytes(), None);
+        assert_eq!(
+            options.get_rotation_interval(),
+            Some(Duration::from_secs(3600))
+        );
+        assert!(options.is_rotating());
+
+        options.max_files = "5".to_string();
+        assert_eq!(options.get_max_files(), Some(5));
+        options.max_files = "0".to_string();
+        assert_eq!(options.get_max_files(), None);
+    }
+
+    #[test]
+    fn test_savefile_options_for_device() {
+        let options = SavefileOptions {
+            path: "/tmp/sniffnet.pcap".to_string(),
+            rotate_seconds: "60".to_string(),
+            ..SavefileOptions::default()
+        };
+        let wg_options = options.for_device("wg0");
+        assert_eq!(wg_options.path, "/tmp/sniffnet_wg0.pcap");
+        assert_eq!(wg_options.rotate_seconds, "60");
+        assert_eq!(
+            options.for_device("\\Device\\NPF_Loopback").path,
+            "/tmp/sniffnet__Device_NPF_Loopback.pcap"
+        );
+        assert!(!SavefileOptions::default().for_device("wg0").is_enabled());
+    }
+}
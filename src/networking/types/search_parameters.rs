@@ -9,6 +9,8 @@ pub struct SearchParameters {
     pub country: String,
     /// Autonomous System name
     pub as_name: String,
+    /// Network adapter
+    pub interface: String,
     /// Whether to display only favorites
     pub only_favorites: bool,
 }
@@ -20,6 +22,7 @@ pub fn is_some_filter_active(&self) -> bool {
             || !self.domain.is_empty()
             || !self.country.is_empty()
             || !self.as_name.is_empty()
+            || !self.interface.is_empty()
     }
 }
 
@@ -28,4 +31,5 @@ pub enum FilterInputType {
     Domain,
     Country,
     AS,
+    Interface,
 }
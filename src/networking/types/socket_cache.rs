@@ -0,0 +1,515 @@
+//! Module defining the `SocketCache` struct, which maps the local sockets to the processes owning
+//! them and is periodically refreshed by a dedicated thread.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::{Duration, Instant};
+
+use crate::networking::types::address_port_pair::AddressPortPair;
+use crate::networking::types::process_info::ProcessInfo;
+use crate::networking::types::traffic_direction::TrafficDirection;
+use crate::TransProtocol;
+
+/// Time for which a closed socket is still attributed to its processes,
+/// so that packets of short-lived connections parsed late are not left unknown
+const SOCKET_RETENTION: Duration = Duration::from_secs(30);
+
+/// Endpoints identifying a local socket
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SocketKey {
+    pub trans_protocol: TransProtocol,
+    pub local_address: IpAddr,
+    pub local_port: u16,
+    /// Unspecified for listening or unconnected sockets
+    pub remote_address: IpAddr,
+    /// Zero for listening or unconnected sockets
+    pub remote_port: u16,
+}
+
+impl SocketKey {
+    /// Returns the socket key corresponding to the local side of the given connection,
+    /// if its transport protocol uses ports
+    pub fn from_address_port_pair(
+        key: &AddressPortPair,
+        traffic_direction: TrafficDirection,
+    ) -> Option<Self> {
+        let (port1, port2) = key.get_ports()?;
+        let address1 = key.address1.parse::<IpAddr>().ok()?;
+        let address2 = key.address2.parse::<IpAddr>().ok()?;
+        let ((local_address, local_port), (remote_address, remote_port)) = match traffic_direction {
+            TrafficDirection::Outgoing => ((address1, port1), (address2, port2)),
+            TrafficDirection::Incoming => ((address2, port2), (address1, port1)),
+        };
+        Some(Self {
+            trans_protocol: key.trans_protocol,
+            local_address,
+            local_port,
+            remote_address,
+            remote_port,
+        })
+    }
+
+    fn is_connected(&self) -> bool {
+        self.remote_port != 0 && !self.remote_address.is_unspecified()
+    }
+}
+
+/// User and processes owning a socket
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SocketOwner {
+    /// User ID of the socket owner
+    pub uid: u32,
+    /// Name of the user owning the socket, if known
+    pub username: Option<String>,
+    /// Processes having the socket among their file descriptors
+    pub pids: Vec<u32>,
+    /// Information about the processes, if available
+    pub processes: Vec<ProcessInfo>,
+    /// Last time the socket was found open
+    last_seen: Instant,
+}
+
+impl SocketOwner {
+    pub fn new(
+        uid: u32,
+        username: Option<String>,
+        pids: Vec<u32>,
+        processes: Vec<ProcessInfo>,
+    ) -> Self {
+        Self {
+            uid,
+            username,
+            pids,
+            processes,
+            last_seen: Instant::now(),
+        }
+    }
+
+    /// Returns the names of the owning processes as a single string, if known
+    pub fn get_process_name(&self) -> Option<String> {
+        let mut process_names: Vec<&str> = self
+            .processes
+            .iter()
+            .map(|process| process.name.as_str())
+            .collect();
+        process_names.sort_unstable();
+        process_names.dedup();
+        if process_names.is_empty() {
+            None
+        } else {
+            Some(process_names.join(", "))
+        }
+    }
+}
+
+/// Local sockets with their owners, as observed during the latest refreshes
+#[derive(Default)]
+pub struct SocketCache {
+    /// Sockets indexed by both their endpoints
+    connections: HashMap<SocketKey, SocketOwner>,
+    /// Listening or unconnected sockets, indexed by their local port
+    local_ports: HashMap<(TransProtocol, u16), SocketOwner>,
+}
+
+impl SocketCache {
+    /// Inserts the sockets currently open, replacing the previous owners of the same endpoints
+    /// (e.g., in case of reused ephemeral ports), and drops the sockets closed long ago
+    pub fn update(&mut self, sockets: Vec<(SocketKey, SocketOwner)>) {
+        for (key, owner) in sockets {
+            if !key.is_connected() {
+                self.local_ports
+                    .insert((key.trans_protocol, key.local_port), owner.clone());
+            }
+            self.connections.insert(key, owner);
+        }
+        self.connections
+            .retain(|_, owner| owner.last_seen.elapsed() < SOCKET_RETENTION);
+        self.local_ports
+            .retain(|_, owner| owner.last_seen.elapsed() < SOCKET_RETENTION);
+    }
+
+    /// Returns the owner of the local socket involved in the given connection, if known
+    pub fn get_owner(
+        &self,
+        key: &AddressPortPair,
+        traffic_direction: TrafficDirection,
+    ) -> Option<&SocketOwner> {
+        let socket_key = SocketKey::from_address_port_pair(key, traffic_direction)?;
+        self.connections.get(&socket_key).or_else(|| {
+            self.local_ports
+                .get(&(socket_key.trans_protocol, socket_key.local_port))
+        })
+    }
+}
+
+/// Reads the sockets currently open on the machine, together with their owners
+#[cfg(target_os = "linux")]
+pub fn read_local_sockets() -> Vec<(SocketKey, SocketOwner)> {
+    use crate::utils::users::get_usernames;
+
+    let inode_pids = get_inode_pids();
+    let usernames = get_usernames();
+    let mut processes: HashMap<u32, Option<ProcessInfo>> = HashMap::new();
+    let mut sockets = Vec::new();
+    for (trans_protocol, table) in [
+        (TransProtocol::TCP, "/proc/net/tcp"),
+        (TransProtocol::TCP, "/proc/net/tcp6"),
+        (TransProtocol::UDP, "/proc/net/udp"),
+        (TransProtocol::UDP, "/proc/net/udp6"),
+    ] {
+        let Ok(content) = std::fs::read_to_string(table) else {
+            continue;
+        };
+        // the first line is the header
+        for entry in content.lines().skip(1).filter_map(parse_proc_net_line) {
+            // sockets not belonging to any process (e.g., in TIME_WAIT state) are skipped
+            let Some(pids) = inode_pids.get(&entry.inode) else {
+                continue;
+            };
+            let owner_processes = pids
+                .iter()
+                .filter_map(|pid| {
+                    processes
+                        .entry(*pid)
+                        .or_insert_with(|| ProcessInfo::from_proc(*pid, &usernames))
+                        .clone()
+                })
+                .collect();
+            sockets.push((
+                SocketKey {
+                    trans_protocol,
+                    local_address: entry.local_address,
+                    local_port: entry.local_port,
+                    remote_address: entry.remote_address,
+                    remote_port: entry.remote_port,
+                },
+                SocketOwner::new(
+                    entry.uid,
+                    usernames.get(&entry.uid).cloned(),
+                    pids.clone(),
+                    owner_processes,
+                ),
+            ));
+        }
+    }
+    sockets
+}
+
+/// Reads the sockets currently open on the machine, together with their owners
+#[cfg(not(target_os = "linux"))]
+pub fn read_local_sockets() -> Vec<(SocketKey, SocketOwner)> {
+    use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo};
+
+    use crate::utils::users::get_usernames;
+
+    let usernames = get_usernames();
+    let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
+    let protocol_flags = ProtocolFlags::TCP | ProtocolFlags::UDP;
+    let Ok(sockets_info) = get_sockets_info(af_flags, protocol_flags) else {
+        return Vec::new();
+    };
+    sockets_info
+        .into_iter()
+        .map(|socket| {
+            let key = match socket.protocol_socket_info {
+                ProtocolSocketInfo::Tcp(tcp) => SocketKey {
+                    trans_protocol: TransProtocol::TCP,
+                    local_address: normalize_address(tcp.local_addr),
+                    local_port: tcp.local_port,
+                    remote_address: normalize_address(tcp.remote_addr),
+                    remote_port: tcp.remote_port,
+                },
+                ProtocolSocketInfo::Udp(udp) => SocketKey {
+                    trans_protocol: TransProtocol::UDP,
+                    local_address: normalize_address(udp.local_addr),
+                    local_port: udp.local_port,
+                    remote_address: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+                    remote_port: 0,
+                },
+            };
+            (
+                key,
+                SocketOwner::new(
+                    socket.uid,
+                    usernames.get(&socket.uid).cloned(),
+                    socket.associated_pids,
+                    Vec::new(),
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Maps the inode of each socket to the processes having it among their file descriptors
+#[cfg(target_os = "linux")]
+fn get_inode_pids() -> HashMap<u64, Vec<u32>> {
+    let mut inode_pids: HashMap<u64, Vec<u32>> = HashMap::new();
+    let Ok(proc_entries) = std::fs::read_dir("/proc") else {
+        return inode_pids;
+    };
+    for proc_entry in proc_entries.flatten() {
+        let Some(pid) = proc_entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        // processes of other users can't be inspected without privileges
+        let Ok(fds) = std::fs::read_dir(proc_entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            if let Some(inode) = std::fs::read_link(fd.path())
+                .ok()
+                .and_then(|target| parse_socket_inode(&target.to_string_lossy()))
+            {
+                let pids = inode_pids.entry(inode).or_default();
+                if !pids.contains(&pid) {
+                    pids.push(pid);
+                }
+            }
+        }
+    }
+    inode_pids
+}
+
+/// A row of `/proc/net/{tcp,tcp6,udp,udp6}`
+#[derive(PartialEq, Eq, Debug)]
+struct ProcNetEntry {
+    local_address: IpAddr,
+    local_port: u16,
+    remote_address: IpAddr,
+    remote_port: u16,
+    uid: u32,
+    inode: u64,
+}
+
+/// Parses a row of `/proc/net/{tcp,tcp6,udp,udp6}`, formatted as
+/// `sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode ...`
+fn parse_proc_net_line(line: &str) -> Option<ProcNetEntry> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 10 {
+        return None;
+    }
+    let (local_address, local_port) = parse_hex_socket_address(fields[1])?;
+    let (remote_address, remote_port) = parse_hex_socket_address(fields[2])?;
+    Some(ProcNetEntry {
+        local_address,
+        local_port,
+        remote_address,
+        remote_port,
+        uid: fields[7].parse().ok()?,
+        inode: fields[9].parse().ok()?,
+    })
+}
+
+/// Parses an address in the form `0100007F:0035`, where the IP is printed
+/// as a sequence of 32 bits words in host byte order, and the port in hexadecimal
+fn parse_hex_socket_address(hex_address: &str) -> Option<(IpAddr, u16)> {
+    let (hex_ip, hex_port) = hex_address.split_once(':')?;
+    let port = u16::from_str_radix(hex_port, 16).ok()?;
+    let mut words = Vec::new();
+    for i in (0..hex_ip.len()).step_by(8) {
+        let word = u32::from_str_radix(hex_ip.get(i..i + 8)?, 16).ok()?;
+        words.extend_from_slice(&word.to_ne_bytes());
+    }
+    let address = match words.len() {
+        4 => IpAddr::V4(Ipv4Addr::new(words[0], words[1], words[2], words[3])),
+        16 => {
+            let octets: [u8; 16] = words.try_into().ok()?;
+            normalize_address(IpAddr::V6(Ipv6Addr::from(octets)))
+        }
+        _ => return None,
+    };
+    Some((address, port))
+}
+
+/// Returns the IPv4 address corresponding to an IPv4-mapped IPv6 address (used by dual stack sockets)
+fn normalize_address(address: IpAddr) -> IpAddr {
+    match address {
+        IpAddr::V6(ipv6) => ipv6.to_ipv4_mapped().map_or(IpAddr::V6(ipv6), IpAddr::V4),
+        IpAddr::V4(_) => address,
+    }
+}
+
+/// Extracts the inode from a file descriptor link in the form `socket:[12345]`
+fn parse_socket_inode(link: &str) -> Option<u64> {
+    link.strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use crate::networking::types::address_port_pair::AddressPortPair;
+    use crate::networking::types::process_info::ProcessInfo;
+    use crate::networking::types::socket_cache::{
+        parse_hex_socket_address, parse_proc_net_line, parse_socket_inode, ProcNetEntry,
+        SocketCache, SocketKey, SocketOwner,
+    };
+    use crate::networking::types::traffic_direction::TrafficDirection;
+    use crate::TransProtocol;
+
+    fn ip(address: &str) -> IpAddr {
+        address.parse().unwrap()
+    }
+
+    fn get_owner(uid: u32, processes: &[(u32, &str)]) -> SocketOwner {
+        SocketOwner::new(
+            uid,
+            None,
+            processes.iter().map(|(pid, _)| *pid).collect(),
+            processes
+                .iter()
+                .map(|(pid, name)| ProcessInfo {
+                    pid: *pid,
+                    name: (*name).to_string(),
+                    ..ProcessInfo::default()
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn test_parse_hex_socket_address() {
+        assert_eq!(
+            parse_hex_socket_address("0100007F:0035"),
+            Some((ip("127.0.0.1"), 53))
+        );
+        assert_eq!(
+            parse_hex_socket_address("00000000000000000000000001000000:1F90"),
+            Some((ip("::1"), 8080))
+        );
+        // IPv4-mapped address of a dual stack socket
+        assert_eq!(
+            parse_hex_socket_address("0000000000000000FFFF00000A01A8C0:01BB"),
+            Some((ip("192.168.1.10"), 443))
+        );
+        assert_eq!(parse_hex_socket_address("0100007F"), None);
+        assert_eq!(parse_hex_socket_address("0100007:0035"), None);
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn test_parse_proc_net_line() {
+        let line = "   1: 0A01A8C0:C350 01010101:01BB 01 00000000:00000000 02:000A7B3C 00000000  1000        0 123456 2 0000000000000000 20 4 30 10 -1";
+        assert_eq!(
+            parse_proc_net_line(line),
+            Some(ProcNetEntry {
+                local_address: ip("192.168.1.10"),
+                local_port: 50000,
+                remote_address: ip("1.1.1.1"),
+                remote_port: 443,
+                uid: 1000,
+                inode: 123_456,
+            })
+        );
+        assert_eq!(
+            parse_proc_net_line(
+                "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_socket_inode() {
+        assert_eq!(parse_socket_inode("socket:[98765]"), Some(98765));
+        assert_eq!(parse_socket_inode("pipe:[98765]"), None);
+        assert_eq!(parse_socket_inode("/dev/null"), None);
+    }
+
+    #[test]
+    fn test_socket_cache_lookup() {
+        let mut cache = SocketCache::default();
+        let connected = SocketKey {
+            trans_protocol: TransProtocol::TCP,
+            local_address: ip("192.168.1.10"),
+            local_port: 50000,
+            remote_address: ip("1.1.1.1"),
+            remote_port: 443,
+        };
+        let listening = SocketKey {
+            trans_protocol: TransProtocol::UDP,
+            local_address: ip("0.0.0.0"),
+            local_port: 53,
+            remote_address: ip("0.0.0.0"),
+            remote_port: 0,
+        };
+        cache.update(vec![
+            (connected, get_owner(1000, &[(42, "curl")])),
+            (listening, get_owner(0, &[(7, "dnsmasq"), (8, "dnsmasq")])),
+        ]);
+
+        let outgoing = AddressPortPair::new(
+            "192.168.1.10".to_string(),
+            50000,
+            "1.1.1.1".to_string(),
+            443,
+            TransProtocol::TCP,
+            "eth0".to_string(),
+        );
+        let owner = cache
+            .get_owner(&outgoing, TrafficDirection::Outgoing)
+            .unwrap();
+        assert_eq!(owner.pids, vec![42]);
+        assert_eq!(owner.get_process_name(), Some("curl".to_string()));
+
+        let incoming = AddressPortPair::new(
+            "192.168.1.20".to_string(),
+            40000,
+            "192.168.1.10".to_string(),
+            53,
+            TransProtocol::UDP,
+            "eth0".to_string(),
+        );
+        let owner = cache
+            .get_owner(&incoming, TrafficDirection::Incoming)
+            .unwrap();
+        assert_eq!(owner.pids, vec![7, 8]);
+        assert_eq!(owner.get_process_name(), Some("dnsmasq".to_string()));
+
+        // same local port towards another host: not attributed to the connected socket
+        let other = AddressPortPair::new(
+            "192.168.1.10".to_string(),
+            50000,
+            "8.8.8.8".to_string(),
+            443,
+            TransProtocol::TCP,
+            "eth0".to_string(),
+        );
+        assert!(cache
+            .get_owner(&other, TrafficDirection::Outgoing)
+            .is_none());
+    }
+
+    #[test]
+    fn test_socket_cache_reused_port() {
+        let mut cache = SocketCache::default();
+        let key = SocketKey {
+            trans_protocol: TransProtocol::TCP,
+            local_address: ip("10.0.0.2"),
+            local_port: 40000,
+            remote_address: ip("10.0.0.1"),
+            remote_port: 22,
+        };
+        cache.update(vec![(key, get_owner(1000, &[(10, "ssh")]))]);
+        cache.update(vec![(key, get_owner(1001, &[(20, "scp")]))]);
+        let pair = AddressPortPair::new(
+            "10.0.0.2".to_string(),
+            40000,
+            "10.0.0.1".to_string(),
+            22,
+            TransProtocol::TCP,
+            "eth0".to_string(),
+        );
+        let owner = cache.get_owner(&pair, TrafficDirection::Outgoing).unwrap();
+        assert_eq!(owner.uid, 1001);
+        assert_eq!(owner.pids, vec![20]);
+    }
+}
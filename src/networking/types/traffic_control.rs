@@ -1,25 +1,126 @@
+//! Module defining the `TrafficControl` struct, which configures the throttling rules on Linux.
+//!
+//! The qdiscs, classes and filters (and the IFB devices they need) are configured by sending
+//! rtnetlink requests to the kernel, while the packets of the throttled processes and users
+//! are still marked by running `iptables` and `ip6tables`: every step returns a typed
+//! `TrafficControlError` in case of failure.
+
 use std::{
     collections::HashMap,
-    fs::File,
-    io,
-    process::{Child, Command},
+    ffi::OsStr,
+    fs, io, iter,
+    ops::Range,
+    path::Path,
+    process::{self, Command},
+    time::Instant,
 };
 
-#[derive(Hash, Eq, PartialEq, PartialOrd, Ord, Debug)]
+use crate::networking::types::cgroup_hierarchy::{
+    get_net_cls_classid, CgroupHierarchy, CGROUP_ROOT,
+};
+use crate::networking::types::class_statistics::ClassStatistics;
+use crate::networking::types::ip_subnet::IpSubnet;
+use crate::networking::types::port_selector::PortSelector;
+use crate::networking::types::rtnetlink::{
+    get_ifindex, Action, Classifier, Endpoint, Filter, FilterProtocol, FlowerKey, Qdisc, RtNetlink,
+};
+use crate::networking::types::traffic_control_error::TrafficControlError;
+use crate::networking::types::traffic_direction::TrafficDirection;
+
+#[derive(Hash, Eq, PartialEq, PartialOrd, Ord, Debug, Clone, Copy)]
 pub enum ThrottlingTarget {
     Pid(u32),
-    PortEgress(u16),
-    PortIngress(u16),
+    Uid(u32),
+    PortEgress(PortSelector),
+    PortIngress(PortSelector),
+    HostEgress(IpSubnet),
+    HostIngress(IpSubnet),
     Interface,
 }
 
+impl ThrottlingTarget {
+    /// Returns the target made of the traffic of the given ports in the given direction
+    pub fn ports(ports: PortSelector, direction: TrafficDirection) -> Self {
+        match direction {
+            TrafficDirection::Outgoing => ThrottlingTarget::PortEgress(ports),
+            TrafficDirection::Incoming => ThrottlingTarget::PortIngress(ports),
+        }
+    }
+}
+
+/// Limit configured for a throttled target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThrottlingLimit {
+    pub kbps: usize,
+    /// Burst of the policing filters, in kilobytes; `None` if the target is only shaped
+    /// by HTB classes, which use their default burst
+    pub burst_kb: Option<usize>,
+}
+
 #[derive(Debug)]
 pub struct TrafficControl {
-    identifier_counter: u16,
+    /// Identifiers (filter priorities, class IDs and packet marks) taken by the throttled
+    /// targets; they're freed as soon as the targets aren't throttled anymore
     identifiers_table: HashMap<ThrottlingTarget, u16>,
+    limits_table: HashMap<ThrottlingTarget, ThrottlingLimit>,
     interface: String,
+    /// Intermediate Functional Block device used to shape the ingress traffic of the interface
+    ifb_interface: String,
+    /// Cgroup hierarchy used to classify the traffic of the throttled processes
+    cgroup_hierarchy: CgroupHierarchy,
+    /// Throughput measured for the classes of the interface and of its IFB device
+    class_statistics: ClassStatistics,
+    /// Whether the settings have already been removed
+    cleaned_up: bool,
+    /// Failure occurred while creating the settings shared by all the throttling rules,
+    /// until it's reported to the user
+    setup_error: Option<TrafficControlError>,
 }
 
+/// Priority of the ingress filter restoring the connection marks set by `iptables`,
+/// which must be evaluated before the filters throttling users
+const CONNMARK_FILTER_PRIO: u16 = 1;
+
+/// IP versions of the traffic matched by the port filters;
+/// each one requires a dedicated filter priority
+const PORT_FILTER_PROTOCOLS: [FilterProtocol; 2] = [FilterProtocol::Ipv4, FilterProtocol::Ipv6];
+
+/// Number of filter priorities taken by the port filters of each direction
+#[allow(clippy::cast_possible_truncation)]
+const PORT_FILTER_PRIOS: u16 = PORT_FILTER_PROTOCOLS.len() as u16;
+
+/// Prefix of the tag attached to the settings created by the application (as the comment of the
+/// `iptables` rules, in the name of the cgroups and as the alias of the IFB devices), used to
+/// find them when cleaning up after a previous run; it's followed by the ID of the process
+/// that created them, so that each instance only removes its own settings
+const INSTANCE_TAG: &str = "sniffnet";
+
+/// Directory listing the running processes
+const PROC_DIR: &str = "/proc";
+
+/// Seconds given to the app to quit by itself when it's interrupted, before its settings
+/// are removed anyway
+pub const QUIT_TIMEOUT_SECS: u64 = 5;
+
+/// Directory listing the network interfaces of the system
+const NET_CLASS_DIR: &str = "/sys/class/net";
+
+/// Priority of the ingress filter policing the whole interface (the one the kernel assigns
+/// to the first filter created without a priority)
+const INTERFACE_FILTER_PRIO: u16 = 0xc000;
+
+/// Identifiers available for the throttled targets, between the priorities of the filters
+/// shared by all the throttling rules
+const TARGET_IDENTIFIERS: Range<u16> = CONNMARK_FILTER_PRIO + 1..INTERFACE_FILTER_PRIO;
+
+/// Priority of the ingress filter redirecting traffic to the IFB device,
+/// which must be evaluated after all the other ingress filters: their policing actions
+/// let the conforming packets continue to it
+const IFB_REDIRECT_PRIO: u16 = u16::MAX;
+
+/// Prefix of the names of the IFB devices created by the application
+const IFB_PREFIX: &str = "snifb";
+
 pub struct IngressThrottleConfig {
     pub kbps: usize,
     pub burst_kb: usize,
@@ -27,264 +128,353 @@ pub struct IngressThrottleConfig {
 
 impl TrafficControl {
     pub fn new(interface: String, interface_config: Option<IngressThrottleConfig>) -> Self {
+        let mut traffic_control = Self {
+            identifiers_table: HashMap::new(),
+            limits_table: HashMap::new(),
+            ifb_interface: get_ifb_name(&interface),
+            interface,
+            cgroup_hierarchy: CgroupHierarchy::detect(Path::new(CGROUP_ROOT)),
+            class_statistics: ClassStatistics::default(),
+            cleaned_up: false,
+            setup_error: None,
+        };
+        traffic_control.setup_error = traffic_control.setup(interface_config).err();
+        traffic_control
+    }
+
+    /// Returns the failure occurred while creating the settings shared by all the throttling
+    /// rules, if it hasn't been returned yet
+    pub fn take_setup_error(&mut self) -> Option<TrafficControlError> {
+        self.setup_error.take()
+    }
+
+    /// Creates the qdiscs and the filters shared by all the throttling rules
+    fn setup(
+        &mut self,
+        interface_config: Option<IngressThrottleConfig>,
+    ) -> Result<(), TrafficControlError> {
+        let interface = &self.interface;
+        let ifb_interface = &self.ifb_interface;
+
         // Clean up all the qdiscs and otherwise before doing anything in case there are previous
         // qdiscs (like if the application was irregularly terminated before and the resources were
-        // not properly cleaned)
-        let _ = Command::new("tc")
-            .args(["qdisc", "del", "dev", &interface, "root"])
-            .output();
-        // Clean the ingress qdisc
-        let _ = Command::new("tc")
-            .args(["qdisc", "del", "dev", &interface, "ingress"])
-            .output();
+        // not properly cleaned); they may not exist, so failures are expected here
+        Self::clean_traffic_control_settings(interface.clone());
 
         // Create the root qdisc.
-        if Command::new("tc")
-            .args([
-                "qdisc", "add", "dev", &interface, "root", "handle", "1:", "htb",
-            ])
-            .output()
-            .is_err()
-        {
-            eprintln!("Couldn't make new root qdisc for interface {}", &interface);
-        }
+        run_netlink_step(format!("create the root qdisc of {interface}"), |netlink| {
+            netlink.add_qdisc(interface, Qdisc::Htb)
+        })?;
 
         // Create the ingress qdisc
-        if Command::new("tc")
-            .args(["qdisc", "add", "dev", &interface, "ingress"])
-            .output()
-            .is_err()
-        {
-            eprintln!(
-                "Couldn't make new ingress qdisc for interface {}",
-                &interface
-            );
-        }
+        run_netlink_step(
+            format!("create the ingress qdisc of {interface}"),
+            |netlink| netlink.add_qdisc(interface, Qdisc::Ingress),
+        )?;
+
+        // Create the filter that redirects cgroup packets to corresponding classes
+        run_netlink_step(
+            format!("create the cgroup filter of {interface}"),
+            |netlink| netlink.add_filter(interface, &get_cgroup_filter()),
+        )?;
+
+        // Create the filter that copies the mark of the connection (set on egress by `iptables`)
+        // to the incoming packets, so that they can be throttled by user
+        run_netlink_step(
+            format!("restore the connection marks on the ingress of {interface}"),
+            |netlink| netlink.add_filter(interface, &get_connmark_filter()),
+        )?;
+
+        // Create the IFB device and redirect to it the ingress traffic of the interface, so that
+        // it can be shaped with an HTB tree as if it was egress traffic
+        run_netlink_step(
+            format!("create the IFB device {ifb_interface}"),
+            |netlink| {
+                netlink.add_ifb_device(ifb_interface)?;
+                netlink.set_link_alias(ifb_interface, &get_instance_tag())
+            },
+        )?;
+        run_netlink_step(
+            format!("bring up the IFB device {ifb_interface}"),
+            |netlink| netlink.set_link_up(ifb_interface),
+        )?;
+        run_netlink_step(
+            format!("create the root qdisc of {ifb_interface}"),
+            |netlink| netlink.add_qdisc(ifb_interface, Qdisc::Htb),
+        )?;
+        run_netlink_step(
+            format!("redirect the ingress of {interface} to {ifb_interface}"),
+            |netlink| {
+                let redirect = get_ifb_redirect(get_ifindex(ifb_interface)?);
+                netlink.add_filter(interface, &redirect)
+            },
+        )?;
 
         // If we have a valid interface config, throttle the interface
         if let Some(ingress_config) = interface_config {
-            if Command::new("tc")
-                .args([
-                    "filter",
-                    "add",
-                    "dev",
-                    &interface,
-                    "parent",
-                    "ffff:",
-                    "protocol",
-                    "ip",
-                    "u32",
-                    "match",
-                    "u32",
-                    "0",
-                    "0",
-                    "police",
-                    "rate",
-                    &format!("{}kbps", ingress_config.kbps),
-                    "burst",
-                    &format!("{}k", ingress_config.burst_kb),
-                    "drop",
-                    "flowid",
-                    ":1",
-                ])
-                .output()
-                .is_err()
-            {
-                eprintln!(
-                    "Couldn't throttle the ingress of the interface {}",
-                    &interface
-                );
-            }
+            self.throttle_interface(&ingress_config)?;
         }
 
-        // Create the filter that redirects cgroup packets to corresponding classes
-        let _ = Command::new("tc")
-            .args([
-                "filter", "add", "dev", &interface, "parent", "1:", "handle", "1:", "cgroup",
-            ])
-            .output();
+        Ok(())
+    }
 
-        Self {
-            identifiers_table: HashMap::new(),
-            interface,
-            identifier_counter: 1,
+    /// Throttles the ingress of the interface, replacing its previous limit if any
+    pub fn throttle_interface(
+        &mut self,
+        ingress_config: &IngressThrottleConfig,
+    ) -> Result<(), TrafficControlError> {
+        let interface = &self.interface;
+
+        // Remove the old filter (it may not exist)
+        let _ = run_netlink_step(
+            format!("remove the ingress limit of {interface}"),
+            |netlink| netlink.delete_filters(interface, Qdisc::Ingress, INTERFACE_FILTER_PRIO),
+        );
+
+        run_netlink_step(format!("throttle the ingress of {interface}"), |netlink| {
+            netlink.add_filter(interface, &get_interface_filter(ingress_config))
+        })?;
+        self.limits_table.insert(
+            ThrottlingTarget::Interface,
+            ThrottlingLimit {
+                kbps: ingress_config.kbps,
+                burst_kb: Some(ingress_config.burst_kb),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Removes the limit of the ingress of the interface
+    pub fn unthrottle_interface(&mut self) -> Result<(), TrafficControlError> {
+        if self
+            .limits_table
+            .remove(&ThrottlingTarget::Interface)
+            .is_some()
+        {
+            run_netlink_step(
+                format!("remove the ingress limit of {}", self.interface),
+                |netlink| {
+                    netlink.delete_filters(&self.interface, Qdisc::Ingress, INTERFACE_FILTER_PRIO)
+                },
+            )?;
         }
+
+        Ok(())
     }
 
     /// Returns true if the connection is throttled, false otherwise
     pub fn pid_is_throttled(&self, pid: u32) -> bool {
         self.identifiers_table
-            .get(&ThrottlingTarget::Pid(pid))
-            .is_some()
+            .contains_key(&ThrottlingTarget::Pid(pid))
     }
 
-    /// TODO: More testing needed
-    ///
-    /// Returns true if the port is throttled, false otherwise
+    /// Returns true if the user is throttled, false otherwise
+    pub fn uid_is_throttled(&self, uid: u32) -> bool {
+        self.identifiers_table
+            .contains_key(&ThrottlingTarget::Uid(uid))
+    }
+
+    /// Returns true if the port is throttled as done by `throttle_port`, i.e., in both directions
+    /// and for any transport protocol, false otherwise: the port being part of a throttled range,
+    /// or being throttled in a single direction, isn't enough
     pub fn port_is_throttled(&self, port: u16) -> bool {
+        [TrafficDirection::Outgoing, TrafficDirection::Incoming]
+            .into_iter()
+            .all(|direction| self.ports_are_throttled(PortSelector::single(port), direction))
+    }
+
+    /// Returns true if the ports are throttled in the given direction, false otherwise
+    pub fn ports_are_throttled(&self, ports: PortSelector, direction: TrafficDirection) -> bool {
         self.identifiers_table
-            .get(&ThrottlingTarget::PortEgress(port))
-            .is_some()
-            && self
-                .identifiers_table
-                .get(&ThrottlingTarget::PortIngress(port))
-                .is_some()
+            .contains_key(&ThrottlingTarget::ports(ports, direction))
     }
 
-    /// TODO: More testing needed
-    ///
+    /// Throttles the traffic of a local port, both on egress and on ingress, for IPv4 and IPv6
+    /// and for both TCP and UDP.
     /// If the burst is not specified, the default of 256k is used
     pub fn throttle_port(
         &mut self,
         port: u16,
         kilobytes_per_second: usize,
         burst_in_kilobytes: Option<usize>,
-    ) -> io::Result<()> {
-        // Get prios for ingress and egress filters. prio will be used as a kind of ID to delete
-        // the filters when we need to unthrottle/rethrottle
-        let egress_port_prio = self
-            .identifiers_table
-            .get(&ThrottlingTarget::PortEgress(port))
-            .unwrap_or(&self.identifier_counter)
-            .to_owned();
-        let ingress_port_prio = self
-            .identifiers_table
-            .get(&ThrottlingTarget::PortIngress(port))
-            .unwrap_or(&(&self.identifier_counter + 1))
-            .to_owned();
-
-        // Remove any old filter that throttles port on egress
-        let _ = Command::new("tc")
-            .args([
-                "filter",
-                "del",
-                "dev",
-                &self.interface,
-                "prio",
-                &egress_port_prio.to_string(),
-            ])
-            .output();
-
-        // Create the filter to throttle the port on egress
-        Command::new("tc")
-            .args([
-                "filter",
-                "add",
-                "dev",
-                &self.interface,
-                "parent",
-                "1:",
-                "protocol",
-                "ip",
-                "prio",
-                &egress_port_prio.to_string(),
-                "basic",
-                "match",
-                &format!("cmp(u16 at 0 layer transport eq {})", port),
-                "action",
-                "police",
-                "rate",
-                &format!("{}kbps", kilobytes_per_second),
-                "burst",
-                &format!("{}k", burst_in_kilobytes.unwrap_or(256)),
-            ])
-            .output()?;
-
-        // Remove any old filter that throttles port on ingress
-        let _ = Command::new("tc")
-            .args([
-                "filter",
-                "del",
-                "dev",
-                &self.interface,
-                "prio",
-                &ingress_port_prio.to_string(),
-            ])
-            .output();
-
-        // Create the filter to throttle the port on ingress
-        Command::new("tc")
-            .args([
-                "filter",
-                "add",
-                "dev",
-                &self.interface,
-                "ingress",
-                "protocol",
-                "ip",
-                "prio",
-                &egress_port_prio.to_string(),
-                "basic",
-                "match",
-                &format!("cmp(u16 at 2 layer transport eq {})", port),
-                "action",
-                "police",
-                "rate",
-                &format!("{}kbps", kilobytes_per_second),
-                "burst",
-                &format!("{}k", burst_in_kilobytes.unwrap_or(256)),
-            ])
-            .output()?;
+    ) -> Result<(), TrafficControlError> {
+        for direction in [TrafficDirection::Outgoing, TrafficDirection::Incoming] {
+            self.throttle_ports(
+                PortSelector::single(port),
+                direction,
+                kilobytes_per_second,
+                burst_in_kilobytes,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Unthrottles a port
+    pub fn unthrottle_port(&mut self, port: u16) -> Result<(), TrafficControlError> {
+        for direction in [TrafficDirection::Outgoing, TrafficDirection::Incoming] {
+            self.unthrottle_ports(PortSelector::single(port), direction)?;
+        }
+        Ok(())
+    }
+
+    /// Throttles the traffic of a range of local ports in the given direction,
+    /// for both IPv4 and IPv6.
+    /// If the burst is not specified, the default of 256k is used
+    pub fn throttle_ports(
+        &mut self,
+        ports: PortSelector,
+        direction: TrafficDirection,
+        kilobytes_per_second: usize,
+        burst_in_kilobytes: Option<usize>,
+    ) -> Result<(), TrafficControlError> {
+        let target = ThrottlingTarget::ports(ports, direction);
+        let direction_name = get_direction_name(direction);
+        // Get the prio of the filters. prio will be used as a kind of ID to delete the filters
+        // when we need to unthrottle/rethrottle; a prio is taken for each IP version
+        let prio = self.get_identifier(
+            target,
+            format!("throttle the {direction_name} of ports {ports}"),
+        )?;
+        let burst_kb = burst_in_kilobytes.unwrap_or(256);
+        let action = get_police_action(kilobytes_per_second, burst_kb);
+
+        // Remove any old filter that throttles the ports (it may not exist)
+        for protocol_prio in get_port_filter_prios(prio) {
+            let _ = run_netlink_step(
+                format!("remove the {direction_name} filters of ports {ports}"),
+                |netlink| {
+                    netlink.delete_filters(&self.interface, get_qdisc(direction), protocol_prio)
+                },
+            );
+        }
+
+        // Create the filters to throttle the ports
+        for filter in get_port_filters(direction, prio, ports, &action) {
+            run_netlink_step(
+                format!("throttle the {direction_name} of ports {ports}"),
+                |netlink| netlink.add_filter(&self.interface, &filter),
+            )?;
+        }
 
         // If everything successful, push to `process_to_control_groups`
-        self.identifiers_table
-            .insert(ThrottlingTarget::PortEgress(port), egress_port_prio);
-        self.identifiers_table
-            .insert(ThrottlingTarget::PortIngress(port), ingress_port_prio);
+        self.identifiers_table.insert(target, prio);
+        self.limits_table.insert(
+            target,
+            ThrottlingLimit {
+                kbps: kilobytes_per_second,
+                burst_kb: Some(burst_kb),
+            },
+        );
+
+        Ok(())
+    }
 
-        // If we used a new identifier (used the counter), then increment the counter
-        if egress_port_prio == self.identifier_counter {
-            self.identifier_counter += 1;
+    /// Removes the filters throttling a range of local ports in the given direction
+    pub fn unthrottle_ports(
+        &mut self,
+        ports: PortSelector,
+        direction: TrafficDirection,
+    ) -> Result<(), TrafficControlError> {
+        let target = ThrottlingTarget::ports(ports, direction);
+        if let Some(prio) = self.identifiers_table.remove(&target) {
+            self.limits_table.remove(&target);
+            let direction_name = get_direction_name(direction);
+            for protocol_prio in get_port_filter_prios(prio) {
+                run_netlink_step(
+                    format!("remove the {direction_name} filters of ports {ports}"),
+                    |netlink| {
+                        netlink.delete_filters(&self.interface, get_qdisc(direction), protocol_prio)
+                    },
+                )?;
+            }
         }
-        if ingress_port_prio == self.identifier_counter {
-            self.identifier_counter += 1;
+
+        Ok(())
+    }
+
+    /// Returns true if the traffic exchanged with the remote subnet is throttled, false otherwise
+    pub fn host_is_throttled(&self, subnet: IpSubnet) -> bool {
+        self.identifiers_table
+            .contains_key(&ThrottlingTarget::HostEgress(subnet))
+    }
+
+    /// Throttles the traffic exchanged with a remote address or subnet, both on egress
+    /// and on ingress, with the default burst of 256k
+    pub fn throttle_host(
+        &mut self,
+        subnet: IpSubnet,
+        kilobytes_per_second: usize,
+    ) -> Result<(), TrafficControlError> {
+        // Get prios for egress and ingress filters: the subnet has a single IP version,
+        // so a prio is enough for each direction
+        let egress_prio = self.get_identifier(
+            ThrottlingTarget::HostEgress(subnet),
+            format!("throttle host {subnet}"),
+        )?;
+        let burst_kb = 256;
+        let action = get_police_action(kilobytes_per_second, burst_kb);
+
+        for (direction, prio) in [
+            (TrafficDirection::Outgoing, egress_prio),
+            (TrafficDirection::Incoming, egress_prio + 1),
+        ] {
+            let direction_name = get_direction_name(direction);
+
+            // Remove any old filter that throttles the host (it may not exist)
+            let _ = run_netlink_step(
+                format!("remove the {direction_name} filter of host {subnet}"),
+                |netlink| netlink.delete_filters(&self.interface, get_qdisc(direction), prio),
+            );
+
+            // Create the filter to throttle the host
+            run_netlink_step(
+                format!("throttle the {direction_name} of host {subnet}"),
+                |netlink| {
+                    let filter = get_host_filter(direction, prio, subnet, &action);
+                    netlink.add_filter(&self.interface, &filter)
+                },
+            )?;
+
+            let target = match direction {
+                TrafficDirection::Outgoing => ThrottlingTarget::HostEgress(subnet),
+                TrafficDirection::Incoming => ThrottlingTarget::HostIngress(subnet),
+            };
+            self.identifiers_table.insert(target, prio);
+            self.limits_table.insert(
+                target,
+                ThrottlingLimit {
+                    kbps: kilobytes_per_second,
+                    burst_kb: Some(burst_kb),
+                },
+            );
         }
 
         Ok(())
     }
 
-    /// TODO: More testing needed
-    ///
-    /// Unthrottles a port
-    pub fn unthrottle_port(&mut self, port: u16) -> io::Result<()> {
-        // Remove the filters that are being used to throttle the port
-        // Get prios for ingress and egress filters. prio will be used as a kind of ID to delete
-        // the filters when we need to unthrottle/rethrottle
-        if self.port_is_throttled(port) {
-            let egress_port_prio = self
-                .identifiers_table
-                .get(&ThrottlingTarget::PortEgress(port))
-                .unwrap()
-                .to_owned();
-            let ingress_port_prio = self
-                .identifiers_table
-                .get(&ThrottlingTarget::PortIngress(port))
-                .unwrap()
-                .to_owned();
-
-            // Remove any old filter that throttles port on egress
-            Command::new("tc")
-                .args([
-                    "filter",
-                    "del",
-                    "dev",
-                    &self.interface,
-                    "prio",
-                    &egress_port_prio.to_string(),
-                ])
-                .output()?;
-
-            // Remove any old filter that throttles port on ingress
-            Command::new("tc")
-                .args([
-                    "filter",
-                    "del",
-                    "dev",
-                    &self.interface,
-                    "prio",
-                    &ingress_port_prio.to_string(),
-                ])
-                .output()?;
+    /// Removes the filters throttling the traffic exchanged with a remote address or subnet
+    pub fn unthrottle_host(&mut self, subnet: IpSubnet) -> Result<(), TrafficControlError> {
+        for (direction, target) in [
+            (
+                TrafficDirection::Outgoing,
+                ThrottlingTarget::HostEgress(subnet),
+            ),
+            (
+                TrafficDirection::Incoming,
+                ThrottlingTarget::HostIngress(subnet),
+            ),
+        ] {
+            if let Some(prio) = self.identifiers_table.remove(&target) {
+                self.limits_table.remove(&target);
+                run_netlink_step(
+                    format!(
+                        "remove the {} filter of host {subnet}",
+                        get_direction_name(direction)
+                    ),
+                    |netlink| netlink.delete_filters(&self.interface, get_qdisc(direction), prio),
+                )?;
+            }
         }
 
         Ok(())
@@ -292,149 +482,1212 @@ pub fn unthrottle_port(&mut self, port: u16) -> io::Result<()> {
 
     /// This is based on the information obtained from here:
     /// https://unix.stackexchange.com/questions/328308/how-can-i-limit-download-bandwidth-of-an-existing-process-iptables-tc
-    /// NOTE: This only throttles on egress, as throttling on ingress by PID proved to be very hard
-    pub fn throttle_pid(&mut self, pid: u32, kilobytes_per_second: usize) -> io::Result<()> {
-        let pid_classid = self
-            .identifiers_table
-            .get(&ThrottlingTarget::Pid(pid))
-            .unwrap_or(&self.identifier_counter)
-            .to_owned();
+    /// On ingress, the packets of the connections opened by the cgroup of the process are
+    /// recognized by the mark saved on egress, and shaped by a class of the IFB device.
+    /// With the unified cgroup hierarchy there's no class ID associated to the cgroup,
+    /// so the mark is used to classify the packets on egress as well.
+    /// If a step fails, the settings already created for the process are removed
+    pub fn throttle_pid(
+        &mut self,
+        pid: u32,
+        kilobytes_per_second: usize,
+    ) -> Result<(), TrafficControlError> {
+        let pid_classid = self.get_identifier(
+            ThrottlingTarget::Pid(pid),
+            format!("throttle process {pid}"),
+        )?;
+
+        if let Err(err) = self.apply_pid_throttling(pid, pid_classid, kilobytes_per_second) {
+            // Roll back the cgroup, the marking rules, the filters and the classes created so far
+            // (the ones not created yet can't be removed, so failures are ignored)
+            let _ = self.remove_pid_throttling(pid, pid_classid);
+            self.identifiers_table.remove(&ThrottlingTarget::Pid(pid));
+            self.limits_table.remove(&ThrottlingTarget::Pid(pid));
+            return Err(err);
+        }
 
+        // If everything successful, push to `process_to_control_groups`
+        self.identifiers_table
+            .insert(ThrottlingTarget::Pid(pid), pid_classid);
+        self.limits_table.insert(
+            ThrottlingTarget::Pid(pid),
+            ThrottlingLimit {
+                kbps: kilobytes_per_second,
+                burst_kb: None,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Creates the settings throttling a process with the given class ID, stopping at the
+    /// first step that fails
+    fn apply_pid_throttling(
+        &self,
+        pid: u32,
+        pid_classid: u16,
+        kilobytes_per_second: usize,
+    ) -> Result<(), TrafficControlError> {
         if !self.pid_is_throttled(pid) {
             // Create a control group for the pid
-            Command::new("cgcreate")
-                .args(["-g", &format!("net_cls:sniffnet_{}", pid)])
-                .output()
-                .unwrap();
+            let cgroup = self.cgroup_hierarchy.get_process_cgroup(pid);
+            fs::create_dir_all(&cgroup).map_err(|error| TrafficControlError::Cgroup {
+                step: format!("create the cgroup of process {pid}"),
+                path: cgroup.clone(),
+                error,
+            })?;
 
             // Set the classid for the newly created cgroup
-            let cgroup_classid_file = File::create(format!(
-                "/sys/fs/cgroup/net_cls/sniffnet_{}/net_cls.classid",
-                pid
-            ))
-            .unwrap();
-            let mut cgroup_classid: Child = Command::new("echo")
-                .arg(&format!("0x1{:04x}", pid_classid))
-                .stdout(cgroup_classid_file)
-                .spawn()
-                .unwrap();
-            cgroup_classid.wait().unwrap();
+            if let CgroupHierarchy::NetCls(_) = self.cgroup_hierarchy {
+                write_cgroup_file(
+                    format!("set the classid of the cgroup of process {pid}"),
+                    &cgroup.join("net_cls.classid"),
+                    &get_net_cls_classid(pid_classid),
+                )?;
+            }
 
             // Move the pid into the newly created cgroup
-            Command::new("cgclassify")
-                .args(["-g", &format!("net_cls:sniffnet_{}", pid), &pid.to_string()])
-                .output()
-                .unwrap();
-        }
-
-        // Remove the class of the pid if it exists, and don't do anything otherwise. This is
-        // useful when we are rethrottling a pid, since in that case the class already exists and
-        // we need to remove it first in order to change the throttling speed
-        let _ = Command::new("tc")
-            .args([
-                "class",
-                "del",
-                "dev",
-                &self.interface,
-                "classid",
-                &format!("1:{}", pid_classid),
-            ])
-            .output();
-
-        // Add the class with the same classid as the one that was written to the cgroup.
-        // The filter added above makes it so that traffic coming from a cgroup with a certain
-        // classid will be put in the tc class with the corresponding classid
-        Command::new("tc")
-            .args([
-                "class",
-                "add",
-                "dev",
+            write_cgroup_file(
+                format!("move process {pid} into its cgroup"),
+                &cgroup.join("cgroup.procs"),
+                &pid.to_string(),
+            )?;
+
+            // Mark the packets generated by the cgroup, and save the mark in their connection
+            let cgroup_match = self.cgroup_hierarchy.get_iptables_match(pid, pid_classid);
+            for iptables in ["iptables", "ip6tables"] {
+                for rule in get_mark_rules(&cgroup_match, pid_classid) {
+                    run_step(
+                        format!("mark the packets of process {pid}"),
+                        iptables,
+                        ["-t", "mangle", "-A"]
+                            .into_iter()
+                            .map(String::from)
+                            .chain(rule),
+                    )?;
+                }
+            }
+
+            // Put the marked packets in the class of the pid on ingress (and on egress when the
+            // cgroup filter can't be used)
+            let mut interfaces = vec![&self.ifb_interface];
+            if let CgroupHierarchy::Unified(_) = self.cgroup_hierarchy {
+                interfaces.push(&self.interface);
+            }
+            for interface in interfaces {
+                run_netlink_step(
+                    format!("classify the packets of process {pid} on {interface}"),
+                    |netlink| netlink.add_filter(interface, &get_mark_filter(pid_classid)),
+                )?;
+            }
+        }
+
+        for interface in [&self.interface, &self.ifb_interface] {
+            // Remove the class of the pid if it exists, and don't do anything otherwise. This is
+            // useful when we are rethrottling a pid, since in that case the class already exists
+            // and we need to remove it first in order to change the throttling speed
+            let _ = run_netlink_step(
+                format!("remove the class of process {pid} on {interface}"),
+                |netlink| netlink.delete_class(interface, pid_classid),
+            );
+
+            // Add the class with the same classid as the one that was written to the cgroup.
+            // The filters added above make it so that traffic coming from a cgroup with a certain
+            // classid (or belonging to its connections) will be put in the tc class
+            // with the corresponding classid
+            run_netlink_step(
+                format!("throttle process {pid} on {interface}"),
+                |netlink| {
+                    netlink.add_htb_class(
+                        interface,
+                        pid_classid,
+                        get_bytes_per_second(kilobytes_per_second),
+                    )
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn unthrottle_pid(&mut self, pid: u32) -> Result<(), TrafficControlError> {
+        // Remove the classes of the pid and the rules marking its connections if they exist,
+        // and move the process back to the root cgroup
+        if let Some(pid_classid) = self.identifiers_table.remove(&ThrottlingTarget::Pid(pid)) {
+            self.limits_table.remove(&ThrottlingTarget::Pid(pid));
+            self.remove_pid_throttling(pid, pid_classid)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the settings throttling a process with the given class ID: all the steps are
+    /// attempted even if some of them fail, and the first failure is returned
+    fn remove_pid_throttling(&self, pid: u32, pid_classid: u16) -> Result<(), TrafficControlError> {
+        let mut result = Ok(());
+        let mut keep_first_error = |step_result: Result<(), TrafficControlError>| {
+            if result.is_ok() {
+                result = step_result;
+            }
+        };
+        let cgroup_match = self.cgroup_hierarchy.get_iptables_match(pid, pid_classid);
+        for iptables in ["iptables", "ip6tables"] {
+            for rule in get_mark_rules(&cgroup_match, pid_classid) {
+                keep_first_error(run_step(
+                    format!("stop marking the packets of process {pid}"),
+                    iptables,
+                    ["-t", "mangle", "-D"]
+                        .into_iter()
+                        .map(String::from)
+                        .chain(rule),
+                ));
+            }
+        }
+        let mut interfaces = vec![&self.ifb_interface];
+        if let CgroupHierarchy::Unified(_) = self.cgroup_hierarchy {
+            interfaces.push(&self.interface);
+        }
+        for interface in interfaces {
+            keep_first_error(run_netlink_step(
+                format!("remove the filter of process {pid} on {interface}"),
+                |netlink| netlink.delete_filters(interface, Qdisc::Htb, pid_classid),
+            ));
+        }
+        for interface in [&self.interface, &self.ifb_interface] {
+            keep_first_error(run_netlink_step(
+                format!("remove the class of process {pid} on {interface}"),
+                |netlink| netlink.delete_class(interface, pid_classid),
+            ));
+        }
+        keep_first_error(
+            self.cgroup_hierarchy
+                .release_process_cgroup(pid)
+                .map_err(|error| TrafficControlError::Cgroup {
+                    step: format!("remove the cgroup of process {pid}"),
+                    path: self.cgroup_hierarchy.get_process_cgroup(pid),
+                    error,
+                }),
+        );
+        result
+    }
+
+    /// Throttles all the traffic of the sockets owned by a user.
+    /// Outgoing packets are marked by `iptables` according to their owner and the mark is also
+    /// saved in the connection, so that it can be restored on ingress: on egress the marked packets
+    /// are put in a dedicated HTB class, while on ingress they are policed
+    pub fn throttle_uid(
+        &mut self,
+        uid: u32,
+        kilobytes_per_second: usize,
+    ) -> Result<(), TrafficControlError> {
+        let uid_id =
+            self.get_identifier(ThrottlingTarget::Uid(uid), format!("throttle user {uid}"))?;
+        let uid_string = uid.to_string();
+        let owner_match = ["-m", "owner", "--uid-owner", &uid_string];
+        if !self.uid_is_throttled(uid) {
+            // Mark the packets generated by the user, and save the mark in their connection
+            for iptables in ["iptables", "ip6tables"] {
+                for rule in get_mark_rules(&owner_match, uid_id) {
+                    run_step(
+                        format!("mark the packets of user {uid}"),
+                        iptables,
+                        ["-t", "mangle", "-A"]
+                            .into_iter()
+                            .map(String::from)
+                            .chain(rule),
+                    )?;
+                }
+            }
+
+            // Put the marked packets in the class of the user on egress
+            run_netlink_step(format!("classify the egress of user {uid}"), |netlink| {
+                netlink.add_filter(&self.interface, &get_mark_filter(uid_id))
+            })?;
+        }
+
+        // Remove the class and the ingress filter of the user if they exist: this is useful when
+        // we are rethrottling a user, in order to change the throttling speed
+        let _ = run_netlink_step(format!("remove the class of user {uid}"), |netlink| {
+            netlink.delete_class(&self.interface, uid_id)
+        });
+        let _ = run_netlink_step(
+            format!("remove the ingress filter of user {uid}"),
+            |netlink| netlink.delete_filters(&self.interface, Qdisc::Ingress, uid_id),
+        );
+
+        // Limit the egress traffic of the user
+        run_netlink_step(format!("throttle the egress of user {uid}"), |netlink| {
+            netlink.add_htb_class(
                 &self.interface,
-                "parent",
-                "1:",
-                "classid",
-                &format!("1:{}", pid_classid),
-                "htb",
-                "rate",
-                &format!("{}kbps", kilobytes_per_second),
-            ])
-            .output()?;
+                uid_id,
+                get_bytes_per_second(kilobytes_per_second),
+            )
+        })?;
+
+        // Limit the ingress traffic of the user
+        run_netlink_step(format!("throttle the ingress of user {uid}"), |netlink| {
+            let filter =
+                get_mark_police_filter(uid_id, get_police_action(kilobytes_per_second, 256));
+            netlink.add_filter(&self.interface, &filter)
+        })?;
 
-        // If everything successful, push to `process_to_control_groups`
         self.identifiers_table
-            .insert(ThrottlingTarget::Pid(pid), pid_classid);
+            .insert(ThrottlingTarget::Uid(uid), uid_id);
+        self.limits_table.insert(
+            ThrottlingTarget::Uid(uid),
+            ThrottlingLimit {
+                kbps: kilobytes_per_second,
+                burst_kb: Some(256),
+            },
+        );
+
+        Ok(())
+    }
 
-        // If we used a new classid (used the counter), then increment the counter
-        if pid_classid == self.identifier_counter {
-            self.identifier_counter += 1;
+    /// Removes the rules throttling the traffic of a user
+    pub fn unthrottle_uid(&mut self, uid: u32) -> Result<(), TrafficControlError> {
+        if let Some(uid_id) = self.identifiers_table.remove(&ThrottlingTarget::Uid(uid)) {
+            self.limits_table.remove(&ThrottlingTarget::Uid(uid));
+            let uid_string = uid.to_string();
+            let owner_match = ["-m", "owner", "--uid-owner", &uid_string];
+            for iptables in ["iptables", "ip6tables"] {
+                for rule in get_mark_rules(&owner_match, uid_id) {
+                    run_step(
+                        format!("stop marking the packets of user {uid}"),
+                        iptables,
+                        ["-t", "mangle", "-D"]
+                            .into_iter()
+                            .map(String::from)
+                            .chain(rule),
+                    )?;
+                }
+            }
+            run_netlink_step(
+                format!("remove the egress filter of user {uid}"),
+                |netlink| netlink.delete_filters(&self.interface, Qdisc::Htb, uid_id),
+            )?;
+            run_netlink_step(
+                format!("remove the ingress filter of user {uid}"),
+                |netlink| netlink.delete_filters(&self.interface, Qdisc::Ingress, uid_id),
+            )?;
+            run_netlink_step(format!("remove the class of user {uid}"), |netlink| {
+                netlink.delete_class(&self.interface, uid_id)
+            })?;
         }
 
         Ok(())
     }
 
-    pub fn unthrottle_pid(&mut self, pid: u32) -> io::Result<()> {
-        // TODO: also remove pid from cgroup
-        // For now, just removing the tc class will suffice, since that is what is being used to
-        // set the actual throttling
-
-        // Remove the class of the pid if it exists
-        if self.pid_is_throttled(pid) {
-            Command::new("tc")
-                .args([
-                    "class",
-                    "del",
-                    "dev",
-                    &self.interface,
-                    "classid",
-                    &format!(
-                        "1:{}",
-                        self.identifiers_table
-                            .remove(&ThrottlingTarget::Pid(pid))
-                            .unwrap_or_else(|| panic!("Couldn't unthrottle {}", pid))
-                    ),
-                ])
-                .output()?;
+    /// Returns the identifier of the target if it's already throttled, or the first one
+    /// of the free identifiers it needs otherwise
+    fn get_identifier(
+        &self,
+        target: ThrottlingTarget,
+        step: String,
+    ) -> Result<u16, TrafficControlError> {
+        if let Some(id) = self.identifiers_table.get(&target) {
+            return Ok(*id);
         }
+        let taken: Vec<Range<u16>> = self
+            .identifiers_table
+            .iter()
+            .map(|(target, id)| *id..id + get_identifiers_count(*target))
+            .collect();
+        get_free_identifiers(taken, get_identifiers_count(target))
+            .ok_or(TrafficControlError::NoIdentifier { step })
+    }
 
-        Ok(())
+    /// Returns the limits currently applied, sorted by target
+    pub fn get_throttling_limits(&self) -> Vec<(ThrottlingTarget, ThrottlingLimit)> {
+        let mut limits: Vec<(ThrottlingTarget, ThrottlingLimit)> = self
+            .limits_table
+            .iter()
+            .map(|(target, limit)| (*target, *limit))
+            .collect();
+        limits.sort_by_key(|(target, _)| *target);
+        limits
+    }
+
+    /// Reads the byte counters of the classes of the interface and of its IFB device,
+    /// updating their measured throughput; devices that can't be queried are ignored
+    pub fn refresh_class_statistics(&mut self) {
+        let mut class_bytes = HashMap::new();
+        for interface in [&self.interface, &self.ifb_interface] {
+            if let Ok(bytes) = run_netlink_step(
+                format!("read the statistics of the classes of {interface}"),
+                |netlink| netlink.get_class_bytes(interface),
+            ) {
+                class_bytes.extend(
+                    bytes
+                        .into_iter()
+                        .map(|(classid, bytes)| ((interface.clone(), classid), bytes)),
+                );
+            }
+        }
+        self.class_statistics.update(class_bytes, Instant::now());
+    }
+
+    /// Returns the measured egress and ingress throughput of a target, in bytes per second;
+    /// it's only available for the traffic shaped by HTB classes
+    pub fn get_throughput(&self, target: ThrottlingTarget) -> (Option<u64>, Option<u64>) {
+        let Some(id) = self.identifiers_table.get(&target).copied() else {
+            return (None, None);
+        };
+        match target {
+            ThrottlingTarget::Pid(_) => (
+                self.class_statistics.get_throughput(&self.interface, id),
+                self.class_statistics
+                    .get_throughput(&self.ifb_interface, id),
+            ),
+            ThrottlingTarget::Uid(_) => (
+                self.class_statistics.get_throughput(&self.interface, id),
+                None,
+            ),
+            _ => (None, None),
+        }
+    }
+
+    /// Removes all the settings created for the interface: the rules marking the packets,
+    /// the cgroups of the throttled processes (which are moved back to the root cgroup) and the
+    /// qdiscs, so that the kernel restores the default ones.
+    /// Failures are ignored, so that the remaining settings are removed anyway; it has no effect
+    /// if called again
+    pub fn clean_up(&mut self) {
+        if self.cleaned_up {
+            return;
+        }
+        self.cleaned_up = true;
+        let instance_tag = get_instance_tag();
+        remove_marking_rules(|tag| tag == instance_tag);
+        for target in self.identifiers_table.keys() {
+            if let ThrottlingTarget::Pid(pid) = target {
+                let _ = self.cgroup_hierarchy.release_process_cgroup(*pid);
+            }
+        }
+        Self::clean_traffic_control_settings(self.interface.clone());
+        self.identifiers_table.clear();
+        self.limits_table.clear();
+    }
+
+    /// Removes the settings created by this instance without relying on the state of the
+    /// controller, which may be locked (e.g., if the app panicked while updating the settings):
+    /// they are found by the tag of the instance
+    pub fn clean_up_instance() {
+        let instance_tag = get_instance_tag();
+        Self::remove_tagged_settings(|tag| tag == instance_tag);
+    }
+
+    /// Removes the settings left by the runs that couldn't clean them up (e.g., because they
+    /// crashed), without relying on their state; the settings of the instances still running
+    /// are left untouched
+    pub fn recover() {
+        Self::remove_tagged_settings(|tag| is_stale_instance_tag(tag, is_process_running));
+    }
+
+    /// Removes the settings created by the instances whose tag satisfies the predicate:
+    /// the rules marking the packets, the cgroups of the throttled processes, and the qdiscs
+    /// of the interfaces having an IFB device created by them
+    fn remove_tagged_settings(is_removed: impl Fn(&str) -> bool) {
+        remove_marking_rules(&is_removed);
+        CgroupHierarchy::detect(Path::new(CGROUP_ROOT)).release_process_cgroups(&is_removed);
+        let interfaces: Vec<String> = fs::read_dir(NET_CLASS_DIR)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        for interface in &interfaces {
+            let ifb_interface = get_ifb_name(interface);
+            if !interfaces.contains(&ifb_interface) {
+                continue;
+            }
+            let alias = fs::read_to_string(
+                Path::new(NET_CLASS_DIR)
+                    .join(&ifb_interface)
+                    .join("ifalias"),
+            )
+            .unwrap_or_default();
+            // older versions didn't tag the IFB devices
+            let tag = match alias.trim() {
+                "" => INSTANCE_TAG,
+                alias => alias,
+            };
+            if is_removed(tag) {
+                Self::clean_traffic_control_settings(interface.clone());
+            }
+        }
     }
 
-    // TODO: Also run this function when we return from a scan and change interfaces
+    /// Removes the qdiscs of the interface and its IFB device, together with all the classes
+    /// and filters attached to them; the ones that don't exist are ignored
     pub fn clean_traffic_control_settings(interface: String) {
-        Command::new("tc")
-            .args(["qdisc", "del", "dev", &interface, "root"])
-            .output()
-            .expect(&("root qdisc couldn't be freed for the interface".to_string() + &interface));
+        let _ = run_netlink_step(format!("remove the IFB device of {interface}"), |netlink| {
+            netlink.delete_link(&get_ifb_name(&interface))
+        });
+        let _ = run_netlink_step(format!("remove the root qdisc of {interface}"), |netlink| {
+            netlink.delete_qdisc(&interface, Qdisc::Htb)
+        });
+        let _ = run_netlink_step(
+            format!("remove the ingress qdisc of {interface}"),
+            |netlink| netlink.delete_qdisc(&interface, Qdisc::Ingress),
+        );
+    }
+}
 
-        // Clean the ingress qdisc
-        Command::new("tc")
-            .args(["qdisc", "del", "dev", &interface, "ingress"])
-            .output()
-            .expect(
-                &("ingress qdisc couldn't be freed for the interface".to_string() + &interface),
+/// Returns the `iptables` rules (in the `mangle` table, without the command) marking the packets
+/// sent by the sockets satisfying the given match, and saving the mark in their connection
+fn get_mark_rules<S: AsRef<str>>(socket_match: &[S], mark: u16) -> [Vec<String>; 2] {
+    [
+        vec![
+            "MARK".to_string(),
+            "--set-mark".to_string(),
+            mark.to_string(),
+        ],
+        vec!["CONNMARK".to_string(), "--save-mark".to_string()],
+    ]
+    .map(|target| {
+        iter::once("OUTPUT".to_string())
+            .chain(socket_match.iter().map(|arg| arg.as_ref().to_string()))
+            .chain(["-m", "comment", "--comment"].map(String::from))
+            .chain(iter::once(get_instance_tag()))
+            .chain(iter::once("-j".to_string()))
+            .chain(target)
+            .collect()
+    })
+}
+
+/// Returns the filter putting the packets of the cgroups in the HTB classes with their class ID
+fn get_cgroup_filter() -> Filter {
+    Filter {
+        qdisc: Qdisc::Htb,
+        protocol: FilterProtocol::All,
+        // the kernel assigns the priority, since the filter is never removed alone
+        prio: 0,
+        classifier: Classifier::Cgroup,
+    }
+}
+
+/// Returns the ingress filter copying the mark of the connection to the incoming packets
+fn get_connmark_filter() -> Filter {
+    Filter {
+        qdisc: Qdisc::Ingress,
+        protocol: FilterProtocol::All,
+        prio: CONNMARK_FILTER_PRIO,
+        classifier: Classifier::Matchall(Action::RestoreConnmark),
+    }
+}
+
+/// Returns the filter putting the packets with the given mark in the HTB class having
+/// the same minor ID, which is also the priority of the filter
+fn get_mark_filter(mark: u16) -> Filter {
+    Filter {
+        qdisc: Qdisc::Htb,
+        protocol: FilterProtocol::All,
+        prio: mark,
+        classifier: Classifier::FwClass {
+            mark,
+            classid: mark,
+        },
+    }
+}
+
+/// Returns the ingress filter policing the packets with the given mark,
+/// which is also the priority of the filter
+fn get_mark_police_filter(mark: u16, action: Action) -> Filter {
+    Filter {
+        qdisc: Qdisc::Ingress,
+        protocol: FilterProtocol::All,
+        prio: mark,
+        classifier: Classifier::FwAction { mark, action },
+    }
+}
+
+/// Returns the filter policing all the ingress traffic of the interface,
+/// regardless of its protocol
+fn get_interface_filter(ingress_config: &IngressThrottleConfig) -> Filter {
+    Filter {
+        qdisc: Qdisc::Ingress,
+        protocol: FilterProtocol::All,
+        prio: INTERFACE_FILTER_PRIO,
+        classifier: Classifier::Matchall(get_police_action(
+            ingress_config.kbps,
+            ingress_config.burst_kb,
+        )),
+    }
+}
+
+/// Returns the filter redirecting all the ingress traffic of the interface to the IFB device
+/// with the given index, after it has been evaluated by the other ingress filters
+fn get_ifb_redirect(ifb_ifindex: u32) -> Filter {
+    Filter {
+        qdisc: Qdisc::Ingress,
+        protocol: FilterProtocol::All,
+        prio: IFB_REDIRECT_PRIO,
+        classifier: Classifier::Matchall(Action::Redirect {
+            ifindex: ifb_ifindex,
+        }),
+    }
+}
+
+/// Returns the `police` action limiting the traffic matched by a filter:
+/// the exceeding packets are dropped, while the conforming ones continue to be classified by
+/// the following filters, so that they can still be shaped by the classes of the processes
+/// and users and, on ingress, be redirected to the IFB device
+fn get_police_action(kilobytes_per_second: usize, burst_kb: usize) -> Action {
+    Action::Police {
+        bytes_per_second: get_bytes_per_second(kilobytes_per_second),
+        burst_bytes: u32::try_from(burst_kb.saturating_mul(1024)).unwrap_or(u32::MAX),
+    }
+}
+
+/// Converts a rate in kilobytes per second (as configured by the user) to bytes per second
+fn get_bytes_per_second(kilobytes_per_second: usize) -> u64 {
+    u64::try_from(kilobytes_per_second)
+        .unwrap_or(u64::MAX)
+        .saturating_mul(1000)
+}
+
+/// Returns the name of the given direction, used to describe the steps
+fn get_direction_name(direction: TrafficDirection) -> &'static str {
+    match direction {
+        TrafficDirection::Outgoing => "egress",
+        TrafficDirection::Incoming => "ingress",
+    }
+}
+
+/// Returns the qdisc the filters of the given direction are attached to
+fn get_qdisc(direction: TrafficDirection) -> Qdisc {
+    match direction {
+        TrafficDirection::Outgoing => Qdisc::Htb,
+        TrafficDirection::Incoming => Qdisc::Ingress,
+    }
+}
+
+/// Returns the filters policing the traffic of a range of local ports in the given direction:
+/// a `flower` filter is needed for each IP version and transport protocol,
+/// and the filters of each IP version share a priority, starting from the given one
+fn get_port_filters(
+    direction: TrafficDirection,
+    prio: u16,
+    ports: PortSelector,
+    action: &Action,
+) -> Vec<Filter> {
+    let endpoint = match direction {
+        TrafficDirection::Outgoing => Endpoint::Source,
+        TrafficDirection::Incoming => Endpoint::Destination,
+    };
+    let mut filters = Vec::new();
+    for (protocol_prio, protocol) in iter::zip(get_port_filter_prios(prio), PORT_FILTER_PROTOCOLS) {
+        for transport in ports.get_transports() {
+            filters.push(Filter {
+                qdisc: get_qdisc(direction),
+                protocol,
+                prio: protocol_prio,
+                classifier: Classifier::Flower {
+                    key: FlowerKey::Ports {
+                        endpoint,
+                        protocol: *transport,
+                        first: ports.first,
+                        last: ports.last,
+                    },
+                    action: action.clone(),
+                },
+            });
+        }
+    }
+    filters
+}
+
+/// Returns the priorities of the port filters created starting from the given one
+fn get_port_filter_prios(prio: u16) -> impl Iterator<Item = u16> {
+    prio..prio + PORT_FILTER_PRIOS
+}
+
+/// Returns the filter policing the traffic exchanged with a remote address or subnet
+/// in the given direction
+fn get_host_filter(
+    direction: TrafficDirection,
+    prio: u16,
+    subnet: IpSubnet,
+    action: &Action,
+) -> Filter {
+    let endpoint = match direction {
+        TrafficDirection::Outgoing => Endpoint::Destination,
+        TrafficDirection::Incoming => Endpoint::Source,
+    };
+    Filter {
+        qdisc: get_qdisc(direction),
+        protocol: FilterProtocol::of_address(subnet.get_address()),
+        prio,
+        classifier: Classifier::Flower {
+            key: FlowerKey::Subnet {
+                endpoint,
+                address: subnet.get_address(),
+                prefix_len: subnet.get_prefix_len(),
+            },
+            action: action.clone(),
+        },
+    }
+}
+
+/// Removes the `iptables` rules created by the instances whose tag (the comment of the rules)
+/// satisfies the predicate; failures are ignored
+fn remove_marking_rules(is_removed: impl Fn(&str) -> bool) {
+    for iptables in ["iptables", "ip6tables"] {
+        let Ok(listing) = run_query(
+            "list the rules marking the packets".to_string(),
+            iptables,
+            ["-t", "mangle", "-S", "OUTPUT"],
+        ) else {
+            continue;
+        };
+        for rule in get_marking_rules(&listing, &is_removed) {
+            let _ = run_step(
+                "remove a rule marking the packets".to_string(),
+                iptables,
+                ["-t", "mangle", "-D"]
+                    .into_iter()
+                    .map(String::from)
+                    .chain(rule),
             );
+        }
+    }
+}
+
+/// Returns the rules (without the command) among the ones listed by `iptables -S` whose
+/// comment satisfies the predicate
+fn get_marking_rules(listing: &str, is_selected: impl Fn(&str) -> bool) -> Vec<Vec<String>> {
+    listing
+        .lines()
+        .filter_map(|line| line.strip_prefix("-A "))
+        .map(|rule| {
+            rule.split_whitespace()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        })
+        .filter(|rule| {
+            rule.windows(2)
+                .any(|args| args[0] == "--comment" && is_selected(&args[1]))
+        })
+        .collect()
+}
+
+/// Returns the tag attached to the settings created by this instance of the app
+pub fn get_instance_tag() -> String {
+    format!("{INSTANCE_TAG}-{}", process::id())
+}
+
+/// Whether the given tag was attached to some settings by an instance of the app that isn't
+/// running anymore, according to the given predicate (older versions didn't append the process
+/// ID, so their settings are always stale)
+fn is_stale_instance_tag(tag: &str, is_running: impl Fn(u32) -> bool) -> bool {
+    match tag.strip_prefix(INSTANCE_TAG) {
+        Some("") => true,
+        Some(suffix) => suffix
+            .strip_prefix('-')
+            .and_then(|pid| pid.parse().ok())
+            .is_some_and(|pid| !is_running(pid)),
+        None => false,
+    }
+}
+
+/// Whether the process with the given ID is running
+fn is_process_running(pid: u32) -> bool {
+    Path::new(PROC_DIR).join(pid.to_string()).exists()
+}
+
+/// Returns the number of consecutive identifiers taken by a target
+fn get_identifiers_count(target: ThrottlingTarget) -> u16 {
+    match target {
+        // a filter priority for each IP version
+        ThrottlingTarget::PortEgress(_) | ThrottlingTarget::PortIngress(_) => PORT_FILTER_PRIOS,
+        // the identifier following the egress one is used for the ingress filter
+        ThrottlingTarget::HostEgress(_) => 2,
+        ThrottlingTarget::Pid(_)
+        | ThrottlingTarget::Uid(_)
+        | ThrottlingTarget::HostIngress(_)
+        | ThrottlingTarget::Interface => 1,
+    }
+}
+
+/// Returns the first of the lowest `count` consecutive identifiers not overlapping
+/// the taken ranges, if there are enough free identifiers
+fn get_free_identifiers(mut taken: Vec<Range<u16>>, count: u16) -> Option<u16> {
+    taken.sort_by_key(|range| range.start);
+    let mut first = TARGET_IDENTIFIERS.start;
+    for range in taken {
+        if range.start >= first + count {
+            break;
+        }
+        first = first.max(range.end);
+    }
+    (first + count <= TARGET_IDENTIFIERS.end).then_some(first)
+}
+
+/// Returns the name of the IFB device used for the given interface, made unique by the index
+/// of the interface (interface names can't be longer than 15 characters, so they can't be
+/// included in full); if the interface doesn't exist, the bare prefix is returned
+fn get_ifb_name(interface: &str) -> String {
+    get_ifb_name_from_index(get_ifindex(interface).ok())
+}
+
+/// Returns the name of the IFB device of the interface with the given index,
+/// which is at most 15 characters long
+fn get_ifb_name_from_index(ifindex: Option<u32>) -> String {
+    match ifindex {
+        Some(ifindex) => format!("{IFB_PREFIX}{ifindex}"),
+        None => IFB_PREFIX.to_string(),
+    }
+}
+
+/// Writes the given value to a file of the cgroup filesystem
+fn write_cgroup_file(step: String, path: &Path, value: &str) -> Result<(), TrafficControlError> {
+    fs::write(path, value).map_err(|error| TrafficControlError::Cgroup {
+        step,
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+/// Sends the rtnetlink requests needed to complete the given step, returning an error if the
+/// connection to the kernel couldn't be opened or if a request failed
+fn run_netlink_step<T>(
+    step: String,
+    request: impl FnOnce(&mut RtNetlink) -> io::Result<T>,
+) -> Result<T, TrafficControlError> {
+    RtNetlink::open()
+        .and_then(|mut netlink| request(&mut netlink))
+        .map_err(|error| TrafficControlError::Netlink { step, error })
+}
+
+/// Runs the program needed to complete the given step, returning an error if it couldn't be
+/// executed or if it failed
+fn run_step<I, S>(step: String, program: &str, args: I) -> Result<(), TrafficControlError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    run_query(step, program, args).map(|_| ())
+}
+
+/// Runs the program needed to complete the given step, returning its standard output
+fn run_query<I, S>(step: String, program: &str, args: I) -> Result<String, TrafficControlError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output =
+        Command::new(program)
+            .args(args)
+            .output()
+            .map_err(|error| TrafficControlError::Spawn {
+                step: step.clone(),
+                program: program.to_string(),
+                error,
+            })?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(TrafficControlError::Command {
+            step,
+            program: program.to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        })
     }
 }
 
 impl Drop for TrafficControl {
-    // Clean up the qdiscs and classes and filters...
-    // TODO: also move all the processes out of the cgroups they are in
-    // TODO: run this when application terminates to ensure the cleaning up happens in that case
     fn drop(&mut self) {
-        // Command::new("tc")
-        //     .args(["qdisc", "del", "dev", &self.interface, "root"])
-        //     .output()
-        //     .expect(
-        //         &("root qdisc couldn't be freed for the interface".to_string() + &self.interface),
-        //     );
-
-        // // Clean the ingress qdisc
-        // Command::new("tc")
-        //     .args(["qdisc", "del", "dev", &self.interface, "ingress"])
-        //     .output()
-        //     .expect(
-        //         &("ingress qdisc couldn't be freed for the interface".to_string()
-        //             + &self.interface),
-        //     );
+        self.clean_up();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use crate::networking::types::cgroup_hierarchy::CgroupHierarchy;
+    use crate::networking::types::class_statistics::ClassStatistics;
+    use crate::networking::types::ip_subnet::IpSubnet;
+    use crate::networking::types::port_selector::PortSelector;
+    use crate::networking::types::rtnetlink::{
+        Action, Classifier, Endpoint, Filter, FilterProtocol, FlowerKey, Qdisc,
+    };
+    use crate::networking::types::traffic_control::{
+        get_free_identifiers, get_host_filter, get_ifb_name_from_index, get_ifb_redirect,
+        get_interface_filter, get_marking_rules, get_police_action, get_port_filter_prios,
+        get_port_filters, is_stale_instance_tag, IngressThrottleConfig, ThrottlingTarget,
+        TrafficControl, IFB_REDIRECT_PRIO, TARGET_IDENTIFIERS,
+    };
+    use crate::networking::types::traffic_direction::TrafficDirection;
+    use crate::TransProtocol;
+
+    /// Returns a controller throttling the given targets, without touching the system
+    /// (it's already marked as cleaned up, so dropping it doesn't remove any setting)
+    fn get_traffic_control(targets: &[ThrottlingTarget]) -> TrafficControl {
+        TrafficControl {
+            identifiers_table: targets
+                .iter()
+                .zip(TARGET_IDENTIFIERS.step_by(2))
+                .map(|(target, id)| (*target, id))
+                .collect(),
+            limits_table: HashMap::new(),
+            interface: "eth0".to_string(),
+            ifb_interface: "snifb2".to_string(),
+            cgroup_hierarchy: CgroupHierarchy::Unified(PathBuf::from("/sys/fs/cgroup")),
+            class_statistics: ClassStatistics::default(),
+            cleaned_up: true,
+            setup_error: None,
+        }
+    }
+
+    /// Returns the filter policing the given ports, as created by `get_port_filters`
+    fn get_port_filter(
+        qdisc: Qdisc,
+        protocol: FilterProtocol,
+        prio: u16,
+        key: FlowerKey,
+        action: &Action,
+    ) -> Filter {
+        Filter {
+            qdisc,
+            protocol,
+            prio,
+            classifier: Classifier::Flower {
+                key,
+                action: action.clone(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_port_filters_cover_ipv4_and_ipv6() {
+        let police = get_police_action(100, 256);
+        assert_eq!(
+            police,
+            Action::Police {
+                bytes_per_second: 100_000,
+                burst_bytes: 262_144
+            }
+        );
+        let filters = get_port_filters(
+            TrafficDirection::Outgoing,
+            10,
+            PortSelector::single(8080),
+            &police,
+        );
+        let key = |protocol| FlowerKey::Ports {
+            endpoint: Endpoint::Source,
+            protocol,
+            first: 8080,
+            last: 8080,
+        };
+        assert_eq!(
+            filters,
+            vec![
+                get_port_filter(
+                    Qdisc::Htb,
+                    FilterProtocol::Ipv4,
+                    10,
+                    key(TransProtocol::TCP),
+                    &police
+                ),
+                get_port_filter(
+                    Qdisc::Htb,
+                    FilterProtocol::Ipv4,
+                    10,
+                    key(TransProtocol::UDP),
+                    &police
+                ),
+                get_port_filter(
+                    Qdisc::Htb,
+                    FilterProtocol::Ipv6,
+                    11,
+                    key(TransProtocol::TCP),
+                    &police
+                ),
+                get_port_filter(
+                    Qdisc::Htb,
+                    FilterProtocol::Ipv6,
+                    11,
+                    key(TransProtocol::UDP),
+                    &police
+                ),
+            ]
+        );
+
+        let filters = get_port_filters(
+            TrafficDirection::Incoming,
+            12,
+            PortSelector::single(8080),
+            &police,
+        );
+        assert_eq!(filters.len(), 4);
+        for filter in &filters {
+            assert_eq!(filter.qdisc, Qdisc::Ingress);
+            let Classifier::Flower {
+                key: FlowerKey::Ports { endpoint, .. },
+                ..
+            } = filter.classifier
+            else {
+                panic!("not a port filter");
+            };
+            assert_eq!(endpoint, Endpoint::Destination);
+        }
+        assert_eq!(filters[3].protocol, FilterProtocol::Ipv6);
+        assert_eq!(filters[3].prio, 13);
+
+        assert_eq!(get_port_filter_prios(12).collect::<Vec<_>>(), vec![12, 13]);
+    }
+
+    #[test]
+    fn test_port_range_filters_match_only_the_protocol() {
+        let ports = PortSelector::new(27000, 27100, TransProtocol::UDP).unwrap();
+        let police = get_police_action(50, 256);
+        let filters = get_port_filters(TrafficDirection::Incoming, 20, ports, &police);
+        let key = FlowerKey::Ports {
+            endpoint: Endpoint::Destination,
+            protocol: TransProtocol::UDP,
+            first: 27000,
+            last: 27100,
+        };
+        assert_eq!(
+            filters,
+            vec![
+                get_port_filter(Qdisc::Ingress, FilterProtocol::Ipv4, 20, key, &police),
+                get_port_filter(Qdisc::Ingress, FilterProtocol::Ipv6, 21, key, &police),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_host_filters() {
+        let police = get_police_action(10, 256);
+        let subnet = IpSubnet::new("10.1.2.3".parse().unwrap(), 8).unwrap();
+        assert_eq!(
+            get_host_filter(TrafficDirection::Outgoing, 30, subnet, &police),
+            Filter {
+                qdisc: Qdisc::Htb,
+                protocol: FilterProtocol::Ipv4,
+                prio: 30,
+                classifier: Classifier::Flower {
+                    key: FlowerKey::Subnet {
+                        endpoint: Endpoint::Destination,
+                        address: "10.0.0.0".parse().unwrap(),
+                        prefix_len: 8
+                    },
+                    action: police.clone()
+                }
+            }
+        );
+        let address = IpSubnet::from("2001:db8::1".parse::<std::net::IpAddr>().unwrap());
+        assert_eq!(
+            get_host_filter(TrafficDirection::Incoming, 31, address, &police),
+            Filter {
+                qdisc: Qdisc::Ingress,
+                protocol: FilterProtocol::Ipv6,
+                prio: 31,
+                classifier: Classifier::Flower {
+                    key: FlowerKey::Subnet {
+                        endpoint: Endpoint::Source,
+                        address: "2001:db8::1".parse().unwrap(),
+                        prefix_len: 128
+                    },
+                    action: police
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_interface_filter_covers_all_protocols() {
+        let filter = get_interface_filter(&IngressThrottleConfig {
+            kbps: 2000,
+            burst_kb: 250,
+        });
+        assert_eq!(
+            filter,
+            Filter {
+                qdisc: Qdisc::Ingress,
+                protocol: FilterProtocol::All,
+                prio: 49152,
+                classifier: Classifier::Matchall(Action::Police {
+                    bytes_per_second: 2_000_000,
+                    burst_bytes: 256_000
+                })
+            }
+        );
+    }
+
+    #[test]
+    fn test_ingress_filters_precede_the_ifb_redirect() {
+        let redirect = get_ifb_redirect(2);
+        assert_eq!(
+            redirect,
+            Filter {
+                qdisc: Qdisc::Ingress,
+                protocol: FilterProtocol::All,
+                prio: 65535,
+                classifier: Classifier::Matchall(Action::Redirect { ifindex: 2 })
+            }
+        );
+
+        // the ingress filters are evaluated by increasing priority, and the policing ones
+        // let the conforming packets continue up to the redirect to the IFB device
+        let subnet = IpSubnet::new("10.1.2.3".parse().unwrap(), 8).unwrap();
+        let mut ingress_filters = get_port_filters(
+            TrafficDirection::Incoming,
+            2,
+            PortSelector::single(8080),
+            &get_police_action(100, 256),
+        );
+        ingress_filters.push(get_host_filter(
+            TrafficDirection::Incoming,
+            5,
+            subnet,
+            &get_police_action(10, 256),
+        ));
+        ingress_filters.push(get_interface_filter(&IngressThrottleConfig {
+            kbps: 2000,
+            burst_kb: 250,
+        }));
+        for filter in &ingress_filters {
+            assert_eq!(filter.qdisc, Qdisc::Ingress);
+            assert!(filter.prio < IFB_REDIRECT_PRIO);
+            assert!(matches!(
+                filter.classifier,
+                Classifier::Flower {
+                    action: Action::Police { .. },
+                    ..
+                } | Classifier::Matchall(Action::Police { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_ifb_names_are_unique() {
+        assert_eq!(get_ifb_name_from_index(Some(2)), "snifb2");
+        assert_ne!(
+            get_ifb_name_from_index(Some(12)),
+            get_ifb_name_from_index(Some(13))
+        );
+        assert!(get_ifb_name_from_index(Some(u32::MAX)).len() <= 15);
+    }
+
+    #[test]
+    fn test_marking_rules_are_recognized() {
+        let listing = "-P OUTPUT ACCEPT\n\
+            -A OUTPUT -m owner --uid-owner 1001 -j MARK --set-xmark 0x7/0xffffffff\n\
+            -A OUTPUT -m owner --uid-owner 1000 -m comment --comment sniffnet \
+            -j MARK --set-xmark 0x2/0xffffffff\n\
+            -A OUTPUT -m cgroup --path sniffnet_1234 -m comment --comment sniffnet-42 \
+            -j CONNMARK --save-mark --nfmask 0xffffffff --ctmask 0xffffffff\n\
+            -A OUTPUT -m owner --uid-owner 1002 -m comment --comment sniffnetwork \
+            -j MARK --set-xmark 0x3/0xffffffff\n";
+        let rules = get_marking_rules(listing, |tag| {
+            tag.starts_with("sniffnet-") || tag == "sniffnet"
+        });
+        assert_eq!(rules.len(), 2);
+        assert_eq!(
+            rules[0].join(" "),
+            "OUTPUT -m owner --uid-owner 1000 -m comment --comment sniffnet \
+            -j MARK --set-xmark 0x2/0xffffffff"
+        );
+        assert_eq!(rules[1][..4], ["OUTPUT", "-m", "cgroup", "--path"]);
+        assert!(get_marking_rules("", |_| true).is_empty());
+
+        // each instance only removes its own rules
+        let rules = get_marking_rules(listing, |tag| tag == "sniffnet-42");
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0][..4], ["OUTPUT", "-m", "cgroup", "--path"]);
+        assert!(get_marking_rules(listing, |tag| tag == "sniffnet-43").is_empty());
+    }
+
+    #[test]
+    fn test_stale_instance_tags() {
+        let is_running = |pid| pid == 42;
+        // the settings of the running instances are left to them
+        assert!(!is_stale_instance_tag("sniffnet-42", is_running));
+        assert!(is_stale_instance_tag("sniffnet-43", is_running));
+        // older versions didn't append the process ID
+        assert!(is_stale_instance_tag("sniffnet", is_running));
+        // the settings of other applications are never removed
+        assert!(!is_stale_instance_tag("sniffnetwork", is_running));
+        assert!(!is_stale_instance_tag("sniffnet-", is_running));
+        assert!(!is_stale_instance_tag("sniffnet-x", is_running));
+        assert!(!is_stale_instance_tag("other", is_running));
+    }
+
+    #[test]
+    fn test_free_identifiers_are_reused() {
+        let first = TARGET_IDENTIFIERS.start;
+        assert_eq!(get_free_identifiers(Vec::new(), 2), Some(first));
+        // the identifiers freed by removed targets are taken again
+        assert_eq!(
+            get_free_identifiers(vec![first + 2..first + 4, first..first + 1], 1),
+            Some(first + 1)
+        );
+        assert_eq!(
+            get_free_identifiers(vec![first + 2..first + 4, first..first + 1], 2),
+            Some(first + 4)
+        );
+        // overlapping ranges
+        assert_eq!(
+            get_free_identifiers(vec![first..first + 3, first + 1..first + 2], 1),
+            Some(first + 3)
+        );
+    }
+
+    #[test]
+    fn test_identifiers_stay_below_the_interface_filter() {
+        let first = TARGET_IDENTIFIERS.start;
+        let last = TARGET_IDENTIFIERS.end - 1;
+        assert_eq!(
+            get_free_identifiers(vec![first..first + 1, first + 1..last], 1),
+            Some(last)
+        );
+        assert_eq!(
+            get_free_identifiers(vec![first..first + 1, first + 1..last], 2),
+            None
+        );
+        assert_eq!(get_free_identifiers(vec![TARGET_IDENTIFIERS], 1), None);
+    }
+
+    #[test]
+    fn test_port_is_throttled_in_both_directions() {
+        let port = PortSelector::single(8080);
+        let traffic_control = get_traffic_control(&[
+            ThrottlingTarget::PortEgress(port),
+            ThrottlingTarget::PortIngress(port),
+            ThrottlingTarget::PortEgress(PortSelector::single(443)),
+        ]);
+        assert!(traffic_control.port_is_throttled(8080));
+        assert!(traffic_control.ports_are_throttled(port, TrafficDirection::Incoming));
+        // a single direction isn't enough
+        assert!(!traffic_control.port_is_throttled(443));
+        assert!(traffic_control
+            .ports_are_throttled(PortSelector::single(443), TrafficDirection::Outgoing));
+        assert!(!traffic_control
+            .ports_are_throttled(PortSelector::single(443), TrafficDirection::Incoming));
+        assert!(!traffic_control.port_is_throttled(80));
+    }
+
+    #[test]
+    fn test_port_in_throttled_range_or_protocol_is_not_throttled() {
+        let range = PortSelector::new(8000, 8100, TransProtocol::Other).unwrap();
+        let tcp = PortSelector::new(9000, 9000, TransProtocol::TCP).unwrap();
+        let traffic_control = get_traffic_control(&[
+            ThrottlingTarget::PortEgress(range),
+            ThrottlingTarget::PortIngress(range),
+            ThrottlingTarget::PortEgress(tcp),
+            ThrottlingTarget::PortIngress(tcp),
+        ]);
+        assert!(!traffic_control.port_is_throttled(8080));
+        assert!(!traffic_control.port_is_throttled(9000));
+        assert!(traffic_control.ports_are_throttled(range, TrafficDirection::Outgoing));
+        assert!(traffic_control.ports_are_throttled(tcp, TrafficDirection::Incoming));
     }
 }
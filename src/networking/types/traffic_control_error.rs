@@ -0,0 +1,141 @@
+//! Module defining the `TrafficControlError` enum, which represents a failure occurred while
+//! configuring the throttling rules.
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Error occurred while configuring traffic control, with the description of the failed step
+#[derive(Debug)]
+pub enum TrafficControlError {
+    /// The program needed for the step couldn't be started (e.g., because it isn't installed)
+    Spawn {
+        step: String,
+        program: String,
+        error: io::Error,
+    },
+    /// The program needed for the step exited with a failure
+    Command {
+        step: String,
+        program: String,
+        stderr: String,
+    },
+    /// A file of the cgroup filesystem couldn't be updated
+    Cgroup {
+        step: String,
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// The kernel rejected the rtnetlink request needed for the step, or it couldn't be sent
+    Netlink { step: String, error: io::Error },
+    /// All the identifiers (filter priorities, class IDs and packet marks) are taken by the
+    /// other throttled targets
+    NoIdentifier { step: String },
+}
+
+impl TrafficControlError {
+    /// Returns the description of the step that failed
+    pub fn get_step(&self) -> &str {
+        match self {
+            TrafficControlError::Spawn { step, .. }
+            | TrafficControlError::Command { step, .. }
+            | TrafficControlError::Cgroup { step, .. }
+            | TrafficControlError::Netlink { step, .. }
+            | TrafficControlError::NoIdentifier { step } => step,
+        }
+    }
+
+    /// Returns the reason why the step failed: the program that couldn't be run or that failed,
+    /// with its error output, the cgroup file that couldn't be updated, the error
+    /// of the rtnetlink request, or the lack of free identifiers
+    pub fn get_cause(&self) -> String {
+        match self {
+            TrafficControlError::Spawn { program, error, .. } => {
+                format!("cannot run '{program}' ({error})")
+            }
+            TrafficControlError::Command {
+                program, stderr, ..
+            } => format!("'{program}' failed ({stderr})"),
+            TrafficControlError::Cgroup { path, error, .. } => {
+                format!("{} ({error})", path.display())
+            }
+            TrafficControlError::Netlink { error, .. } => {
+                format!("netlink request failed ({error})")
+            }
+            TrafficControlError::NoIdentifier { .. } => {
+                "too many targets are throttled".to_string()
+            }
+        }
+    }
+}
+
+impl fmt::Display for TrafficControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "couldn't {}: {}", self.get_step(), self.get_cause())
+    }
+}
+
+impl std::error::Error for TrafficControlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TrafficControlError::Spawn { error, .. }
+            | TrafficControlError::Cgroup { error, .. }
+            | TrafficControlError::Netlink { error, .. } => Some(error),
+            TrafficControlError::Command { .. } | TrafficControlError::NoIdentifier { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::networking::types::traffic_control_error::TrafficControlError;
+
+    #[test]
+    fn test_traffic_control_error_display() {
+        let error = TrafficControlError::Command {
+            step: "mark the packets of user 1000".to_string(),
+            program: "iptables".to_string(),
+            stderr: "iptables: Permission denied (you must be root).".to_string(),
+        };
+        assert_eq!(error.get_step(), "mark the packets of user 1000");
+        assert_eq!(
+            error.get_cause(),
+            "'iptables' failed (iptables: Permission denied (you must be root).)"
+        );
+        assert_eq!(
+            error.to_string(),
+            "couldn't mark the packets of user 1000: 'iptables' failed \
+            (iptables: Permission denied (you must be root).)"
+        );
+
+        let error = TrafficControlError::Spawn {
+            step: "mark the packets of user 1000".to_string(),
+            program: "iptables".to_string(),
+            error: io::Error::new(io::ErrorKind::NotFound, "not found"),
+        };
+        assert_eq!(
+            error.to_string(),
+            "couldn't mark the packets of user 1000: cannot run 'iptables' (not found)"
+        );
+
+        let error = TrafficControlError::Netlink {
+            step: "create the root qdisc of eth0".to_string(),
+            error: io::Error::from_raw_os_error(1),
+        };
+        assert_eq!(
+            error.to_string(),
+            "couldn't create the root qdisc of eth0: netlink request failed \
+            (Operation not permitted (os error 1))"
+        );
+
+        let error = TrafficControlError::NoIdentifier {
+            step: "throttle process 1234".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "couldn't throttle process 1234: too many targets are throttled"
+        );
+    }
+}
@@ -1,16 +1,22 @@
 use std::fmt;
 
-use crate::translations::translations::both_translation;
+use serde::{Deserialize, Serialize};
+
+use crate::translations::translations::all_translation;
 use crate::Language;
 
 /// Enum representing the possible observed values of transport layer protocol.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum TransProtocol {
     /// Transmission Control Protocol
     TCP,
     /// User Datagram Protocol
     UDP,
+    /// Internet Control Message Protocol
+    ICMP,
+    /// Internet Control Message Protocol for IPv6
+    ICMPv6,
     /// Not identified
     Other,
 }
@@ -22,14 +28,27 @@ fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 }
 
 impl TransProtocol {
-    pub(crate) const ALL: [TransProtocol; 3] =
-        [TransProtocol::TCP, TransProtocol::UDP, TransProtocol::Other];
+    pub(crate) const ALL: [TransProtocol; 5] = [
+        TransProtocol::TCP,
+        TransProtocol::UDP,
+        TransProtocol::ICMP,
+        TransProtocol::ICMPv6,
+        TransProtocol::Other,
+    ];
 
     pub fn get_radio_label(&self, language: Language) -> &str {
         match self {
             TransProtocol::TCP => "TCP",
             TransProtocol::UDP => "UDP",
-            TransProtocol::Other => both_translation(language),
+            TransProtocol::ICMP => "ICMP",
+            TransProtocol::ICMPv6 => "ICMPv6",
+            TransProtocol::Other => all_translation(language),
         }
     }
+
+    /// Returns true for ICMP and ICMPv6, whose messages are identified by their type and code
+    /// instead of ports
+    pub fn is_icmp(self) -> bool {
+        matches!(self, TransProtocol::ICMP | TransProtocol::ICMPv6)
+    }
 }
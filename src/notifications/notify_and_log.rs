@@ -1,22 +1,31 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 
-use chrono::Local;
+use chrono::{DateTime, Local};
 
+use crate::configs::types::data_quota::DataQuota;
+use crate::networking::types::host::Host;
+use crate::networking::types::traffic_direction::TrafficDirection;
 use crate::notifications::types::logged_notification::{
-    BytesThresholdExceeded, FavoriteTransmitted, LoggedNotification, PacketsThresholdExceeded,
+    BytesThresholdExceeded, CaptureFailed, FavoriteTransmitted, LoggedNotification,
+    PacketsThresholdExceeded, ProcessThresholdExceeded, QuotaExceeded, ThrottlingFailed,
 };
-use crate::notifications::types::notifications::Notifications;
+use crate::notifications::types::notifications::{Notifications, ProcessNotification};
 use crate::notifications::types::sound::{play, Sound};
-use crate::{InfoTraffic, RunTimeData};
+use crate::{ChartType, InfoTraffic, RunTimeData};
 
-/// Checks if one or more notifications have to be emitted and logs them.
+/// Checks if one or more notifications have to be emitted and logs them,
+/// given the favorite hosts that exchanged data in the last interval and the interval timestamp.
 ///
 /// It returns the number of new notifications emitted
 pub fn notify_and_log(
     runtime_data: &mut RunTimeData,
     notifications: Notifications,
     info_traffic: &Arc<Mutex<InfoTraffic>>,
+    favorites: &HashSet<Host>,
+    timestamp: DateTime<Local>,
 ) -> usize {
+    let formatted_timestamp = timestamp.to_string().get(11..19).unwrap().to_string();
     let mut already_emitted_sound = false;
     let mut emitted_notifications = 0;
     // packets threshold
@@ -37,7 +46,7 @@ pub fn notify_and_log(
                     threshold: notifications.packets_notification.previous_threshold,
                     incoming: received_packets_entry.try_into().unwrap(),
                     outgoing: sent_packets_entry.try_into().unwrap(),
-                    timestamp: Local::now().to_string().get(11..19).unwrap().to_string(),
+                    timestamp: formatted_timestamp.clone(),
                 }),
             );
             if notifications.packets_notification.sound.ne(&Sound::None) {
@@ -68,7 +77,7 @@ pub fn notify_and_log(
                     threshold: notifications.bytes_notification.previous_threshold,
                     incoming: received_bytes_entry.try_into().unwrap(),
                     outgoing: sent_bytes_entry.try_into().unwrap(),
-                    timestamp: Local::now().to_string().get(11..19).unwrap().to_string(),
+                    timestamp: formatted_timestamp.clone(),
                 }),
             );
             if !already_emitted_sound && notifications.bytes_notification.sound.ne(&Sound::None) {
@@ -79,15 +88,9 @@ pub fn notify_and_log(
         }
     }
     // from favorites
-    if notifications.favorite_notification.notify_on_favorite
-        && !info_traffic
-            .lock()
-            .unwrap()
-            .favorites_last_interval
-            .is_empty()
-    {
+    if notifications.favorite_notification.notify_on_favorite && !favorites.is_empty() {
         let info_traffic_lock = info_traffic.lock().unwrap();
-        for host in &info_traffic_lock.favorites_last_interval.clone() {
+        for host in favorites {
             //log this notification
             emitted_notifications += 1;
             if runtime_data.logged_notifications.len() >= 30 {
@@ -100,7 +103,7 @@ pub fn notify_and_log(
                     FavoriteTransmitted {
                         host: host.clone(),
                         data_info_host: info_traffic_lock.hosts.get(host).unwrap().clone(),
-                        timestamp: Local::now().to_string().get(11..19).unwrap().to_string(),
+                        timestamp: formatted_timestamp.clone(),
                     },
                 ));
         }
@@ -111,8 +114,405 @@ pub fn notify_and_log(
                 notifications.favorite_notification.sound,
                 notifications.volume,
             );
+            already_emitted_sound = true;
+        }
+    }
+    // from processes
+    let info_traffic_lock = info_traffic.lock().unwrap();
+    let process_traffic = get_process_traffic(&info_traffic_lock);
+    let processes_threshold_exceeded = get_processes_threshold_exceeded(
+        &info_traffic_lock,
+        &process_traffic,
+        &runtime_data.process_traffic_prev,
+        notifications.process_notification,
+        &formatted_timestamp,
+    );
+    drop(info_traffic_lock);
+    runtime_data.process_traffic_prev = process_traffic;
+    if !processes_threshold_exceeded.is_empty() {
+        for process_threshold_exceeded in processes_threshold_exceeded {
+            //log this notification
+            emitted_notifications += 1;
+            if runtime_data.logged_notifications.len() >= 30 {
+                runtime_data.logged_notifications.pop_back();
+            }
+            runtime_data.logged_notifications.push_front(
+                LoggedNotification::ProcessThresholdExceeded(process_threshold_exceeded),
+            );
+        }
+        if !already_emitted_sound && notifications.process_notification.sound.ne(&Sound::None) {
+            // emit sound
+            play(
+                notifications.process_notification.sound,
+                notifications.volume,
+            );
         }
     }
 
     emitted_notifications
 }
+
+/// Returns the packets and bytes exchanged by each process since the capture started,
+/// indexed by PID
+fn get_process_traffic(info_traffic: &InfoTraffic) -> HashMap<u32, (u128, u128)> {
+    let mut process_traffic: HashMap<u32, (u128, u128)> = HashMap::new();
+    for val in info_traffic.map.values() {
+        for pid in val.pids.iter().flatten() {
+            let traffic = process_traffic.entry(*pid).or_default();
+            traffic.0 += val.transmitted_packets;
+            traffic.1 += val.transmitted_bytes;
+        }
+    }
+    process_traffic
+}
+
+/// Returns the processes whose traffic in the last interval exceeds the threshold, if set;
+/// processes sharing a name are evaluated together, so that both single PIDs and process names
+/// exceeding the threshold are reported
+fn get_processes_threshold_exceeded(
+    info_traffic: &InfoTraffic,
+    process_traffic: &HashMap<u32, (u128, u128)>,
+    process_traffic_prev: &HashMap<u32, (u128, u128)>,
+    process_notification: ProcessNotification,
+    timestamp: &str,
+) -> Vec<ProcessThresholdExceeded> {
+    let Some(threshold) = process_notification.threshold else {
+        return Vec::new();
+    };
+    let mut processes_by_name: BTreeMap<String, (Vec<u32>, u128)> = BTreeMap::new();
+    for (pid, (packets, bytes)) in process_traffic {
+        let (packets_prev, bytes_prev) = process_traffic_prev.get(pid).copied().unwrap_or_default();
+        let value = match process_notification.threshold_type {
+            ChartType::Packets => packets.saturating_sub(packets_prev),
+            ChartType::Bytes => bytes.saturating_sub(bytes_prev),
+        };
+        if value == 0 {
+            continue;
+        }
+        let name = info_traffic
+            .processes
+            .get(pid)
+            .map_or_else(|| pid.to_string(), |process| process.name.clone());
+        let entry = processes_by_name.entry(name).or_default();
+        entry.0.push(*pid);
+        entry.1 += value;
+    }
+
+    processes_by_name
+        .into_iter()
+        .filter(|(_, (_, value))| *value > u128::from(threshold))
+        .map(|(name, (mut pids, value))| {
+            pids.sort_unstable();
+            ProcessThresholdExceeded {
+                users: get_process_users(info_traffic, &pids),
+                top_hosts: get_top_hosts(info_traffic, &pids),
+                name,
+                pids,
+                threshold,
+                threshold_type: process_notification.threshold_type,
+                value,
+                timestamp: timestamp.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Returns the names (or IDs, if unknown) of the users running the given processes
+fn get_process_users(info_traffic: &InfoTraffic, pids: &[u32]) -> Vec<String> {
+    let mut users = Vec::new();
+    for process in pids
+        .iter()
+        .filter_map(|pid| info_traffic.processes.get(pid))
+    {
+        let user = process
+            .username
+            .clone()
+            .unwrap_or_else(|| process.uid.to_string());
+        if !users.contains(&user) {
+            users.push(user);
+        }
+    }
+    users
+}
+
+/// Returns up to three remote hosts which exchanged the most bytes with the given processes,
+/// identified by their domain if resolved
+fn get_top_hosts(info_traffic: &InfoTraffic, pids: &[u32]) -> Vec<String> {
+    let mut hosts: HashMap<String, u128> = HashMap::new();
+    for (key, val) in &info_traffic.map {
+        if !val.pids.iter().flatten().any(|pid| pids.contains(pid)) {
+            continue;
+        }
+        let remote_address = match val.traffic_direction {
+            TrafficDirection::Outgoing => &key.address2,
+            TrafficDirection::Incoming => &key.address1,
+        };
+        let host = match info_traffic.addresses_resolved.get(remote_address) {
+            Some((_, host)) if !host.domain.is_empty() => host.domain.clone(),
+            _ => remote_address.clone(),
+        };
+        *hosts.entry(host).or_default() += val.transmitted_bytes;
+    }
+    let mut hosts: Vec<(String, u128)> = hosts.into_iter().collect();
+    hosts.sort_by(|(host1, bytes1), (host2, bytes2)| bytes2.cmp(bytes1).then(host1.cmp(host2)));
+    hosts.into_iter().take(3).map(|(host, _)| host).collect()
+}
+
+/// Logs that a data quota has been exceeded, and its target throttled
+pub fn log_quota_exceeded(runtime_data: &mut RunTimeData, quota: &DataQuota) {
+    if runtime_data.logged_notifications.len() >= 30 {
+        runtime_data.logged_notifications.pop_back();
+    }
+    runtime_data
+        .logged_notifications
+        .push_front(LoggedNotification::QuotaExceeded(QuotaExceeded {
+            target: quota.target.clone(),
+            limit_bytes: quota.limit_bytes,
+            period: quota.period,
+            kbps: quota.kbps,
+            timestamp: Local::now().to_string().get(11..19).unwrap().to_string(),
+        }));
+}
+
+/// Logs that a step needed to apply, remove or save the throttling rules failed,
+/// together with its cause
+pub fn log_throttling_failed(runtime_data: &mut RunTimeData, step: &str, cause: String) {
+    if runtime_data.logged_notifications.len() >= 30 {
+        runtime_data.logged_notifications.pop_back();
+    }
+    runtime_data
+        .logged_notifications
+        .push_front(LoggedNotification::ThrottlingFailed(ThrottlingFailed {
+            step: step.to_string(),
+            cause,
+            timestamp: Local::now().to_string().get(11..19).unwrap().to_string(),
+        }));
+}
+
+/// Logs that the packets of an adapter couldn't be decoded or dumped, together with the cause
+pub fn log_capture_failed(runtime_data: &mut RunTimeData, device: String, cause: String) {
+    if runtime_data.logged_notifications.len() >= 30 {
+        runtime_data.logged_notifications.pop_back();
+    }
+    runtime_data
+        .logged_notifications
+        .push_front(LoggedNotification::CaptureFailed(CaptureFailed {
+            device,
+            cause,
+            timestamp: Local::now().to_string().get(11..19).unwrap().to_string(),
+        }));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    use chrono::Local;
+
+    use crate::configs::types::data_quota::{DataQuota, QuotaPeriod, QuotaTarget, QuotaUsage};
+    use crate::networking::types::address_port_pair::AddressPortPair;
+    use crate::networking::types::asn::Asn;
+    use crate::networking::types::host::Host;
+    use crate::networking::types::info_address_port_pair::InfoAddressPortPair;
+    use crate::networking::types::process_info::ProcessInfo;
+    use crate::networking::types::traffic_control_error::TrafficControlError;
+    use crate::networking::types::traffic_direction::TrafficDirection;
+    use crate::notifications::notify_and_log::{
+        log_capture_failed, log_quota_exceeded, log_throttling_failed, notify_and_log,
+    };
+    use crate::notifications::types::logged_notification::LoggedNotification;
+    use crate::notifications::types::notifications::{Notifications, ProcessNotification};
+    use crate::notifications::types::sound::Sound;
+    use crate::{ChartType, InfoTraffic, RunTimeData, TransProtocol};
+
+    fn set_connection_traffic(
+        info_traffic: &Arc<Mutex<InfoTraffic>>,
+        remote_address: &str,
+        pid: u32,
+        packets: u128,
+    ) {
+        let key = AddressPortPair::new(
+            "192.168.1.2".to_string(),
+            40000 + u16::try_from(pid).unwrap(),
+            remote_address.to_string(),
+            443,
+            TransProtocol::TCP,
+            "eth0".to_string(),
+        );
+        info_traffic.lock().unwrap().map.insert(
+            key,
+            InfoAddressPortPair {
+                transmitted_packets: packets,
+                transmitted_bytes: packets * 100,
+                traffic_direction: TrafficDirection::Outgoing,
+                pids: Some(vec![pid]),
+                ..InfoAddressPortPair::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_process_threshold_exceeded() {
+        let info_traffic = Arc::new(Mutex::new(InfoTraffic::new()));
+        let mut info_traffic_lock = info_traffic.lock().unwrap();
+        for (pid, name, uid, username) in [
+            (10, "firefox", 1000, Some("alice".to_string())),
+            (11, "firefox", 1001, None),
+            (20, "curl", 1000, Some("alice".to_string())),
+        ] {
+            info_traffic_lock.processes.insert(
+                pid,
+                ProcessInfo {
+                    pid,
+                    name: name.to_string(),
+                    uid,
+                    username,
+                    ..ProcessInfo::default()
+                },
+            );
+        }
+        info_traffic_lock.addresses_resolved.insert(
+            "93.184.216.34".to_string(),
+            (
+                "example.com".to_string(),
+                Host {
+                    domain: "example.com".to_string(),
+                    asn: Asn::default(),
+                    country: Default::default(),
+                },
+            ),
+        );
+        drop(info_traffic_lock);
+        let notifications = Notifications {
+            process_notification: ProcessNotification {
+                threshold: Some(100),
+                threshold_type: ChartType::Packets,
+                sound: Sound::None,
+                previous_threshold: 100,
+            },
+            ..Notifications::default()
+        };
+        let mut runtime_data = RunTimeData::new();
+
+        // neither of the firefox processes exceeds the threshold, but together they do
+        set_connection_traffic(&info_traffic, "93.184.216.34", 10, 60);
+        set_connection_traffic(&info_traffic, "151.101.1.69", 11, 50);
+        set_connection_traffic(&info_traffic, "151.101.1.69", 20, 30);
+        assert_eq!(
+            notify_and_log(
+                &mut runtime_data,
+                notifications,
+                &info_traffic,
+                &HashSet::new(),
+                Local::now()
+            ),
+            1
+        );
+        let Some(LoggedNotification::ProcessThresholdExceeded(firefox)) =
+            runtime_data.logged_notifications.front()
+        else {
+            panic!("the process notification wasn't logged");
+        };
+        assert_eq!(firefox.name, "firefox");
+        assert_eq!(firefox.pids, vec![10, 11]);
+        assert_eq!(firefox.users, vec!["alice", "1001"]);
+        assert_eq!(firefox.value, 110);
+        assert_eq!(firefox.top_hosts, vec!["example.com", "151.101.1.69"]);
+
+        // only the traffic of the last interval is evaluated
+        set_connection_traffic(&info_traffic, "151.101.1.69", 20, 180);
+        assert_eq!(
+            notify_and_log(
+                &mut runtime_data,
+                notifications,
+                &info_traffic,
+                &HashSet::new(),
+                Local::now()
+            ),
+            1
+        );
+        let Some(LoggedNotification::ProcessThresholdExceeded(curl)) =
+            runtime_data.logged_notifications.front()
+        else {
+            panic!("the process notification wasn't logged");
+        };
+        assert_eq!(curl.name, "curl");
+        assert_eq!(curl.pids, vec![20]);
+        assert_eq!(curl.value, 150);
+        assert_eq!(curl.top_hosts, vec!["151.101.1.69"]);
+        assert_eq!(
+            notify_and_log(
+                &mut runtime_data,
+                notifications,
+                &info_traffic,
+                &HashSet::new(),
+                Local::now()
+            ),
+            0
+        );
+    }
+    #[test]
+    fn test_throttling_failure_is_logged() {
+        let mut runtime_data = RunTimeData::new();
+        let error = TrafficControlError::Netlink {
+            step: "throttle the ingress of user 1000".to_string(),
+            error: io::Error::new(io::ErrorKind::NotFound, "Specified class not found"),
+        };
+        log_throttling_failed(&mut runtime_data, error.get_step(), error.get_cause());
+        let Some(LoggedNotification::ThrottlingFailed(failure)) =
+            runtime_data.logged_notifications.front()
+        else {
+            panic!("the throttling failure wasn't logged");
+        };
+        assert_eq!(failure.step, "throttle the ingress of user 1000");
+        assert_eq!(
+            failure.cause,
+            "netlink request failed (Specified class not found)"
+        );
+    }
+
+    #[test]
+    fn test_capture_failure_is_logged() {
+        let mut runtime_data = RunTimeData::new();
+        log_capture_failed(
+            &mut runtime_data,
+            "eth0".to_string(),
+            "couldn't write to the pcap savefile: packets dump stopped".to_string(),
+        );
+        let Some(LoggedNotification::CaptureFailed(failure)) =
+            runtime_data.logged_notifications.front()
+        else {
+            panic!("the capture failure wasn't logged");
+        };
+        assert_eq!(failure.device, "eth0");
+        assert_eq!(
+            failure.cause,
+            "couldn't write to the pcap savefile: packets dump stopped"
+        );
+    }
+
+    #[test]
+    fn test_exceeded_quota_is_logged() {
+        let mut runtime_data = RunTimeData::new();
+        let quota = DataQuota {
+            limit_bytes: 1000,
+            period: QuotaPeriod::Daily,
+            kbps: 10,
+            target: QuotaTarget::User(1000),
+            usage: QuotaUsage::default(),
+            last_total: None,
+        };
+        log_quota_exceeded(&mut runtime_data, &quota);
+        let Some(LoggedNotification::QuotaExceeded(exceeded)) =
+            runtime_data.logged_notifications.front()
+        else {
+            panic!("the exceeded quota wasn't logged");
+        };
+        assert_eq!(exceeded.target, QuotaTarget::User(1000));
+        assert_eq!(exceeded.limit_bytes, 1000);
+        assert_eq!(exceeded.period, QuotaPeriod::Daily);
+        assert_eq!(exceeded.kbps, 10);
+    }
+}
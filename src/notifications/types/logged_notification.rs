@@ -1,5 +1,7 @@
+use crate::configs::types::data_quota::{QuotaPeriod, QuotaTarget};
 use crate::networking::types::data_info_host::DataInfoHost;
 use crate::networking::types::host::Host;
+use crate::ChartType;
 
 /// Enum representing the possible notification events.
 pub enum LoggedNotification {
@@ -9,6 +11,14 @@ pub enum LoggedNotification {
     BytesThresholdExceeded(BytesThresholdExceeded),
     /// Favorite connection exchanged data
     FavoriteTransmitted(FavoriteTransmitted),
+    /// Process threshold exceeded
+    ProcessThresholdExceeded(ProcessThresholdExceeded),
+    /// Data quota exceeded, and its target throttled
+    QuotaExceeded(QuotaExceeded),
+    /// A throttling rule couldn't be applied or removed
+    ThrottlingFailed(ThrottlingFailed),
+    /// The packets of an adapter couldn't be decoded or dumped
+    CaptureFailed(CaptureFailed),
 }
 
 #[derive(Clone)]
@@ -33,3 +43,46 @@ pub struct FavoriteTransmitted {
     pub(crate) data_info_host: DataInfoHost,
     pub(crate) timestamp: String,
 }
+
+#[derive(Clone)]
+pub struct ProcessThresholdExceeded {
+    /// Name of the process; its PID, if the name is unknown
+    pub(crate) name: String,
+    pub(crate) pids: Vec<u32>,
+    /// Names (or IDs) of the users running the process
+    pub(crate) users: Vec<String>,
+    pub(crate) threshold: u32,
+    pub(crate) threshold_type: ChartType,
+    /// Packets or bytes exchanged by the process in the last interval
+    pub(crate) value: u128,
+    /// Remote hosts which exchanged the most bytes with the process
+    pub(crate) top_hosts: Vec<String>,
+    pub(crate) timestamp: String,
+}
+
+#[derive(Clone)]
+pub struct QuotaExceeded {
+    pub(crate) target: QuotaTarget,
+    pub(crate) limit_bytes: u64,
+    pub(crate) period: QuotaPeriod,
+    pub(crate) kbps: u32,
+    pub(crate) timestamp: String,
+}
+
+#[derive(Clone)]
+pub struct ThrottlingFailed {
+    /// Description of the step that couldn't be completed
+    pub(crate) step: String,
+    /// Program that couldn't be run or that failed with its error output,
+    /// or cgroup file that couldn't be updated
+    pub(crate) cause: String,
+    pub(crate) timestamp: String,
+}
+
+#[derive(Clone)]
+pub struct CaptureFailed {
+    /// Name of the adapter whose packets couldn't be decoded or dumped
+    pub(crate) device: String,
+    pub(crate) cause: String,
+    pub(crate) timestamp: String,
+}
@@ -2,7 +2,7 @@
 
 use crate::networking::types::byte_multiple::from_char_to_multiple;
 use crate::notifications::types::sound::Sound;
-use crate::ByteMultiple;
+use crate::{ByteMultiple, ChartType};
 
 /// Used to contain the notifications configuration set by the user
 #[derive(Clone, Serialize, Deserialize, Copy)]
@@ -178,8 +178,12 @@ pub fn off(sound: Sound) -> Self {
 
 #[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Copy)]
 pub struct ProcessNotification {
-    /// Threshold of received + sent bytes; if exceeded a notification is emitted
+    /// Threshold of received + sent packets or bytes of a single process; if exceeded
+    /// a notification is emitted
     pub threshold: Option<u32>,
+    /// Whether the threshold is expressed in packets or bytes
+    #[serde(default)]
+    pub threshold_type: ChartType,
     /// The sound to emit
     pub sound: Sound,
     /// The last used Some value for the threshold field
@@ -190,6 +194,7 @@ impl Default for ProcessNotification {
     fn default() -> Self {
         ProcessNotification {
             threshold: None,
+            threshold_type: ChartType::Packets,
             sound: Sound::Gulp,
             previous_threshold: 750,
         }
@@ -0,0 +1,343 @@
+//! Module containing functions to export the connections table in machine-readable formats.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+use crate::networking::manage_packets::get_address_to_lookup;
+use crate::report::types::export_format::ExportFormat;
+use crate::InfoTraffic;
+
+/// Columns of the exported CSV file, in the same order of the `ExportedConnection` fields
+const CSV_HEADER: [&str; 23] = [
+    "src_address",
+    "src_port",
+    "dst_address",
+    "dst_port",
+    "transport_protocol",
+    "application_protocol",
+    "traffic_direction",
+    "packets",
+    "bytes",
+    "initial_timestamp",
+    "final_timestamp",
+    "rdns",
+    "domain",
+    "asn_number",
+    "asn_name",
+    "country",
+    "pids",
+    "process_name",
+    "uid",
+    "icmp_type",
+    "icmp_code",
+    "icmp_id",
+    "interface",
+];
+
+/// A row of the connections table, enriched with the information about the remote host
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ExportedConnection {
+    pub src_address: String,
+    /// Source port; `None` for ICMP and ICMPv6
+    pub src_port: Option<u16>,
+    pub dst_address: String,
+    /// Destination port; `None` for ICMP and ICMPv6
+    pub dst_port: Option<u16>,
+    pub transport_protocol: String,
+    pub application_protocol: String,
+    pub traffic_direction: String,
+    pub packets: u128,
+    pub bytes: u128,
+    pub initial_timestamp: String,
+    pub final_timestamp: String,
+    /// Full reverse DNS of the remote address; empty if not resolved yet
+    pub rdns: String,
+    pub domain: String,
+    pub asn_number: u32,
+    pub asn_name: String,
+    pub country: String,
+    pub pids: Vec<u32>,
+    /// Names of the local processes owning the connection; empty if unknown
+    pub process_name: String,
+    pub uid: Option<u32>,
+    pub icmp_type: Option<u8>,
+    pub icmp_code: Option<u8>,
+    /// Identifier of ICMP and ICMPv6 echo messages (zero for the other messages)
+    pub icmp_id: Option<u16>,
+    /// Network adapter on which the connection was observed
+    pub interface: String,
+}
+
+impl ExportedConnection {
+    fn to_csv_record(&self) -> String {
+        let pids = self
+            .pids
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join(" ");
+        [
+            self.src_address.clone(),
+            optional_field(self.src_port),
+            self.dst_address.clone(),
+            optional_field(self.dst_port),
+            self.transport_protocol.clone(),
+            self.application_protocol.clone(),
+            self.traffic_direction.clone(),
+            self.packets.to_string(),
+            self.bytes.to_string(),
+            self.initial_timestamp.clone(),
+            self.final_timestamp.clone(),
+            self.rdns.clone(),
+            self.domain.clone(),
+            self.asn_number.to_string(),
+            self.asn_name.clone(),
+            self.country.clone(),
+            pids,
+            self.process_name.clone(),
+            optional_field(self.uid),
+            optional_field(self.icmp_type),
+            optional_field(self.icmp_code),
+            optional_field(self.icmp_id),
+            self.interface.clone(),
+        ]
+        .iter()
+        .map(|field| escape_csv_field(field))
+        .collect::<Vec<String>>()
+        .join(",")
+    }
+}
+
+/// Returns all the connections observed so far, in order of appearance
+pub fn get_exported_connections(info_traffic: &InfoTraffic) -> Vec<ExportedConnection> {
+    info_traffic
+        .map
+        .iter()
+        .map(|(key, val)| {
+            let address_to_lookup = get_address_to_lookup(key, val.traffic_direction);
+            let (rdns, host) = info_traffic
+                .addresses_resolved
+                .get(&address_to_lookup)
+                .cloned()
+                .unwrap_or_default();
+            let ports = key.get_ports();
+            let icmp_type_code = key.get_icmp_type_code();
+            ExportedConnection {
+                src_address: key.address1.clone(),
+                src_port: ports.map(|(port1, _)| port1),
+                dst_address: key.address2.clone(),
+                dst_port: ports.map(|(_, port2)| port2),
+                transport_protocol: format!("{:?}", key.trans_protocol),
+                application_protocol: format!("{:?}", val.app_protocol),
+                traffic_direction: format!("{:?}", val.traffic_direction),
+                packets: val.transmitted_packets,
+                bytes: val.transmitted_bytes,
+                initial_timestamp: val.initial_timestamp.to_rfc3339(),
+                final_timestamp: val.final_timestamp.to_rfc3339(),
+                rdns,
+                domain: host.domain,
+                asn_number: host.asn.number,
+                asn_name: host.asn.name,
+                country: host.country.to_string(),
+                pids: val.pids.clone().unwrap_or_default(),
+                process_name: val.process_name.clone().unwrap_or_default(),
+                uid: val.uid,
+                icmp_type: icmp_type_code.map(|(icmp_type, _)| icmp_type),
+                icmp_code: icmp_type_code.map(|(_, code)| code),
+                icmp_id: key.get_icmp_identifier(),
+                interface: key.interface.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Writes the connections table to the file at the given path, in the specified format
+pub fn export_connections(
+    info_traffic: &Arc<Mutex<InfoTraffic>>,
+    format: ExportFormat,
+    path: &Path,
+) -> Result<(), String> {
+    let info_traffic_lock = info_traffic.lock().unwrap();
+    let connections = get_exported_connections(&info_traffic_lock);
+    drop(info_traffic_lock);
+
+    let file = File::create(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    let mut output = BufWriter::new(file);
+    match format {
+        ExportFormat::Csv => write_csv(&connections, &mut output),
+        ExportFormat::Json => write_json(&connections, &mut output),
+    }
+    .and_then(|()| output.flush())
+    .map_err(|err| format!("{}: {err}", path.display()))
+}
+
+fn write_csv(connections: &[ExportedConnection], output: &mut impl Write) -> std::io::Result<()> {
+    writeln!(output, "{}", CSV_HEADER.join(","))?;
+    for connection in connections {
+        writeln!(output, "{}", connection.to_csv_record())?;
+    }
+    Ok(())
+}
+
+fn write_json(connections: &[ExportedConnection], output: &mut impl Write) -> std::io::Result<()> {
+    for connection in connections {
+        serde_json::to_writer(&mut *output, connection)?;
+        writeln!(output)?;
+    }
+    Ok(())
+}
+
+/// Formats an optional CSV field, leaving it empty if missing
+fn optional_field(field: Option<impl ToString>) -> String {
+    field.map(|field| field.to_string()).unwrap_or_default()
+}
+
+/// Quotes a CSV field if it contains separators, quotes, or line breaks
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Local, TimeZone};
+
+    use crate::countries::types::country::Country;
+    use crate::networking::types::address_port_pair::AddressPortPair;
+    use crate::networking::types::asn::Asn;
+    use crate::networking::types::host::Host;
+    use crate::networking::types::info_address_port_pair::InfoAddressPortPair;
+    use crate::networking::types::traffic_direction::TrafficDirection;
+    use crate::report::export_connections::{
+        escape_csv_field, get_exported_connections, write_csv, write_json, CSV_HEADER,
+    };
+    use crate::{AppProtocol, InfoTraffic, TransProtocol};
+
+    fn get_info_traffic() -> InfoTraffic {
+        let mut info_traffic = InfoTraffic::new();
+        let timestamp = Local.timestamp_opt(1_700_000_000, 0).unwrap();
+        info_traffic.map.insert(
+            AddressPortPair::new(
+                "192.168.1.10".to_string(),
+                50000,
+                "1.1.1.1".to_string(),
+                443,
+                TransProtocol::TCP,
+                "eth0".to_string(),
+            ),
+            InfoAddressPortPair {
+                transmitted_bytes: 1500,
+                transmitted_packets: 3,
+                initial_timestamp: timestamp,
+                final_timestamp: timestamp,
+                app_protocol: AppProtocol::HTTPS,
+                traffic_direction: TrafficDirection::Outgoing,
+                pids: Some(vec![42, 43]),
+                process_name: Some("curl".to_string()),
+                uid: Some(1000),
+                ..InfoAddressPortPair::default()
+            },
+        );
+        info_traffic.addresses_resolved.insert(
+            "1.1.1.1".to_string(),
+            (
+                "one.one.one.one".to_string(),
+                Host {
+                    domain: "one.one".to_string(),
+                    asn: Asn {
+                        number: 13335,
+                        name: "CLOUDFLARENET, US".to_string(),
+                    },
+                    country: Country::US,
+                },
+            ),
+        );
+        info_traffic
+    }
+
+    #[test]
+    fn test_exported_connections() {
+        let connections = get_exported_connections(&get_info_traffic());
+        assert_eq!(connections.len(), 1);
+        let connection = &connections[0];
+        assert_eq!(connection.dst_address, "1.1.1.1");
+        assert_eq!(connection.transport_protocol, "TCP");
+        assert_eq!(connection.application_protocol, "HTTPS");
+        assert_eq!(connection.rdns, "one.one.one.one");
+        assert_eq!(connection.asn_number, 13335);
+        assert_eq!(connection.country, "US");
+        assert_eq!(connection.pids, vec![42, 43]);
+        assert_eq!(connection.uid, Some(1000));
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let connections = get_exported_connections(&get_info_traffic());
+        let mut output = Vec::new();
+        write_csv(&connections, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], CSV_HEADER.join(","));
+        assert!(lines[1].starts_with("192.168.1.10,50000,1.1.1.1,443,TCP,HTTPS,Outgoing,3,1500,"));
+        assert!(lines[1].ends_with(
+            ",one.one.one.one,one.one,13335,\"CLOUDFLARENET, US\",US,42 43,curl,1000,,,,eth0"
+        ));
+    }
+
+    #[test]
+    fn test_write_json() {
+        let connections = get_exported_connections(&get_info_traffic());
+        let mut output = Vec::new();
+        write_json(&connections, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        let value: serde_json::Value = serde_json::from_str(output.trim_end()).unwrap();
+        assert_eq!(value["dst_port"], 443);
+        assert_eq!(value["asn_name"], "CLOUDFLARENET, US");
+        assert_eq!(value["pids"], serde_json::json!([42, 43]));
+        assert_eq!(value["process_name"], "curl");
+        assert_eq!(value["uid"], 1000);
+    }
+
+    #[test]
+    fn test_exported_icmp_message() {
+        let mut info_traffic = InfoTraffic::new();
+        info_traffic.map.insert(
+            AddressPortPair::new(
+                "192.168.1.10".to_string(),
+                0x0800,
+                "1.1.1.1".to_string(),
+                4660,
+                TransProtocol::ICMP,
+                "eth0".to_string(),
+            ),
+            InfoAddressPortPair::default(),
+        );
+        let connections = get_exported_connections(&info_traffic);
+        let connection = &connections[0];
+        assert_eq!(connection.src_port, None);
+        assert_eq!(connection.dst_port, None);
+        assert_eq!(connection.icmp_type, Some(8));
+        assert_eq!(connection.icmp_code, Some(0));
+        assert_eq!(connection.icmp_id, Some(4660));
+        assert!(connection
+            .to_csv_record()
+            .starts_with("192.168.1.10,,1.1.1.1,,ICMP,"));
+        assert!(connection.to_csv_record().ends_with(",8,0,4660,eth0"));
+    }
+
+    #[test]
+    fn test_escape_csv_field() {
+        assert_eq!(escape_csv_field("plain"), "plain");
+        assert_eq!(escape_csv_field("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -1,4 +1,5 @@
 use std::cmp::{min, Ordering};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use iced::widget::Tooltip;
@@ -14,6 +15,7 @@
 use crate::networking::types::data_info_host::DataInfoHost;
 use crate::networking::types::host::Host;
 use crate::networking::types::info_address_port_pair::InfoAddressPortPair;
+use crate::report::types::user_summary::UserSummary;
 use crate::{AppProtocol, ChartType, InfoTraffic, ReportSortType, Sniffer};
 
 use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo};
@@ -52,6 +54,12 @@ pub fn get_searched_entries(
             {
                 return false;
             }
+            // check network adapter filter
+            let searched_interface = &*sniffer.search.interface.to_lowercase();
+            if !searched_interface.is_empty() && key.interface.to_lowercase().ne(searched_interface)
+            {
+                return false;
+            }
             // check application protocol filter
             let searched_app = &*sniffer.search.app.to_lowercase();
             let app = format!("{:?}", value.app_protocol).to_lowercase();
@@ -178,3 +186,55 @@ pub fn get_app_entries(
 
     sorted_vec.iter().map(|e| (*e.0, *e.1)).collect()
 }
+
+/// Returns the traffic aggregated by process ID together with the process name (if known),
+/// sorted by the given chart type;
+/// connections not associated to any process are aggregated under PID 0
+pub fn get_pid_entries(
+    info_traffic: &Arc<Mutex<InfoTraffic>>,
+    chart_type: ChartType,
+) -> Vec<(u32, Option<String>, DataInfo)> {
+    let info_traffic_lock = info_traffic.lock().unwrap();
+    let mut pid_stats: HashMap<u32, (Option<String>, DataInfo)> = HashMap::new();
+    for info in info_traffic_lock.map.values() {
+        let pids = info.pids.clone().unwrap_or_else(|| vec![0]);
+        for pid in pids {
+            let (process_name, data_info) = pid_stats.entry(pid).or_default();
+            if process_name.is_none() {
+                process_name.clone_from(&info.process_name);
+            }
+            data_info.add_packets(
+                info.transmitted_packets,
+                info.transmitted_bytes,
+                info.traffic_direction,
+            );
+        }
+    }
+    drop(info_traffic_lock);
+
+    let mut sorted_vec: Vec<(u32, Option<String>, DataInfo)> = pid_stats
+        .into_iter()
+        .map(|(pid, (process_name, data_info))| (pid, process_name, data_info))
+        .collect();
+    sorted_vec.sort_by(|(_, _, a), (_, _, b)| match chart_type {
+        ChartType::Packets => b.tot_packets().cmp(&a.tot_packets()),
+        ChartType::Bytes => b.tot_bytes().cmp(&a.tot_bytes()),
+    });
+    sorted_vec
+}
+
+/// Returns the traffic aggregated by local user, sorted by the given chart type
+pub fn get_user_entries(
+    info_traffic: &Arc<Mutex<InfoTraffic>>,
+    chart_type: ChartType,
+) -> Vec<UserSummary> {
+    let info_traffic_lock = info_traffic.lock().unwrap();
+    let mut sorted_vec = UserSummary::from_info_traffic(&info_traffic_lock);
+    drop(info_traffic_lock);
+
+    sorted_vec.sort_by(|a, b| match chart_type {
+        ChartType::Packets => b.data_info.tot_packets().cmp(&a.data_info.tot_packets()),
+        ChartType::Bytes => b.data_info.tot_bytes().cmp(&a.data_info.tot_bytes()),
+    });
+    sorted_vec
+}
@@ -1,2 +1,3 @@
+pub mod export_connections;
 pub mod get_report_entries;
 pub mod types;
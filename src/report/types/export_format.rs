@@ -0,0 +1,66 @@
+use std::fmt;
+use std::path::Path;
+
+/// Enum representing the possible formats of the connections table export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    /// Comma-separated values, with a header row
+    Csv,
+    /// Newline-delimited JSON, one object per connection
+    Json,
+}
+
+impl ExportFormat {
+    pub(crate) const ALL: [ExportFormat; 2] = [ExportFormat::Csv, ExportFormat::Json];
+
+    pub fn get_extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "jsonl",
+        }
+    }
+
+    /// Infers the export format from the extension of the given path
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "json" | "jsonl" | "ndjson" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportFormat::Csv => write!(f, "CSV"),
+            ExportFormat::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::report::types::export_format::ExportFormat;
+
+    #[test]
+    fn test_export_format_from_path() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("/tmp/connections.csv")),
+            Some(ExportFormat::Csv)
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("connections.JSON")),
+            Some(ExportFormat::Json)
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("connections.ndjson")),
+            Some(ExportFormat::Json)
+        );
+        assert_eq!(ExportFormat::from_path(Path::new("connections.txt")), None);
+        assert_eq!(ExportFormat::from_path(Path::new("connections")), None);
+    }
+}
@@ -1 +1,3 @@
+pub mod export_format;
 pub mod report_sort_type;
+pub mod user_summary;
@@ -0,0 +1,181 @@
+//! Module defining the `UserSummary` struct, which represents the traffic generated by a local user.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::networking::manage_packets::get_address_to_lookup;
+use crate::networking::types::data_info::DataInfo;
+use crate::InfoTraffic;
+
+/// Number of processes listed for each user
+const TOP_PROCESSES: usize = 3;
+
+/// Traffic aggregated by local user
+#[derive(Clone, Default)]
+pub struct UserSummary {
+    /// User ID; `None` for connections not associated to any user
+    pub uid: Option<u32>,
+    /// Name of the user, if known
+    pub username: Option<String>,
+    /// Incoming and outgoing packets and bytes
+    pub data_info: DataInfo,
+    /// Number of distinct remote addresses contacted
+    pub distinct_hosts: usize,
+    /// Names of the processes which exchanged most bytes, with their amount of bytes
+    pub top_processes: Vec<(String, u128)>,
+}
+
+impl UserSummary {
+    /// Returns the user name if known, or the numeric user ID otherwise
+    pub fn get_user_string(&self) -> String {
+        match (&self.username, self.uid) {
+            (Some(username), _) => username.clone(),
+            (None, Some(uid)) => uid.to_string(),
+            (None, None) => "Unknown".to_string(),
+        }
+    }
+
+    /// Aggregates all the connections observed so far by user, in no particular order
+    pub fn from_info_traffic(info_traffic: &InfoTraffic) -> Vec<Self> {
+        let mut users: HashMap<Option<u32>, (DataInfo, HashSet<String>, HashMap<String, u128>)> =
+            HashMap::new();
+        for (key, info) in &info_traffic.map {
+            let (data_info, hosts, processes) = users.entry(info.uid).or_default();
+            data_info.add_packets(
+                info.transmitted_packets,
+                info.transmitted_bytes,
+                info.traffic_direction,
+            );
+            hosts.insert(get_address_to_lookup(key, info.traffic_direction));
+            if let Some(process_name) = &info.process_name {
+                *processes.entry(process_name.clone()).or_default() += info.transmitted_bytes;
+            }
+        }
+
+        users
+            .into_iter()
+            .map(|(uid, (data_info, hosts, processes))| {
+                let mut top_processes: Vec<(String, u128)> = processes.into_iter().collect();
+                top_processes.sort_by(|(name_a, a), (name_b, b)| b.cmp(a).then(name_a.cmp(name_b)));
+                top_processes.truncate(TOP_PROCESSES);
+                Self {
+                    uid,
+                    username: uid.and_then(|uid| info_traffic.usernames.get(&uid).cloned()),
+                    data_info,
+                    distinct_hosts: hosts.len(),
+                    top_processes,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::networking::types::address_port_pair::AddressPortPair;
+    use crate::networking::types::info_address_port_pair::InfoAddressPortPair;
+    use crate::networking::types::traffic_direction::TrafficDirection;
+    use crate::report::types::user_summary::UserSummary;
+    use crate::{InfoTraffic, TransProtocol};
+
+    fn insert_connection(
+        info_traffic: &mut InfoTraffic,
+        remote: (&str, u16),
+        bytes: u128,
+        traffic_direction: TrafficDirection,
+        owner: Option<(u32, &str)>,
+    ) {
+        let local = ("192.168.1.10".to_string(), 50000 + remote.1);
+        let remote = (remote.0.to_string(), remote.1);
+        let (source, destination) = if traffic_direction == TrafficDirection::Outgoing {
+            (local, remote)
+        } else {
+            (remote, local)
+        };
+        info_traffic.map.insert(
+            AddressPortPair::new(
+                source.0,
+                source.1,
+                destination.0,
+                destination.1,
+                TransProtocol::TCP,
+                "eth0".to_string(),
+            ),
+            InfoAddressPortPair {
+                transmitted_bytes: bytes,
+                transmitted_packets: 1,
+                traffic_direction,
+                uid: owner.map(|(uid, _)| uid),
+                pids: owner.map(|_| vec![1]),
+                process_name: owner.map(|(_, name)| name.to_string()),
+                ..InfoAddressPortPair::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_user_summaries() {
+        let mut info_traffic = InfoTraffic::new();
+        info_traffic.usernames.insert(1000, "alice".to_string());
+        let alice = Some((1000, "curl"));
+        insert_connection(
+            &mut info_traffic,
+            ("1.1.1.1", 443),
+            100,
+            TrafficDirection::Outgoing,
+            alice,
+        );
+        insert_connection(
+            &mut info_traffic,
+            ("1.1.1.1", 80),
+            300,
+            TrafficDirection::Incoming,
+            alice,
+        );
+        insert_connection(
+            &mut info_traffic,
+            ("8.8.8.8", 53),
+            50,
+            TrafficDirection::Outgoing,
+            Some((1000, "firefox")),
+        );
+        insert_connection(
+            &mut info_traffic,
+            ("9.9.9.9", 53),
+            10,
+            TrafficDirection::Outgoing,
+            Some((1001, "dig")),
+        );
+        insert_connection(
+            &mut info_traffic,
+            ("9.9.9.9", 22),
+            20,
+            TrafficDirection::Incoming,
+            None,
+        );
+
+        let mut summaries = UserSummary::from_info_traffic(&info_traffic);
+        summaries.sort_by_key(|summary| summary.uid);
+        assert_eq!(summaries.len(), 3);
+
+        let unknown = &summaries[0];
+        assert_eq!(unknown.uid, None);
+        assert_eq!(unknown.get_user_string(), "Unknown");
+        assert!(unknown.top_processes.is_empty());
+
+        let alice = &summaries[1];
+        assert_eq!(alice.get_user_string(), "alice");
+        assert_eq!(alice.data_info.outgoing_bytes, 150);
+        assert_eq!(alice.data_info.incoming_bytes, 300);
+        assert_eq!(alice.data_info.tot_packets(), 3);
+        assert_eq!(alice.distinct_hosts, 2);
+        assert_eq!(
+            alice.top_processes,
+            vec![("curl".to_string(), 400), ("firefox".to_string(), 50)]
+        );
+
+        let other = &summaries[2];
+        assert_eq!(other.username, None);
+        assert_eq!(other.get_user_string(), "1001");
+        assert_eq!(other.distinct_hosts, 1);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod check_updates;
 pub mod parse_packets;
+pub mod refresh_socket_cache;
 pub mod write_report_file;
@@ -4,32 +4,50 @@
 use std::sync::{Arc, Mutex};
 use std::thread;
 
-use etherparse::PacketHeaders;
-use pcap::{Active, Capture};
+use pcap::{Activated, Capture};
 
 use crate::countries::country_utils::COUNTRY_MMDB;
 use crate::networking::manage_packets::{
     analyze_link_header, analyze_network_header, analyze_transport_header, get_address_to_lookup,
-    modify_or_insert_in_map, reverse_dns_lookup,
+    get_packet_timestamp, modify_or_insert_in_map, reverse_dns_lookup,
 };
 use crate::networking::types::address_port_pair::AddressPortPair;
+use crate::networking::types::capture_source::CaptureSource;
 use crate::networking::types::data_info::DataInfo;
 use crate::networking::types::filters::Filters;
 use crate::networking::types::info_address_port_pair::InfoAddressPortPair;
+use crate::networking::types::link_type::LinkType;
 use crate::networking::types::my_device::MyDevice;
+use crate::networking::types::replay_interval::close_replay_seconds;
+use crate::networking::types::rotating_savefile::RotatingSavefile;
+use crate::networking::types::socket_cache::SocketCache;
 use crate::networking::types::traffic_direction::TrafficDirection;
-use crate::networking::manage_packets::get_pid;
-use crate::networking::manage_packets::get_uid;
 use crate::utils::asn::ASN_MMDB;
 use crate::{AppProtocol, InfoTraffic, IpVersion, TransProtocol};
 
 /// The calling thread enters in a loop in which it waits for network packets, parses them according
 /// to the user specified filters, and inserts them into the shared map variable.
+///
+/// When the packets are read from a file, they are parsed at full speed and the traffic totals
+/// are recorded at the end of each second of the file, so that charts and notifications are
+/// bucketed by packet timestamp; the loop ends as soon as the file has been entirely parsed.
+///
+/// If a savefile is provided, the packets satisfying the filters are also dumped into it.
+///
+/// The packets are attributed to the local processes through the given socket cache, shared by
+/// the captures of all the adapters; it's not provided when the packets are read from a file.
+///
+/// The failures occurred while capturing are added to the shared traffic data,
+/// to be reported to the user.
+#[allow(clippy::too_many_arguments)]
 pub fn parse_packets(
     current_capture_id: &Arc<Mutex<u16>>,
     device: &MyDevice,
-    mut cap: Capture<Active>,
+    mut cap: Capture<dyn Activated>,
+    capture_source: &CaptureSource,
     filters: &Filters,
+    mut savefile: Option<RotatingSavefile>,
+    socket_cache: Option<&Arc<Mutex<SocketCache>>>,
     info_traffic_mutex: &Arc<Mutex<InfoTraffic>>,
 ) {
     let capture_id = *current_capture_id.lock().unwrap();
@@ -49,12 +67,34 @@ pub fn parse_packets(
     let mut application_protocol;
     let mut skip_packet;
     let mut reported_packet;
+    // packets dropped by this capture, which may run alongside the ones of other adapters
+    let mut dropped_packets = 0;
+
+    let link_type = LinkType::from_datalink(cap.get_datalink());
+    if let LinkType::Unsupported(datalink) = link_type {
+        info_traffic_mutex.lock().unwrap().capture_failures.push((
+            device.name.clone(),
+            format!("unsupported link type {datalink}: packets can't be decoded"),
+        ));
+    }
 
     let country_db_reader = Arc::new(maxminddb::Reader::from_source(COUNTRY_MMDB).unwrap());
     let asn_db_reader = Arc::new(maxminddb::Reader::from_source(ASN_MMDB).unwrap());
 
+    // second of an offline capture the parsed packets belong to
+    let mut replay_second = None;
+
     loop {
         match cap.next_packet() {
+            Err(pcap::Error::NoMorePackets) => {
+                // the whole file has been parsed
+                close_replay_seconds(
+                    &mut info_traffic_mutex.lock().unwrap(),
+                    &mut replay_second,
+                    None,
+                );
+                return;
+            }
             Err(_) => {
                 if *current_capture_id.lock().unwrap() != capture_id {
                     return;
@@ -65,11 +105,23 @@ pub fn parse_packets(
                 if *current_capture_id.lock().unwrap() != capture_id {
                     return;
                 }
-                match PacketHeaders::from_ethernet_slice(&packet) {
-                    Err(_) => {
+                #[allow(clippy::useless_conversion)]
+                let timestamp = get_packet_timestamp(
+                    i64::from(packet.header.ts.tv_sec),
+                    i64::from(packet.header.ts.tv_usec),
+                );
+                if capture_source.is_offline() {
+                    close_replay_seconds(
+                        &mut info_traffic_mutex.lock().unwrap(),
+                        &mut replay_second,
+                        Some(timestamp),
+                    );
+                }
+                match link_type.get_packet_headers(&packet) {
+                    None => {
                         continue;
                     }
-                    Ok(value) => {
+                    Some(value) => {
                         let mut mac_address1 = String::new();
                         let mut mac_address2 = String::new();
                         let mut address1 = String::new();
@@ -81,6 +133,8 @@ pub fn parse_packets(
                         reported_packet = false;
 
                         analyze_link_header(
+                            link_type,
+                            &packet,
                             value.link,
                             &mut mac_address1,
                             &mut mac_address2,
@@ -121,6 +175,7 @@ pub fn parse_packets(
                             address2.clone(),
                             port2,
                             transport_protocol,
+                            device.name.clone(),
                         );
 
                         let mut new_info = InfoAddressPortPair::default();
@@ -139,6 +194,8 @@ pub fn parse_packets(
                                 (mac_address1, mac_address2),
                                 exchanged_bytes,
                                 application_protocol,
+                                timestamp,
+                                socket_cache,
                                 pid_filter.clone(),
                                 uid_filter.clone(),
                                 port_filter.clone(),
@@ -146,6 +203,19 @@ pub fn parse_packets(
                             );
                             reported_packet = !filter_packet;
                         }
+                        // dump the packet before discarding its raw content
+                        if reported_packet
+                            && savefile
+                                .as_mut()
+                                .is_some_and(|savefile| savefile.write(&packet).is_err())
+                        {
+                            info_traffic_mutex.lock().unwrap().capture_failures.push((
+                                device.name.clone(),
+                                "couldn't write to the pcap savefile: packets dump stopped"
+                                    .to_string(),
+                            ));
+                            savefile = None;
+                        }
                         let mut info_traffic = info_traffic_mutex
                             .lock()
                             .expect("Error acquiring mutex\n\r");
@@ -154,7 +224,9 @@ pub fn parse_packets(
                         info_traffic.all_bytes += exchanged_bytes;
                         // update dropped packets number
                         if let Ok(stats) = cap.stats() {
-                            info_traffic.dropped_packets = stats.dropped;
+                            info_traffic.dropped_packets +=
+                                stats.dropped.saturating_sub(dropped_packets);
+                            dropped_packets = stats.dropped;
                         }
 
                         if reported_packet {
@@ -0,0 +1,47 @@
+//! Module containing functions executed by the thread in charge of keeping the
+//! local sockets and their owner processes up to date
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::networking::types::socket_cache::{read_local_sockets, SocketCache};
+
+/// Time between two consecutive refreshes of the socket cache
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Returns a socket cache filled with the current local sockets, and spawns the thread keeping
+/// it up to date until a new capture is started; a single cache is shared by the captures
+/// of all the adapters of a session
+pub fn start_socket_cache(current_capture_id: &Arc<Mutex<u16>>) -> Arc<Mutex<SocketCache>> {
+    let capture_id = *current_capture_id.lock().unwrap();
+    let socket_cache = Arc::new(Mutex::new(SocketCache::default()));
+    socket_cache.lock().unwrap().update(read_local_sockets());
+    let current_capture_id2 = current_capture_id.clone();
+    let socket_cache2 = socket_cache.clone();
+    thread::Builder::new()
+        .name("thread_refresh_socket_cache".to_string())
+        .spawn(move || {
+            refresh_socket_cache_loop(&current_capture_id2, capture_id, &socket_cache2);
+        })
+        .unwrap();
+    socket_cache
+}
+
+/// The calling thread enters in a loop in which it periodically reads the local sockets
+/// and updates the shared cache, until a new capture is started
+fn refresh_socket_cache_loop(
+    current_capture_id: &Arc<Mutex<u16>>,
+    capture_id: u16,
+    socket_cache: &Arc<Mutex<SocketCache>>,
+) {
+    loop {
+        thread::sleep(REFRESH_INTERVAL);
+        if *current_capture_id.lock().unwrap() != capture_id {
+            return;
+        }
+        // read the sockets without holding the lock, since it takes some time
+        let sockets = read_local_sockets();
+        socket_cache.lock().unwrap().update(sockets);
+    }
+}
@@ -45,6 +45,17 @@ pub fn inspect_translation(language: Language) -> &'static str {
     }
 }
 
+pub fn throttling_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Throttling",
+        Language::IT => "Limitazione",
+        Language::FR => "Limitation",
+        Language::ES => "Limitación",
+        Language::DE => "Drosselung",
+        _ => "Throttling",
+    }
+}
+
 pub fn connection_details_translation(language: Language) -> &'static str {
     match language {
         Language::EN => "Connection details",
@@ -535,3 +546,14 @@ pub fn color_gradients_translation(language: Language) -> &'static str {
         _ => "Apply color gradients",
     }
 }
+
+pub fn limit_host_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Limit this host",
+        Language::IT => "Limita questo host",
+        Language::FR => "Limiter cet hôte",
+        Language::ES => "Limitar este host",
+        Language::DE => "Diesen Host drosseln",
+        _ => "Limit this host",
+    }
+}
@@ -0,0 +1,427 @@
+#![allow(clippy::match_same_arms, clippy::match_wildcard_for_single_variants)]
+
+use chrono::Weekday;
+
+use crate::configs::types::data_quota::{QuotaPeriod, QuotaTarget, QuotaTargetKind};
+use crate::Language;
+
+pub fn active_profile_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Active profile",
+        Language::IT => "Profilo attivo",
+        Language::FR => "Profil actif",
+        Language::ES => "Perfil activo",
+        Language::DE => "Aktives Profil",
+        _ => "Active profile",
+    }
+}
+
+pub fn profiles_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Profiles",
+        Language::IT => "Profili",
+        Language::FR => "Profils",
+        Language::ES => "Perfiles",
+        Language::DE => "Profile",
+        _ => "Profiles",
+    }
+}
+
+pub fn throttling_profiles_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Throttling profiles",
+        Language::IT => "Profili di limitazione",
+        Language::FR => "Profils de limitation",
+        Language::ES => "Perfiles de limitación",
+        Language::DE => "Drosselungsprofile",
+        _ => "Throttling profiles",
+    }
+}
+
+pub fn no_profiles_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "No profiles have been created",
+        Language::IT => "Nessun profilo è stato creato",
+        Language::FR => "Aucun profil n'a été créé",
+        Language::ES => "No se ha creado ningún perfil",
+        Language::DE => "Es wurden keine Profile erstellt",
+        _ => "No profiles have been created",
+    }
+}
+
+pub fn name_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Name",
+        Language::IT => "Nome",
+        Language::FR => "Nom",
+        Language::ES => "Nombre",
+        Language::DE => "Name",
+        _ => "Name",
+    }
+}
+
+pub fn every_day_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Every day",
+        Language::IT => "Ogni giorno",
+        Language::FR => "Tous les jours",
+        Language::ES => "Todos los días",
+        Language::DE => "Jeden Tag",
+        _ => "Every day",
+    }
+}
+
+pub fn weekday_translation(language: Language, weekday: Weekday) -> &'static str {
+    let days = match language {
+        Language::IT => ["Lun", "Mar", "Mer", "Gio", "Ven", "Sab", "Dom"],
+        Language::FR => ["Lun", "Mar", "Mer", "Jeu", "Ven", "Sam", "Dim"],
+        Language::ES => ["Lun", "Mar", "Mié", "Jue", "Vie", "Sáb", "Dom"],
+        Language::DE => ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+        _ => ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+    };
+    days[weekday.num_days_from_monday() as usize]
+}
+
+pub fn start_time_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Start (HH:MM)",
+        Language::IT => "Inizio (HH:MM)",
+        Language::FR => "Début (HH:MM)",
+        Language::ES => "Inicio (HH:MM)",
+        Language::DE => "Beginn (HH:MM)",
+        _ => "Start (HH:MM)",
+    }
+}
+
+pub fn end_time_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "End (HH:MM)",
+        Language::IT => "Fine (HH:MM)",
+        Language::FR => "Fin (HH:MM)",
+        Language::ES => "Fin (HH:MM)",
+        Language::DE => "Ende (HH:MM)",
+        _ => "End (HH:MM)",
+    }
+}
+
+pub fn interface_limit_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Interface limit (KB/s)",
+        Language::IT => "Limite dell'interfaccia (KB/s)",
+        Language::FR => "Limite de l'interface (KB/s)",
+        Language::ES => "Límite de la interfaz (KB/s)",
+        Language::DE => "Schnittstellenlimit (KB/s)",
+        _ => "Interface limit (KB/s)",
+    }
+}
+
+pub fn rules_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Rules",
+        Language::IT => "Regole",
+        Language::FR => "Règles",
+        Language::ES => "Reglas",
+        Language::DE => "Regeln",
+        _ => "Rules",
+    }
+}
+
+pub fn add_current_limits_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Add the current limits",
+        Language::IT => "Aggiungi i limiti attuali",
+        Language::FR => "Ajouter les limites actuelles",
+        Language::ES => "Añadir los límites actuales",
+        Language::DE => "Aktuelle Limits hinzufügen",
+        _ => "Add the current limits",
+    }
+}
+
+pub fn new_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "New",
+        Language::IT => "Nuovo",
+        Language::FR => "Nouveau",
+        Language::ES => "Nuevo",
+        Language::DE => "Neu",
+        _ => "New",
+    }
+}
+
+pub fn save_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Save",
+        Language::IT => "Salva",
+        Language::FR => "Enregistrer",
+        Language::ES => "Guardar",
+        Language::DE => "Speichern",
+        _ => "Save",
+    }
+}
+
+pub fn edit_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Edit",
+        Language::IT => "Modifica",
+        Language::FR => "Modifier",
+        Language::ES => "Editar",
+        Language::DE => "Bearbeiten",
+        _ => "Edit",
+    }
+}
+
+pub fn remove_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Remove",
+        Language::IT => "Rimuovi",
+        Language::FR => "Supprimer",
+        Language::ES => "Eliminar",
+        Language::DE => "Entfernen",
+        _ => "Remove",
+    }
+}
+
+pub fn quotas_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Quotas",
+        Language::IT => "Quote",
+        Language::FR => "Quotas",
+        Language::ES => "Cuotas",
+        Language::DE => "Kontingente",
+        _ => "Quotas",
+    }
+}
+
+pub fn data_quotas_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Data quotas",
+        Language::IT => "Quote di dati",
+        Language::FR => "Quotas de données",
+        Language::ES => "Cuotas de datos",
+        Language::DE => "Datenkontingente",
+        _ => "Data quotas",
+    }
+}
+
+pub fn no_quotas_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "No data quotas have been set",
+        Language::IT => "Nessuna quota di dati è stata impostata",
+        Language::FR => "Aucun quota de données n'a été défini",
+        Language::ES => "No se ha establecido ninguna cuota de datos",
+        Language::DE => "Es wurden keine Datenkontingente festgelegt",
+        _ => "No data quotas have been set",
+    }
+}
+
+pub fn data_quota_exceeded_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Data quota exceeded",
+        Language::IT => "Quota di dati superata",
+        Language::FR => "Quota de données dépassé",
+        Language::ES => "Cuota de datos superada",
+        Language::DE => "Datenkontingent überschritten",
+        _ => "Data quota exceeded",
+    }
+}
+
+pub fn quota_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Quota",
+        Language::IT => "Quota",
+        Language::FR => "Quota",
+        Language::ES => "Cuota",
+        Language::DE => "Kontingent",
+        _ => "Quota",
+    }
+}
+
+pub fn executable_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Executable",
+        Language::IT => "Eseguibile",
+        Language::FR => "Exécutable",
+        Language::ES => "Ejecutable",
+        Language::DE => "Programm",
+        _ => "Executable",
+    }
+}
+
+pub fn user_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "User",
+        Language::IT => "Utente",
+        Language::FR => "Utilisateur",
+        Language::ES => "Usuario",
+        Language::DE => "Benutzer",
+        _ => "User",
+    }
+}
+
+pub fn port_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Port",
+        Language::IT => "Porta",
+        Language::FR => "Port",
+        Language::ES => "Puerto",
+        Language::DE => "Port",
+        _ => "Port",
+    }
+}
+
+pub fn target_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Executable path, user ID or port",
+        Language::IT => "Percorso dell'eseguibile, ID utente o porta",
+        Language::FR => "Chemin de l'exécutable, ID utilisateur ou port",
+        Language::ES => "Ruta del ejecutable, ID de usuario o puerto",
+        Language::DE => "Programmpfad, Benutzer-ID oder Port",
+        _ => "Executable path, user ID or port",
+    }
+}
+
+pub fn quota_limit_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Limit (e.g. 500M, 2G)",
+        Language::IT => "Limite (es. 500M, 2G)",
+        Language::FR => "Limite (ex. 500M, 2G)",
+        Language::ES => "Límite (p. ej. 500M, 2G)",
+        Language::DE => "Limit (z. B. 500M, 2G)",
+        _ => "Limit (e.g. 500M, 2G)",
+    }
+}
+
+pub fn exceeded_bandwidth_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Bandwidth once exceeded (KB/s)",
+        Language::IT => "Banda una volta superata (KB/s)",
+        Language::FR => "Bande passante une fois dépassé (KB/s)",
+        Language::ES => "Ancho de banda una vez superada (KB/s)",
+        Language::DE => "Bandbreite nach Überschreitung (KB/s)",
+        _ => "Bandwidth once exceeded (KB/s)",
+    }
+}
+
+pub fn quota_target_kind_translation(language: Language, kind: QuotaTargetKind) -> &'static str {
+    match kind {
+        QuotaTargetKind::Executable => executable_translation(language),
+        QuotaTargetKind::User => user_translation(language),
+        QuotaTargetKind::Port => port_translation(language),
+    }
+}
+
+pub fn quota_target_translation(language: Language, target: &QuotaTarget) -> String {
+    match target {
+        QuotaTarget::Executable(executable) => {
+            format!("{} {executable}", executable_translation(language))
+        }
+        QuotaTarget::User(uid) => format!("{} {uid}", user_translation(language)),
+        QuotaTarget::Port(port) => format!("{} {port}", port_translation(language)),
+    }
+}
+
+pub fn quota_period_translation(language: Language, period: QuotaPeriod) -> &'static str {
+    match (language, period) {
+        (Language::IT, QuotaPeriod::Daily) => "Giornaliera",
+        (Language::IT, QuotaPeriod::Weekly) => "Settimanale",
+        (Language::IT, QuotaPeriod::Monthly) => "Mensile",
+        (Language::FR, QuotaPeriod::Daily) => "Quotidien",
+        (Language::FR, QuotaPeriod::Weekly) => "Hebdomadaire",
+        (Language::FR, QuotaPeriod::Monthly) => "Mensuel",
+        (Language::ES, QuotaPeriod::Daily) => "Diaria",
+        (Language::ES, QuotaPeriod::Weekly) => "Semanal",
+        (Language::ES, QuotaPeriod::Monthly) => "Mensual",
+        (Language::DE, QuotaPeriod::Daily) => "Täglich",
+        (Language::DE, QuotaPeriod::Weekly) => "Wöchentlich",
+        (Language::DE, QuotaPeriod::Monthly) => "Monatlich",
+        (_, QuotaPeriod::Daily) => "Daily",
+        (_, QuotaPeriod::Weekly) => "Weekly",
+        (_, QuotaPeriod::Monthly) => "Monthly",
+    }
+}
+
+pub fn per_period_translation(language: Language, period: QuotaPeriod) -> &'static str {
+    match (language, period) {
+        (Language::IT, QuotaPeriod::Daily) => "al giorno",
+        (Language::IT, QuotaPeriod::Weekly) => "a settimana",
+        (Language::IT, QuotaPeriod::Monthly) => "al mese",
+        (Language::FR, QuotaPeriod::Daily) => "par jour",
+        (Language::FR, QuotaPeriod::Weekly) => "par semaine",
+        (Language::FR, QuotaPeriod::Monthly) => "par mois",
+        (Language::ES, QuotaPeriod::Daily) => "por día",
+        (Language::ES, QuotaPeriod::Weekly) => "por semana",
+        (Language::ES, QuotaPeriod::Monthly) => "por mes",
+        (Language::DE, QuotaPeriod::Daily) => "pro Tag",
+        (Language::DE, QuotaPeriod::Weekly) => "pro Woche",
+        (Language::DE, QuotaPeriod::Monthly) => "pro Monat",
+        (_, QuotaPeriod::Daily) => "per day",
+        (_, QuotaPeriod::Weekly) => "per week",
+        (_, QuotaPeriod::Monthly) => "per month",
+    }
+}
+
+pub fn throttled_to_translation(language: Language, kbps: u32) -> String {
+    match language {
+        Language::EN => format!("Throttled to {kbps} KB/s"),
+        Language::IT => format!("Limitato a {kbps} KB/s"),
+        Language::FR => format!("Limité à {kbps} KB/s"),
+        Language::ES => format!("Limitado a {kbps} KB/s"),
+        Language::DE => format!("Gedrosselt auf {kbps} KB/s"),
+        _ => format!("Throttled to {kbps} KB/s"),
+    }
+}
+
+pub fn connections_exported_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Connections exported to",
+        Language::IT => "Connessioni esportate in",
+        Language::FR => "Connexions exportées vers",
+        Language::ES => "Conexiones exportadas a",
+        Language::DE => "Verbindungen exportiert nach",
+        _ => "Connections exported to",
+    }
+}
+
+pub fn export_failed_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Export failed",
+        Language::IT => "Esportazione non riuscita",
+        Language::FR => "Échec de l'exportation",
+        Language::ES => "Error en la exportación",
+        Language::DE => "Export fehlgeschlagen",
+        _ => "Export failed",
+    }
+}
+
+pub fn throttling_failed_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Throttling failed",
+        Language::IT => "Limitazione non riuscita",
+        Language::FR => "Échec de la limitation",
+        Language::ES => "Error en la limitación",
+        Language::DE => "Drosselung fehlgeschlagen",
+        _ => "Throttling failed",
+    }
+}
+
+pub fn failed_step_translation(language: Language, step: &str) -> String {
+    match language {
+        Language::EN => format!("Couldn't {step}"),
+        Language::IT => format!("Operazione non riuscita: {step}"),
+        Language::FR => format!("Opération échouée : {step}"),
+        Language::ES => format!("Operación fallida: {step}"),
+        Language::DE => format!("Schritt fehlgeschlagen: {step}"),
+        _ => format!("Couldn't {step}"),
+    }
+}
+
+pub fn capture_failed_translation(language: Language) -> &'static str {
+    match language {
+        Language::EN => "Capture failed",
+        Language::IT => "Cattura non riuscita",
+        Language::FR => "Échec de la capture",
+        Language::ES => "Error en la captura",
+        Language::DE => "Aufzeichnung fehlgeschlagen",
+        _ => "Capture failed",
+    }
+}
@@ -1,3 +1,4 @@
+use std::fmt;
 use std::net::IpAddr;
 use std::path::PathBuf;
 
@@ -9,6 +10,7 @@
 use crate::gui::types::message::Message;
 use crate::networking::types::filters::Filters;
 use crate::networking::types::traffic_direction::TrafficDirection;
+use crate::report::types::export_format::ExportFormat;
 use crate::translations::translations::{
     active_filters_translation, none_translation, open_report_translation,
 };
@@ -143,6 +145,14 @@ pub fn get_report_path() -> PathBuf {
     }
 }
 
+/// Returns the path of the connections table exported in the given format,
+/// placed next to the text report
+pub fn get_export_path(format: ExportFormat) -> PathBuf {
+    let mut export_path = get_report_path();
+    export_path.set_file_name(format!("connections.{}", format.get_extension()));
+    export_path
+}
+
 pub fn get_open_report_tooltip(language: Language) -> String {
     let open_report_translation = open_report_translation(language).to_string();
     //open_report_translation.push_str(&format!(" [{}+O]", get_command_key()));
@@ -202,7 +212,8 @@ pub fn get_domain_from_r_dns(r_dns: String) -> String {
     }
 }
 
-pub fn get_socket_address(address: &String, port: u16) -> String {
+/// Returns the address followed by the port; for ICMP, the port is replaced by the message label
+pub fn get_socket_address(address: &String, port: impl fmt::Display) -> String {
     if address.contains(':') {
         // IPv6
         format!("[{address}]:{port}")
@@ -1,3 +1,4 @@
 pub mod asn;
 pub mod formatted_strings;
 pub mod types;
+pub mod users;
@@ -0,0 +1,45 @@
+//! Module containing functions to resolve user IDs into user names.
+
+use std::collections::HashMap;
+
+/// Reads the system passwd database, returning the name of each user ID
+pub fn get_usernames() -> HashMap<u32, String> {
+    std::fs::read_to_string("/etc/passwd")
+        .map(|content| parse_passwd(&content))
+        .unwrap_or_default()
+}
+
+/// Parses the content of a passwd file, whose rows are formatted as
+/// `name:password:uid:gid:gecos:home:shell`
+fn parse_passwd(content: &str) -> HashMap<u32, String> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse::<u32>().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::users::parse_passwd;
+
+    #[test]
+    fn test_parse_passwd() {
+        let usernames = parse_passwd(
+            "# comment\n\
+            root:x:0:0:root:/root:/bin/bash\n\
+            alice:x:1000:1000:Alice,,,:/home/alice:/bin/zsh\n\
+            malformed line\n\
+            nobody:x:65534:65534:nobody:/nonexistent:/usr/sbin/nologin\n",
+        );
+        assert_eq!(usernames.len(), 3);
+        assert_eq!(usernames.get(&0), Some(&"root".to_string()));
+        assert_eq!(usernames.get(&1000), Some(&"alice".to_string()));
+        assert_eq!(usernames.get(&65534), Some(&"nobody".to_string()));
+    }
+}